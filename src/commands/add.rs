@@ -1,5 +1,7 @@
-use crate::common::notes::{resolve_dir, write_note};
+use crate::common::crypto::read_passphrase;
+use crate::common::hooks::{run_hook, Hook};
 use crate::config::Config;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
@@ -11,20 +13,36 @@ pub struct AddCommand {
     /// The name of the note
     pub name: String,
     /// The content to be added to the end of the note
+    #[clap(allow_hyphen_values = true)]
     pub message: String,
     /// The note directory. defaults to "notes"
     #[clap(long)]
     pub dir: Option<PathBuf>,
+    /// Add the content to the end of this section instead, e.g. "Action items" or "## Action items"
+    #[clap(long)]
+    pub under: Option<String>,
 }
 
 impl Run for AddCommand {
     fn run(&self, config: &Config) -> Result<()> {
         debug!("add command {:?}", self);
 
-        let dir = resolve_dir(&self.dir);
-        let note_parent_dir = config.notes_dir.join(&dir);
+        let notebook = Notebook::new(config.clone())?;
+        let note_identifier = Notebook::identifier(&self.name, &self.dir);
+        let passphrase = if notebook.needs_passphrase(&note_identifier) {
+            Some(read_passphrase()?)
+        } else {
+            None
+        };
+
+        notebook.append(
+            &note_identifier,
+            &self.message,
+            self.under.as_deref(),
+            passphrase.as_deref(),
+        )?;
 
-        write_note(&note_parent_dir, &self.name, &self.message)?;
+        run_hook(config, Hook::PostAdd, "add", Some(&note_identifier))?;
 
         Ok(())
     }
@@ -0,0 +1,243 @@
+use crate::common::agenda::{find_agenda_items, AgendaItem, AgendaKind};
+use crate::config::Config;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use chrono::prelude::{Local, Utc};
+use chrono::{Duration, NaiveDate};
+use clap::Parser;
+use log::debug;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Lines longer than this many octets are folded, as required by RFC 5545.
+const ICS_LINE_LENGTH: usize = 75;
+
+#[derive(Debug, Parser)]
+pub struct AgendaCommand {
+    /// The number of upcoming days to include, starting from today
+    #[clap(long, default_value = "7")]
+    pub days: u32,
+    /// Include only notes under this directory
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+    /// Include only notes tagged with this tag (or its nested tags)
+    #[clap(long)]
+    pub tag: Option<String>,
+    /// Export the items to an iCalendar file instead of listing them
+    #[clap(long)]
+    pub ics: Option<PathBuf>,
+}
+
+struct Entry {
+    note_identifier: String,
+    item: AgendaItem,
+}
+
+impl Entry {
+    fn id(&self) -> String {
+        format!("{}:{}", self.note_identifier, self.item.line)
+    }
+}
+
+fn escape_ics(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a content line, continuing it on the next lines with a leading space.
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_length = 0;
+
+    for c in line.chars() {
+        if line_length + c.len_utf8() > ICS_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            line_length = 1;
+        }
+
+        folded.push(c);
+        line_length += c.len_utf8();
+    }
+
+    folded.push_str("\r\n");
+
+    folded
+}
+
+impl AgendaCommand {
+    /// Collects the overdue items and the items of the next `days` days, sorted by date and time.
+    fn collect(&self, config: &Config, today: NaiveDate) -> Result<Vec<Entry>> {
+        let notebook = Notebook::new(config.clone())?;
+        let note_identifiers = notebook.select(self.dir.as_deref(), self.tag.as_deref())?;
+        let last_day = today + Duration::days(i64::from(self.days.max(1)) - 1);
+
+        let mut entries = vec![];
+        for note_identifier in note_identifiers {
+            // Encrypted notes can't be scanned without their passphrase.
+            let content = notebook.read_plain(&note_identifier).unwrap_or_default();
+
+            for item in find_agenda_items(&content) {
+                if item.date <= last_day {
+                    entries.push(Entry {
+                        note_identifier: note_identifier.clone(),
+                        item,
+                    });
+                }
+            }
+        }
+
+        entries.sort_by(|a, b| {
+            (a.item.date, a.item.time, &a.note_identifier, a.item.line).cmp(&(
+                b.item.date,
+                b.item.time,
+                &b.note_identifier,
+                b.item.line,
+            ))
+        });
+
+        Ok(entries)
+    }
+
+    fn list(&self, entries: &[Entry], today: NaiveDate) {
+        let mut days: BTreeMap<NaiveDate, Vec<&Entry>> = BTreeMap::new();
+        for entry in entries {
+            days.entry(entry.item.date).or_default().push(entry);
+        }
+
+        if !entries.is_empty() {
+            println!("total {}", entries.len());
+        }
+
+        for (date, entries) in days {
+            let label = if date < today {
+                " (overdue)"
+            } else if date == today {
+                " (today)"
+            } else {
+                ""
+            };
+
+            println!("{}{}", date.format("%Y-%m-%d %a"), label);
+
+            for entry in entries {
+                let time = entry
+                    .item
+                    .time
+                    .map(|time| format!(" {}", time.format("%H:%M")))
+                    .unwrap_or_default();
+
+                println!(
+                    "  {}{} {} ({})",
+                    entry.item.kind,
+                    time,
+                    entry.item.text,
+                    entry.id()
+                );
+            }
+        }
+    }
+
+    fn render_ics(&self, entries: &[Entry]) -> String {
+        let timestamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut lines = vec![
+            String::from("BEGIN:VCALENDAR"),
+            String::from("VERSION:2.0"),
+            String::from("PRODID:-//gnotes//agenda//EN"),
+        ];
+
+        for entry in entries {
+            let item = &entry.item;
+
+            lines.push(String::from("BEGIN:VEVENT"));
+            lines.push(format!(
+                "UID:{}:{}@gnotes",
+                escape_ics(&entry.id()),
+                item.kind
+            ));
+            lines.push(format!("DTSTAMP:{}", timestamp));
+            match item.time {
+                // Times are written without a time zone, so calendars show them in local time.
+                Some(time) => lines.push(format!(
+                    "DTSTART:{}",
+                    item.date.and_time(time).format("%Y%m%dT%H%M%S")
+                )),
+                _ => {
+                    lines.push(format!("DTSTART;VALUE=DATE:{}", item.date.format("%Y%m%d")));
+                    lines.push(format!(
+                        "DTEND;VALUE=DATE:{}",
+                        (item.date + Duration::days(1)).format("%Y%m%d")
+                    ));
+                }
+            }
+            lines.push(format!("SUMMARY:{}", escape_ics(&item.text)));
+            lines.push(format!("DESCRIPTION:{}", escape_ics(&entry.id())));
+
+            if item.kind == AgendaKind::Remind {
+                lines.push(String::from("BEGIN:VALARM"));
+                lines.push(String::from("ACTION:DISPLAY"));
+                lines.push(String::from("TRIGGER:PT0M"));
+                lines.push(format!("DESCRIPTION:{}", escape_ics(&item.text)));
+                lines.push(String::from("END:VALARM"));
+            }
+
+            lines.push(String::from("END:VEVENT"));
+        }
+
+        lines.push(String::from("END:VCALENDAR"));
+
+        lines.iter().map(|line| fold_ics_line(line)).collect()
+    }
+}
+
+impl Run for AgendaCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("agenda command {:?}", self);
+
+        let today = Local::today().naive_local();
+        let entries = self.collect(config, today)?;
+
+        match &self.ics {
+            Some(ics_file_path) => {
+                fs::write(ics_file_path, self.render_ics(&entries))?;
+
+                println!(
+                    "exported {} items to {}",
+                    entries.len(),
+                    ics_file_path.display()
+                );
+            }
+            _ => self.list(&entries, today),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("pay rent", "pay rent")]
+    #[case("a, b; c\\d", "a\\, b\\; c\\\\d")]
+    fn escape_ics_test(#[case] value: &str, #[case] expected: &str) {
+        assert_eq!(escape_ics(value), expected);
+    }
+
+    #[test]
+    fn fold_ics_line_test() {
+        let line = format!("SUMMARY:{}", "a".repeat(100));
+        let folded = fold_ics_line(&line);
+
+        assert_eq!(
+            folded,
+            format!("SUMMARY:{}\r\n {}\r\n", "a".repeat(67), "a".repeat(33))
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use crate::config::Config;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::Parser;
+use log::debug;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct BacklinksCommand {
+    /// The name of the note
+    pub name: String,
+    /// The note directory. defaults to "notes"
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+}
+
+impl Run for BacklinksCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("backlinks command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+        let note_identifier = notebook.existing_identifier(&self.name, &self.dir)?;
+        let results = notebook.backlinks(&note_identifier)?;
+
+        println!("total {}", results.len());
+        for result in results {
+            println!("{}", result);
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,12 @@
-use crate::common::git::auth_callbacks;
+use crate::common::git::{auth_callbacks, register_merge_driver};
 use crate::config::Config;
+use crate::error::GnotesError;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
 use git2::{build::RepoBuilder, FetchOptions, Repository};
 use log::debug;
 use std::path::Path;
-use std::process;
 
 #[derive(Debug, Parser)]
 pub struct CloneCommand {}
@@ -29,13 +29,12 @@ impl Run for CloneCommand {
 
         match &config.repository {
             Some(repository) => {
-                self.clone(&repository, &config.ssh_file_path, &config.notes_dir)?;
-            }
-            _ => {
-                eprintln!("Can't clone without a repository. Please specify a repository in the config file.");
+                let repository =
+                    self.clone(repository, &config.ssh_file_path, &config.notes_dir)?;
 
-                process::exit(1);
+                register_merge_driver(&repository)?;
             }
+            _ => return Err(GnotesError::NoRepository.into()),
         }
 
         Ok(())
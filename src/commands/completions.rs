@@ -0,0 +1,334 @@
+use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
+use anyhow::Result;
+use clap::{Arg, ArgEnum, Command, Parser};
+use clap_complete::{generate, Shell};
+use log::debug;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::Path;
+
+const BIN_NAME: &str = "gnotes";
+
+/// Completes names, directories and tags with `gnotes __complete`, and falls back to the
+/// generated completions when it exits with a failure.
+const BASH_DYNAMIC_COMPLETIONS: &str = r#"
+_gnotes_dynamic() {
+    local candidates
+    if candidates=$(gnotes __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null); then
+        COMPREPLY=()
+        if [[ -n "${candidates}" ]]; then
+            mapfile -t COMPREPLY <<< "${candidates}"
+        fi
+        return 0
+    fi
+    _gnotes "$@"
+}
+
+complete -F _gnotes_dynamic -o bashdefault -o default gnotes
+"#;
+
+const ZSH_DYNAMIC_COMPLETIONS: &str = r#"
+_gnotes() {
+    local output
+    if output=$(gnotes __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null); then
+        local -a candidates
+        candidates=(${(f)output})
+        compadd -a candidates
+        return
+    fi
+    _gnotes_static "$@"
+}
+
+"#;
+
+const FISH_DYNAMIC_COMPLETIONS: &str = r#"
+function __gnotes_complete
+    gnotes __complete -- (commandline -opc)[2..-1] (commandline -ct) 2>/dev/null
+end
+
+complete -c gnotes -n "__gnotes_complete >/dev/null" -f -a "(__gnotes_complete)"
+"#;
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Debug, Parser)]
+pub struct CompletionsCommand {
+    /// The shell to generate the completions for
+    #[clap(arg_enum)]
+    pub shell: CompletionShell,
+}
+
+impl CompletionsCommand {
+    /// Prints the completions script for the command line interface `cli`.
+    pub fn generate(&self, cli: &Command) -> Result<()> {
+        debug!("completions command {:?}", self);
+
+        let shell = match self.shell {
+            CompletionShell::Bash => Shell::Bash,
+            CompletionShell::Zsh => Shell::Zsh,
+            CompletionShell::Fish => Shell::Fish,
+        };
+        // Hidden commands are left out. `__complete` would also break the bash completions,
+        // which join the names of nested commands with `__`. The help flag is added again by clap.
+        let mut cli = Command::new(BIN_NAME)
+            .args(
+                cli.get_arguments()
+                    .filter(|arg| arg.get_id() != "help")
+                    .cloned(),
+            )
+            .subcommands(cli.get_subcommands().filter(|c| !c.is_hide_set()).cloned());
+        let mut script = vec![];
+
+        generate(shell, &mut cli, BIN_NAME, &mut script);
+
+        let script = String::from_utf8(script)?;
+        let script = match self.shell {
+            CompletionShell::Bash => script + BASH_DYNAMIC_COMPLETIONS,
+            CompletionShell::Zsh => {
+                // The generated function is renamed, so `_gnotes` tries the dynamic completions first.
+                let script = script.replacen("\n_gnotes() {\n", "\n_gnotes_static() {\n", 1);
+                let (functions, call) =
+                    script.split_at(script.rfind("_gnotes \"$@\"").unwrap_or(script.len()));
+
+                format!("{}{}{}", functions, ZSH_DYNAMIC_COMPLETIONS, call)
+            }
+            CompletionShell::Fish => script + FISH_DYNAMIC_COMPLETIONS,
+        };
+
+        print!("{}", script);
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct CompleteCommand {
+    /// The words after `gnotes`, ending with the word being completed
+    #[clap(last = true)]
+    pub words: Vec<String>,
+}
+
+/// The values a word can be completed with.
+#[derive(Debug, PartialEq)]
+enum Candidates {
+    /// Note names, only those under the directory when given
+    Names(Option<String>),
+    Dirs,
+    Tags,
+}
+
+fn candidates_for(arg: &Arg, dir: Option<String>) -> Option<Candidates> {
+    match arg.get_id() {
+        "name" => Some(Candidates::Names(dir)),
+        "dir" | "to-dir" => Some(Candidates::Dirs),
+        "tag" | "tags" => Some(Candidates::Tags),
+        _ => None,
+    }
+}
+
+/// Finds what the last word stands for, by walking the arguments of `cli` over the previous words.
+/// Returns `None` when it isn't a note name, directory or tag.
+fn find_candidates(cli: &Command, words: &[String]) -> Option<Candidates> {
+    let (current, previous) = words.split_last()?;
+    let mut command = cli;
+    let mut positionals = 0;
+    let mut dir = None;
+    let mut option: Option<&Arg> = None;
+
+    for word in previous {
+        if let Some(arg) = option.take() {
+            if arg.get_id() == "dir" {
+                dir = Some(word.clone());
+            }
+
+            continue;
+        }
+
+        if let Some(long) = word.strip_prefix("--") {
+            let (long, value) = match long.split_once('=') {
+                Some((long, value)) => (long, Some(value)),
+                _ => (long, None),
+            };
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_long() == Some(long));
+
+            match (arg, value) {
+                (Some(arg), Some(value)) if arg.get_id() == "dir" => {
+                    dir = Some(String::from(value))
+                }
+                (Some(arg), None) if arg.is_takes_value_set() => option = Some(arg),
+                _ => {}
+            }
+        } else if let Some(short) = word.strip_prefix('-').filter(|short| !short.is_empty()) {
+            option = command
+                .get_arguments()
+                .find(|arg| arg.get_short() == short.chars().next())
+                .filter(|arg| short.len() == 1 && arg.is_takes_value_set());
+        } else if let Some(subcommand) = command.find_subcommand(word).filter(|_| positionals == 0)
+        {
+            command = subcommand;
+        } else {
+            positionals += 1;
+        }
+    }
+
+    if let Some(arg) = option {
+        return candidates_for(arg, dir);
+    }
+
+    if current.starts_with('-') {
+        return None;
+    }
+
+    let positional_args: Vec<&Arg> = command.get_positionals().collect();
+    let arg = match positional_args.get(positionals) {
+        Some(arg) => arg,
+        // The remaining words go to the last argument, when it takes several values.
+        _ => positional_args
+            .last()
+            .filter(|arg| arg.is_multiple_values_set() || arg.is_multiple_occurrences_set())?,
+    };
+
+    candidates_for(arg, dir)
+}
+
+fn list_candidates(config: &Config, candidates: &Candidates) -> Result<BTreeSet<String>> {
+    let notebook = Notebook::new(config.clone())?;
+    let values = match candidates {
+        Candidates::Names(dir) => {
+            let note_identifiers = notebook.identifiers(None)?;
+
+            match dir {
+                Some(dir) => {
+                    let note_identifier_prefix =
+                        String::from(Path::new(dir).join("").to_string_lossy());
+
+                    note_identifiers
+                        .iter()
+                        .filter_map(|note_identifier| {
+                            note_identifier.strip_prefix(&note_identifier_prefix)
+                        })
+                        .map(String::from)
+                        .collect()
+                }
+                // Names are resolved across all directories, so the file names are enough.
+                _ => note_identifiers
+                    .iter()
+                    .filter_map(|note_identifier| {
+                        Path::new(note_identifier)
+                            .file_name()
+                            .map(|name| String::from(name.to_string_lossy()))
+                    })
+                    .collect(),
+            }
+        }
+        Candidates::Dirs => notebook
+            .identifiers(None)?
+            .iter()
+            .filter_map(|note_identifier| Path::new(note_identifier).parent())
+            .map(|dir| String::from(dir.to_string_lossy()))
+            .collect(),
+        Candidates::Tags => notebook.tags()?.into_keys().collect(),
+    };
+
+    Ok(values)
+}
+
+impl CompleteCommand {
+    /// Prints the note names, directories or tags that complete the last word, and fails with
+    /// `InvalidArgs` when the word isn't one of those, so the shell falls back to its own completions.
+    pub fn complete(&self, cli: &Command, config: &Config) -> Result<()> {
+        debug!("complete command {:?}", self);
+
+        let current = self.words.last().map(String::as_str).unwrap_or_default();
+        let candidates = find_candidates(cli, &self.words)
+            .ok_or_else(|| GnotesError::InvalidArgs(format!("no completions for '{}'", current)))?;
+
+        let mut stdout = io::stdout().lock();
+        for value in list_candidates(config, &candidates)? {
+            if value.starts_with(current) {
+                writeln!(stdout, "{}", value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[derive(Debug, Parser)]
+    struct TestCli {
+        #[clap(long)]
+        debug: bool,
+        #[clap(subcommand)]
+        command: TestCommand,
+    }
+
+    #[derive(Debug, clap::Subcommand)]
+    enum TestCommand {
+        Show {
+            name: String,
+            #[clap(long)]
+            dir: Option<String>,
+        },
+        Tag {
+            name: String,
+            tags: Vec<String>,
+            #[clap(long)]
+            dir: Option<String>,
+        },
+        Mv {
+            name: String,
+            new_name: String,
+            #[clap(long)]
+            to_dir: Option<String>,
+        },
+        List {
+            #[clap(long)]
+            tag: Option<String>,
+            #[clap(long)]
+            all: bool,
+        },
+    }
+
+    #[rstest]
+    #[case("", None)]
+    #[case("sh", None)]
+    #[case("show ", Some(Candidates::Names(None)))]
+    #[case("--debug show cho", Some(Candidates::Names(None)))]
+    #[case(
+        "show --dir work ",
+        Some(Candidates::Names(Some(String::from("work"))))
+    )]
+    #[case(
+        "show --dir=work ",
+        Some(Candidates::Names(Some(String::from("work"))))
+    )]
+    #[case("show --dir ", Some(Candidates::Dirs))]
+    #[case("show --", None)]
+    #[case("show chores ", None)]
+    #[case("tag chores ", Some(Candidates::Tags))]
+    #[case("tag chores home ", Some(Candidates::Tags))]
+    #[case("list --all --tag ", Some(Candidates::Tags))]
+    #[case("list ", None)]
+    #[case("mv chores ", None)]
+    #[case("mv chores tasks --to-dir ", Some(Candidates::Dirs))]
+    fn find_candidates_test(#[case] line: &str, #[case] expected: Option<Candidates>) {
+        let cli = <TestCli as clap::CommandFactory>::command();
+        let words: Vec<String> = line.split(' ').map(String::from).collect();
+
+        assert_eq!(find_candidates(&cli, &words), expected);
+    }
+}
@@ -1,5 +1,7 @@
-use crate::common::notes::resolve_dir;
+use crate::common::crypto::read_passphrase;
+use crate::common::resolve::resolve_note;
 use crate::config::Config;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
@@ -20,15 +22,45 @@ impl Run for EditCommand {
     fn run(&self, config: &Config) -> Result<()> {
         debug!("edit command {:?}", self);
 
-        let dir = resolve_dir(&self.dir);
-        let note_parent_dir = config.notes_dir.join(&dir);
-        let note_file_path = note_parent_dir.join(&self.name);
+        let notebook = Notebook::new(config.clone())?;
+        // A new note is created when no existing note matches the name.
+        let note_identifier = resolve_note(&config.notes_dir, &self.name, &self.dir)?
+            .unwrap_or_else(|| Notebook::identifier(&self.name, &self.dir));
+        let note_file_path = notebook.path(&note_identifier);
 
-        fs::create_dir_all(note_parent_dir)?;
+        if notebook.needs_passphrase(&note_identifier) {
+            let passphrase = read_passphrase()?;
+            let content = if notebook.exists(&note_identifier) {
+                notebook.read(&note_identifier, Some(&passphrase))?.content
+            } else {
+                String::new()
+            };
+
+            debug!("Opening editor for encrypted file {:?}", note_file_path);
+
+            let content = edit::edit(content)?;
+
+            if notebook.exists(&note_identifier) {
+                notebook.update(&note_identifier, &content, Some(&passphrase))?;
+            } else {
+                notebook.create(&note_identifier, &content, Some(&passphrase))?;
+            }
+
+            return Ok(());
+        }
+
+        if let Some(note_parent_dir) = note_file_path.parent() {
+            fs::create_dir_all(note_parent_dir)?;
+        }
 
         debug!("Opening editor for file {:?}", note_file_path);
 
-        edit::edit_file(note_file_path)?;
+        edit::edit_file(&note_file_path)?;
+
+        // The editor may exit without saving the note.
+        if let Some(content) = notebook.read_plain(&note_identifier) {
+            notebook.refresh_inline_tags(&note_identifier, &content)?;
+        }
 
         Ok(())
     }
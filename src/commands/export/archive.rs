@@ -0,0 +1,73 @@
+use crate::common::archive::{
+    write_archive, ArchiveFormat, Manifest, ManifestNote, MANIFEST_FILE_NAME, NOTES_DIR_NAME,
+};
+use crate::config::Config;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::Parser;
+use log::debug;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct ExportArchiveCommand {
+    /// The archive file to write
+    pub file: PathBuf,
+    /// Export only notes under this directory
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+    /// Export only notes tagged with this tag (or its nested tags)
+    #[clap(long)]
+    pub tag: Option<String>,
+    /// The archive format. defaults to the file extension, or tar
+    #[clap(long, arg_enum)]
+    pub format: Option<ArchiveFormat>,
+}
+
+impl Run for ExportArchiveCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("export archive command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+        let tags = notebook.tags()?;
+        let note_identifiers = notebook.select(self.dir.as_deref(), self.tag.as_deref())?;
+
+        let mut files = BTreeMap::new();
+        let mut manifest_notes = vec![];
+
+        for note_identifier in note_identifiers {
+            let mut note_tags: Vec<String> = tags
+                .iter()
+                .filter(|(_, tags_set)| tags_set.contains(&note_identifier))
+                .map(|(tag, _)| tag.clone())
+                .collect();
+            note_tags.sort();
+
+            // Encrypted notes are exported as they're stored, encrypted.
+            files.insert(
+                format!("{}/{}", NOTES_DIR_NAME, note_identifier),
+                notebook.note_store().read(&note_identifier)?.into_bytes(),
+            );
+            manifest_notes.push(ManifestNote {
+                identifier: note_identifier,
+                tags: note_tags,
+            });
+        }
+
+        let total = manifest_notes.len();
+        files.insert(
+            String::from(MANIFEST_FILE_NAME),
+            serde_json::to_vec_pretty(&Manifest::new(manifest_notes))?,
+        );
+
+        let format = self
+            .format
+            .unwrap_or_else(|| ArchiveFormat::detect(&self.file));
+        write_archive(&self.file, format, &files)?;
+
+        println!("exported {} notes to {}", total, self.file.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,402 @@
+use crate::common::links::map_links;
+use crate::common::tags::Tags;
+use crate::config::Config;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::Parser;
+use log::debug;
+use pulldown_cmark::{
+    escape::escape_html, html, CowStr, Event, Options, Parser as MarkdownParser, Tag,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const INDEX_FILE_NAME: &str = "index.html";
+const SEARCH_INDEX_FILE_NAME: &str = "search-index.js";
+// Note pages and tag pages are in separate directories, so neither can collide with the other or
+// with the index, whatever the notes dirs and the tags are named.
+const NOTES_DIR_NAME: &str = "notes";
+const TAGS_DIR_NAME: &str = "tags";
+
+const SEARCH_SCRIPT: &str = r#"<script>
+  const input = document.getElementById("search");
+  const results = document.getElementById("results");
+
+  input.addEventListener("input", () => {
+    const terms = input.value.toLowerCase().split(/\s+/).filter((term) => term);
+    results.innerHTML = "";
+
+    if (terms.length === 0) {
+      return;
+    }
+
+    for (const entry of window.GNOTES_SEARCH_INDEX) {
+      const text = (entry.title + " " + entry.tags.join(" ") + " " + entry.text).toLowerCase();
+
+      if (terms.every((term) => text.includes(term))) {
+        const item = document.createElement("li");
+        const link = document.createElement("a");
+        link.href = entry.url;
+        link.textContent = entry.title;
+        item.appendChild(link);
+        results.appendChild(item);
+      }
+    }
+  });
+</script>
+"#;
+
+#[derive(Debug, Parser)]
+pub struct ExportHtmlCommand {
+    /// The directory to write the site to
+    pub out_dir: PathBuf,
+    /// Export only notes under this directory
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+    /// Export only notes tagged with this tag (or its nested tags)
+    #[clap(long)]
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SearchEntry {
+    title: String,
+    url: String,
+    tags: Vec<String>,
+    text: String,
+}
+
+/// The relative path from a page to the site root, e.g. `../../` for `tags/project/alpha.html`.
+fn root_prefix(page_path: &str) -> String {
+    "../".repeat(page_path.matches('/').count())
+}
+
+fn note_page_path(note_identifier: &str) -> String {
+    format!(
+        "{}/{}.html",
+        NOTES_DIR_NAME,
+        note_identifier.replace('\\', "/")
+    )
+}
+
+fn tag_page_path(tag: &str) -> String {
+    // Tags are free text, so they must not escape the tags directory.
+    let tag: Vec<&str> = tag
+        .split('/')
+        .map(|segment| match segment {
+            "" | "." | ".." => "_",
+            _ => segment,
+        })
+        .collect();
+
+    format!("{}/{}.html", TAGS_DIR_NAME, tag.join("/"))
+}
+
+fn escape(value: &str) -> String {
+    let mut escaped = String::new();
+
+    // Writing to a String can't fail.
+    let _ = escape_html(&mut escaped, value);
+
+    escaped
+}
+
+/// Escapes the characters which Markdown would interpret, so the text is rendered as is.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c.is_ascii_punctuation() {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Percent-encodes the URL of a link, so it can't end the Markdown link it's written to.
+fn encode_url(url: &str) -> String {
+    url.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                String::from(byte as char)
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Whether the link target is relative, or an http(s) or mailto URL. Other schemes, such as
+/// `javascript:` or `data:`, could run scripts in the site.
+fn is_safe_url(url: &str) -> bool {
+    // Browsers ignore whitespace and control characters in the scheme.
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .collect();
+    let scheme_end = url.find([':', '/', '?', '#']);
+
+    match scheme_end {
+        Some(index) if url[index..].starts_with(':') => {
+            ["http", "https", "mailto"].contains(&url[..index].to_lowercase().as_str())
+        }
+        _ => true,
+    }
+}
+
+fn safe_url(url: CowStr) -> CowStr {
+    if is_safe_url(&url) {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
+
+fn render_markdown(content: &str) -> String {
+    let mut output = String::new();
+    // Raw HTML is shown as text, and unsafe link targets are dropped, so the notes can't add
+    // scripts to the site.
+    let events = MarkdownParser::new_ext(content, Options::all()).map(|event| match event {
+        Event::Html(html) => Event::Text(html),
+        Event::Start(Tag::Link(link_type, url, title)) => {
+            Event::Start(Tag::Link(link_type, safe_url(url), title))
+        }
+        Event::Start(Tag::Image(link_type, url, title)) => {
+            Event::Start(Tag::Image(link_type, safe_url(url), title))
+        }
+        event => event,
+    });
+
+    html::push_html(&mut output, events);
+
+    output
+}
+
+fn render_page(title: &str, page_path: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>{title}</title>
+</head>
+<body>
+  <nav><a href="{root}{index}">Index</a></nav>
+  <h1>{title}</h1>
+{body}</body>
+</html>
+"#,
+        title = escape(title),
+        root = root_prefix(page_path),
+        index = INDEX_FILE_NAME,
+        body = body,
+    )
+}
+
+fn render_list<'a, I>(page_path: &str, items: I) -> String
+where
+    I: IntoIterator<Item = (&'a str, String)>,
+{
+    let mut output = String::from("<ul>\n");
+
+    for (label, target_page_path) in items {
+        output.push_str(&format!(
+            "  <li><a href=\"{}{}\">{}</a></li>\n",
+            root_prefix(page_path),
+            escape(&target_page_path),
+            escape(label)
+        ));
+    }
+
+    output.push_str("</ul>\n");
+
+    output
+}
+
+fn write_page(out_dir: &Path, page_path: &str, content: &str) -> Result<()> {
+    let file_path = out_dir.join(page_path);
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    debug!("Writing page {:?}", file_path);
+
+    fs::write(file_path, content)?;
+
+    Ok(())
+}
+
+impl ExportHtmlCommand {
+    fn note_tags(&self, tags: &Tags, note_identifier: &str) -> Vec<String> {
+        let mut note_tags: Vec<String> = tags
+            .iter()
+            .filter(|(_, tags_set)| tags_set.contains(note_identifier))
+            .map(|(tag, _)| tag.clone())
+            .collect();
+        note_tags.sort();
+
+        note_tags
+    }
+
+    fn render_note(
+        &self,
+        note_identifier: &str,
+        content: &str,
+        note_tags: &[String],
+        exported: &BTreeMap<String, String>,
+    ) -> String {
+        let page_path = note_page_path(note_identifier);
+
+        // Links to notes that aren't exported are left as plain text.
+        let content = map_links(content, |linked_note| match exported.get(linked_note) {
+            Some(_) => Some(format!(
+                "[{}]({})",
+                escape_markdown(linked_note),
+                encode_url(&format!(
+                    "{}{}",
+                    root_prefix(&page_path),
+                    note_page_path(linked_note)
+                ))
+            )),
+            _ => Some(escape_markdown(linked_note)),
+        });
+
+        let mut body = render_markdown(&content);
+
+        if !note_tags.is_empty() {
+            body.push_str("<h2>Tags</h2>\n");
+            body.push_str(&render_list(
+                &page_path,
+                note_tags
+                    .iter()
+                    .map(|tag| (tag.as_str(), tag_page_path(tag))),
+            ));
+        }
+
+        render_page(note_identifier, &page_path, &body)
+    }
+
+    fn render_index(
+        &self,
+        exported: &BTreeMap<String, String>,
+        tag_pages: &BTreeMap<String, BTreeSet<String>>,
+    ) -> String {
+        let mut body = format!(
+            "<script src=\"{}\"></script>\n<input id=\"search\" type=\"search\" placeholder=\"Search\">\n<ul id=\"results\"></ul>\n",
+            SEARCH_INDEX_FILE_NAME
+        );
+
+        body.push_str("<h2>Notes</h2>\n");
+        body.push_str(&render_list(
+            INDEX_FILE_NAME,
+            exported
+                .keys()
+                .map(|note_identifier| (note_identifier.as_str(), note_page_path(note_identifier))),
+        ));
+
+        if !tag_pages.is_empty() {
+            body.push_str("<h2>Tags</h2>\n");
+            body.push_str(&render_list(
+                INDEX_FILE_NAME,
+                tag_pages
+                    .keys()
+                    .map(|tag| (tag.as_str(), tag_page_path(tag))),
+            ));
+        }
+
+        body.push_str(SEARCH_SCRIPT);
+
+        render_page("gnotes", INDEX_FILE_NAME, &body)
+    }
+}
+
+impl Run for ExportHtmlCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("export html command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+        let tags = notebook.tags()?;
+        let note_identifiers = notebook.select(self.dir.as_deref(), self.tag.as_deref())?;
+
+        // Encrypted notes can't be rendered without their passphrase, so they aren't exported.
+        let exported: BTreeMap<String, String> = note_identifiers
+            .into_iter()
+            .filter_map(|note_identifier| {
+                notebook
+                    .read_plain(&note_identifier)
+                    .map(|content| (note_identifier, content))
+            })
+            .collect();
+
+        let mut tag_pages: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        let mut search_index = vec![];
+
+        for (note_identifier, content) in &exported {
+            let note_tags = self.note_tags(&tags, note_identifier);
+
+            write_page(
+                &self.out_dir,
+                &note_page_path(note_identifier),
+                &self.render_note(note_identifier, content, &note_tags, &exported),
+            )?;
+
+            for tag in &note_tags {
+                tag_pages
+                    .entry(tag.clone())
+                    .or_default()
+                    .insert(note_identifier.clone());
+            }
+
+            search_index.push(SearchEntry {
+                title: note_identifier.clone(),
+                url: note_page_path(note_identifier),
+                tags: note_tags,
+                text: content.clone(),
+            });
+        }
+
+        for (tag, note_identifiers) in &tag_pages {
+            let page_path = tag_page_path(tag);
+            let body = render_list(
+                &page_path,
+                note_identifiers.iter().map(|note_identifier| {
+                    (note_identifier.as_str(), note_page_path(note_identifier))
+                }),
+            );
+
+            write_page(
+                &self.out_dir,
+                &page_path,
+                &render_page(&format!("#{}", tag), &page_path, &body),
+            )?;
+        }
+
+        write_page(
+            &self.out_dir,
+            SEARCH_INDEX_FILE_NAME,
+            &format!(
+                "window.GNOTES_SEARCH_INDEX = {};\n",
+                serde_json::to_string(&search_index)?
+            ),
+        )?;
+        write_page(
+            &self.out_dir,
+            INDEX_FILE_NAME,
+            &self.render_index(&exported, &tag_pages),
+        )?;
+
+        println!(
+            "exported {} notes to {}",
+            exported.len(),
+            self.out_dir.display()
+        );
+
+        Ok(())
+    }
+}
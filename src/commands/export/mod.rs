@@ -0,0 +1,36 @@
+mod archive;
+mod html;
+
+use crate::config::Config;
+use crate::run::Run;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use log::debug;
+
+pub use archive::ExportArchiveCommand;
+pub use html::ExportHtmlCommand;
+
+#[derive(Debug, Parser)]
+pub struct ExportCommand {
+    #[clap(subcommand)]
+    pub command: ExportSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportSubcommand {
+    /// Exports the notes as a static HTML site
+    Html(ExportHtmlCommand),
+    /// Exports the notes and their tags to a tar or zip archive
+    Archive(ExportArchiveCommand),
+}
+
+impl Run for ExportCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("export command {:?}", self);
+
+        match &self.command {
+            ExportSubcommand::Html(html_command) => html_command.run(config),
+            ExportSubcommand::Archive(archive_command) => archive_command.run(config),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::common::links::find_linked_notes;
+use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::{ArgEnum, Parser};
+use log::debug;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+#[derive(ArgEnum, Clone, Debug)]
+pub enum GraphFormat {
+    Dot,
+    Json,
+}
+
+#[derive(Debug, Parser)]
+pub struct GraphCommand {
+    /// Include only notes under this directory
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+    /// Include only notes tagged with this tag (or its nested tags)
+    #[clap(long)]
+    pub tag: Option<String>,
+    /// The output format
+    #[clap(long, arg_enum, default_value = "dot")]
+    pub format: GraphFormat,
+    /// Start from this note, in the form of "dir/name"
+    #[clap(long)]
+    pub root: Option<String>,
+    /// The maximum distance from the root note
+    #[clap(long, requires = "root")]
+    pub depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    Note,
+    Tag,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EdgeKind {
+    Tag,
+    Link,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Node {
+    pub id: String,
+    pub kind: NodeKind,
+    pub label: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub missing: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Graph {
+    pub nodes: Vec<Node>,
+    pub edges: Vec<Edge>,
+}
+
+fn note_node_id(note_identifier: &str) -> String {
+    format!("note:{}", note_identifier)
+}
+
+fn tag_node_id(tag: &str) -> String {
+    format!("tag:{}", tag)
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl GraphCommand {
+    fn build_graph(&self, notebook: &Notebook) -> Result<Graph> {
+        let tags = notebook.tags()?;
+        let note_identifiers: BTreeSet<String> = notebook
+            .select(self.dir.as_deref(), self.tag.as_deref())?
+            .into_iter()
+            .collect();
+
+        let mut nodes: BTreeMap<String, Node> = BTreeMap::new();
+        let mut edges: BTreeSet<Edge> = BTreeSet::new();
+
+        for note_identifier in &note_identifiers {
+            nodes.insert(
+                note_node_id(note_identifier),
+                Node {
+                    id: note_node_id(note_identifier),
+                    kind: NodeKind::Note,
+                    label: note_identifier.clone(),
+                    missing: false,
+                },
+            );
+
+            // Encrypted notes can't be scanned without their passphrase.
+            let content = notebook.read_plain(note_identifier).unwrap_or_default();
+
+            for linked_note in find_linked_notes(&content) {
+                if !note_identifiers.contains(&linked_note) {
+                    let missing = !notebook.exists(&linked_note);
+
+                    // Existing notes which were filtered out are left out of the graph.
+                    if !missing {
+                        continue;
+                    }
+
+                    nodes.insert(
+                        note_node_id(&linked_note),
+                        Node {
+                            id: note_node_id(&linked_note),
+                            kind: NodeKind::Note,
+                            label: linked_note.clone(),
+                            missing,
+                        },
+                    );
+                }
+
+                edges.insert(Edge {
+                    from: note_node_id(note_identifier),
+                    to: note_node_id(&linked_note),
+                    kind: EdgeKind::Link,
+                });
+            }
+        }
+
+        for (tag, tags_set) in &tags {
+            for note_identifier in tags_set {
+                if note_identifiers.contains(note_identifier) {
+                    nodes.entry(tag_node_id(tag)).or_insert_with(|| Node {
+                        id: tag_node_id(tag),
+                        kind: NodeKind::Tag,
+                        label: tag.clone(),
+                        missing: false,
+                    });
+
+                    edges.insert(Edge {
+                        from: tag_node_id(tag),
+                        to: note_node_id(note_identifier),
+                        kind: EdgeKind::Tag,
+                    });
+                }
+            }
+        }
+
+        Ok(Graph {
+            nodes: nodes.into_values().collect(),
+            edges: edges.into_iter().collect(),
+        })
+    }
+
+    /// Keeps only the nodes within `depth` edges of the root, regardless of the edges direction.
+    fn limit_depth(&self, graph: Graph, root: &str, depth: Option<usize>) -> Graph {
+        let mut distances: BTreeMap<&str, usize> = BTreeMap::new();
+        let mut queue = VecDeque::new();
+
+        distances.insert(root, 0);
+        queue.push_back(root);
+
+        while let Some(node_id) = queue.pop_front() {
+            let distance = distances[node_id];
+
+            if depth.map(|depth| distance >= depth).unwrap_or_default() {
+                continue;
+            }
+
+            for edge in &graph.edges {
+                let neighbour = if edge.from == node_id {
+                    edge.to.as_str()
+                } else if edge.to == node_id {
+                    edge.from.as_str()
+                } else {
+                    continue;
+                };
+
+                if !distances.contains_key(neighbour) {
+                    distances.insert(neighbour, distance + 1);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        let reachable: BTreeSet<String> = distances.keys().map(|id| String::from(*id)).collect();
+
+        Graph {
+            nodes: graph
+                .nodes
+                .into_iter()
+                .filter(|node| reachable.contains(&node.id))
+                .collect(),
+            edges: graph
+                .edges
+                .into_iter()
+                .filter(|edge| reachable.contains(&edge.from) && reachable.contains(&edge.to))
+                .collect(),
+        }
+    }
+
+    fn render_dot(&self, graph: &Graph) -> String {
+        let mut output = String::from("digraph gnotes {\n");
+
+        for node in &graph.nodes {
+            let shape = match node.kind {
+                NodeKind::Note => "box",
+                NodeKind::Tag => "ellipse",
+            };
+            let style = if node.missing { ", style=dashed" } else { "" };
+
+            output.push_str(&format!(
+                "  \"{}\" [label=\"{}\", shape={}{}];\n",
+                escape_dot(&node.id),
+                escape_dot(&node.label),
+                shape,
+                style
+            ));
+        }
+
+        for edge in &graph.edges {
+            let style = match edge.kind {
+                EdgeKind::Tag => " [style=dotted]",
+                EdgeKind::Link => "",
+            };
+
+            output.push_str(&format!(
+                "  \"{}\" -> \"{}\"{};\n",
+                escape_dot(&edge.from),
+                escape_dot(&edge.to),
+                style
+            ));
+        }
+
+        output.push_str("}\n");
+
+        output
+    }
+}
+
+impl Run for GraphCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("graph command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+        let mut graph = self.build_graph(&notebook)?;
+
+        if let Some(root) = &self.root {
+            let root_path = Path::new(root);
+            let (dir, name) = match (root_path.parent(), root_path.file_name()) {
+                (Some(dir), Some(name)) if !dir.as_os_str().is_empty() => {
+                    (dir.to_path_buf(), name.to_string_lossy())
+                }
+                _ => {
+                    return Err(GnotesError::InvalidArgs(String::from(
+                        "root must be in the form of \"dir/name\"",
+                    ))
+                    .into())
+                }
+            };
+            let note_identifier = notebook.existing_identifier(&name, &Some(dir))?;
+
+            graph = self.limit_depth(graph, &note_node_id(&note_identifier), self.depth);
+        }
+
+        match self.format {
+            GraphFormat::Dot => print!("{}", self.render_dot(&graph)),
+            GraphFormat::Json => println!("{}", serde_json::to_string_pretty(&graph)?),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use crate::common::archive::{
+    is_safe_identifier, read_archive, read_manifest, ArchiveFormat, NOTES_DIR_NAME,
+};
+use crate::common::tags::Tags;
+use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::{ArgEnum, Parser};
+use log::debug;
+use std::path::PathBuf;
+use std::str;
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum ConflictStrategy {
+    /// Keep the existing note
+    Skip,
+    /// Replace the existing note
+    Overwrite,
+    /// Import the note under a new name
+    Rename,
+}
+
+#[derive(Debug, Parser)]
+pub struct ImportArchiveCommand {
+    /// The archive file to read
+    pub file: PathBuf,
+    /// What to do when a note already exists
+    #[clap(long, arg_enum, default_value = "skip")]
+    pub conflict: ConflictStrategy,
+    /// The archive format. defaults to the file extension, or tar
+    #[clap(long, arg_enum)]
+    pub format: Option<ArchiveFormat>,
+}
+
+/// Finds a free identifier by adding a numeric suffix, e.g. `notes/chores-1`.
+fn free_identifier(notebook: &Notebook, note_identifier: &str) -> String {
+    (1..)
+        .map(|index| format!("{}-{}", note_identifier, index))
+        .find(|candidate| !notebook.exists(candidate))
+        .unwrap_or_default()
+}
+
+impl Run for ImportArchiveCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("import archive command {:?}", self);
+
+        let format = self
+            .format
+            .unwrap_or_else(|| ArchiveFormat::detect(&self.file));
+        let files = read_archive(&self.file, format)?;
+        let manifest = read_manifest(&files)?;
+
+        let mut imported = 0;
+
+        // The whole archive is checked first, so a bad note doesn't leave it half imported.
+        for note in &manifest.notes {
+            if !is_safe_identifier(&note.identifier) {
+                return Err(GnotesError::InvalidArchive(format!(
+                    "bad note identifier '{}'",
+                    note.identifier
+                ))
+                .into());
+            }
+
+            match files.get(&format!("{}/{}", NOTES_DIR_NAME, note.identifier)) {
+                Some(content) if str::from_utf8(content).is_err() => {
+                    return Err(GnotesError::InvalidArchive(format!(
+                        "note '{}' isn't text",
+                        note.identifier
+                    ))
+                    .into());
+                }
+                Some(_) => {}
+                _ => {
+                    return Err(GnotesError::InvalidArchive(format!(
+                        "note '{}' not found",
+                        note.identifier
+                    ))
+                    .into());
+                }
+            }
+        }
+
+        let notebook = Notebook::new(config.clone())?;
+
+        // The notes are written while holding the lock, so the conflicts are checked against
+        // what's there, and a concurrent gnotes doesn't see them without their tags.
+        notebook.locked(&mut || {
+            let mut tags = Tags::new();
+
+            for note in &manifest.notes {
+                // Checked above.
+                let content =
+                    str::from_utf8(&files[&format!("{}/{}", NOTES_DIR_NAME, note.identifier)])
+                        .unwrap_or_default();
+
+                let note_identifier = if notebook.exists(&note.identifier) {
+                    match self.conflict {
+                        ConflictStrategy::Skip => {
+                            println!("skipped {}", note.identifier);
+
+                            continue;
+                        }
+                        ConflictStrategy::Overwrite => {
+                            println!("overwritten {}", note.identifier);
+
+                            note.identifier.clone()
+                        }
+                        ConflictStrategy::Rename => {
+                            let renamed = free_identifier(&notebook, &note.identifier);
+
+                            println!("renamed {} to {}", note.identifier, renamed);
+
+                            renamed
+                        }
+                    }
+                } else {
+                    println!("imported {}", note.identifier);
+
+                    note.identifier.clone()
+                };
+
+                // Written as is, so encrypted notes stay encrypted.
+                notebook.note_store().write(&note_identifier, content)?;
+
+                for tag in &note.tags {
+                    tags.entry(tag.clone())
+                        .or_default()
+                        .insert(note_identifier.clone());
+                }
+
+                imported += 1;
+            }
+
+            notebook.modify_tags(&mut |all_tags| {
+                for (tag, note_identifiers) in &tags {
+                    all_tags
+                        .entry(tag.clone())
+                        .or_default()
+                        .extend(note_identifiers.iter().cloned());
+                }
+
+                Ok(())
+            })
+        })?;
+
+        println!("total {}", imported);
+
+        Ok(())
+    }
+}
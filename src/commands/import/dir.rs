@@ -0,0 +1,321 @@
+use crate::common::hashtags::find_hashtags;
+use crate::common::links::{find_links, format_link};
+use crate::common::notes::resolve_dir;
+use crate::common::tags::Tags;
+use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::Parser;
+use filetime::FileTime;
+use log::debug;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const NOTE_EXTENSIONS: [&str; 2] = ["md", "txt"];
+const FRONT_MATTER_DELIMITER: &str = "---";
+
+#[derive(Debug, Parser)]
+pub struct ImportDirCommand {
+    /// The folder to import
+    pub path: PathBuf,
+    /// The notes directory to import into. defaults to "notes"
+    #[clap(long)]
+    pub into: Option<PathBuf>,
+    /// Only report what would be imported
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FrontMatter {
+    #[serde(default)]
+    tags: Option<FrontMatterTags>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum FrontMatterTags {
+    List(Vec<String>),
+    Text(String),
+}
+
+struct ImportedNote {
+    source: PathBuf,
+    note_identifier: String,
+    content: String,
+    tags: BTreeSet<String>,
+}
+
+/// Reads the tags from a YAML front matter, either as a list or as a space or comma separated string.
+fn front_matter_tags(content: &str) -> BTreeSet<String> {
+    let front_matter = content
+        .strip_prefix(FRONT_MATTER_DELIMITER)
+        .and_then(|rest| {
+            rest.strip_prefix('\n')
+                .or_else(|| rest.strip_prefix("\r\n"))
+        })
+        .and_then(|rest| {
+            rest.find(&format!("\n{}", FRONT_MATTER_DELIMITER))
+                .map(|end| &rest[..end])
+        });
+
+    let tags = front_matter
+        .and_then(|front_matter| serde_yaml::from_str::<FrontMatter>(front_matter).ok())
+        .and_then(|front_matter| front_matter.tags);
+
+    let tags = match tags {
+        Some(FrontMatterTags::List(tags)) => tags,
+        Some(FrontMatterTags::Text(tags)) => tags
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .map(String::from)
+            .collect(),
+        _ => vec![],
+    };
+
+    tags.into_iter()
+        .map(|tag| String::from(tag.trim().trim_start_matches('#')))
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Collects the note files of the folder. Symlinked directories are followed, but each directory
+/// is only visited once, so a symlink loop doesn't make the import run forever.
+fn collect_files(root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut pending = vec![root.to_path_buf()];
+    let mut visited = HashSet::new();
+
+    while let Some(dir) = pending.pop() {
+        if !visited.insert(dir.canonicalize()?) {
+            debug!("Skipping {:?}, which was already visited", dir);
+
+            continue;
+        }
+
+        for entry in fs::read_dir(&dir)?.filter_map(|entry| entry.ok()) {
+            // Skips hidden entries, such as `.obsidian` and `.git`.
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let path = entry.path();
+
+            if path.is_dir() {
+                pending.push(path);
+            } else if path
+                .extension()
+                .map(|extension| NOTE_EXTENSIONS.iter().any(|e| extension == *e))
+                .unwrap_or_default()
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+
+    Ok(files)
+}
+
+impl ImportDirCommand {
+    fn note_identifier(&self, into: &Path, relative_path: &Path) -> String {
+        String::from(
+            into.join(relative_path.with_extension(""))
+                .to_string_lossy(),
+        )
+    }
+
+    /// Turns `[[wikilinks]]` into gnotes identifiers, resolving them by path or by file name like
+    /// Obsidian does. Links to notes outside the imported folder are left untouched.
+    fn convert_links(&self, content: &str, by_name: &HashMap<String, Vec<String>>) -> String {
+        let mut result = String::with_capacity(content.len());
+        let mut offset = 0;
+
+        for link in find_links(content) {
+            let target = link
+                .target
+                .split(['|', '#'])
+                .next()
+                .unwrap_or_default()
+                .trim();
+            let target = target
+                .strip_suffix(".md")
+                .or_else(|| target.strip_suffix(".txt"))
+                .unwrap_or(target);
+
+            let candidates = by_name.get(target).or_else(|| {
+                Path::new(target)
+                    .file_name()
+                    .and_then(|name| by_name.get(name.to_string_lossy().as_ref()))
+            });
+
+            if let Some(note_identifier) = candidates.and_then(|candidates| {
+                candidates
+                    .iter()
+                    .find(|candidate| candidate.ends_with(target))
+                    .or_else(|| candidates.first())
+            }) {
+                result.push_str(&content[offset..link.start]);
+                result.push_str(&format_link(note_identifier));
+                offset = link.end;
+            }
+        }
+
+        result.push_str(&content[offset..]);
+
+        result
+    }
+
+    fn prepare(&self, into: &Path) -> Result<Vec<ImportedNote>> {
+        let mut files = vec![];
+        let mut sources: HashMap<String, PathBuf> = HashMap::new();
+
+        // Files which only differ by their extension, e.g. `plan.md` and `plan.txt`, would be the
+        // same note. The first one is imported, and the others are reported.
+        for file in collect_files(&self.path)? {
+            let relative_path = file.strip_prefix(&self.path)?;
+            let note_identifier = self.note_identifier(into, relative_path);
+
+            match sources.get(&note_identifier) {
+                Some(source) => eprintln!(
+                    "skipped {}: same note {} as {}",
+                    file.display(),
+                    note_identifier,
+                    source.display()
+                ),
+                _ => {
+                    sources.insert(note_identifier, file.clone());
+                    files.push(file);
+                }
+            }
+        }
+
+        let mut by_name: HashMap<String, Vec<String>> = HashMap::new();
+        for file in &files {
+            let relative_path = file.strip_prefix(&self.path)?;
+            let note_identifier = self.note_identifier(into, relative_path);
+
+            if let Some(stem) = file.file_stem() {
+                by_name
+                    .entry(String::from(stem.to_string_lossy()))
+                    .or_default()
+                    .push(note_identifier.clone());
+            }
+        }
+
+        let mut notes = vec![];
+        for file in files {
+            let content = match fs::read_to_string(&file) {
+                Ok(content) => content,
+                _ => {
+                    eprintln!("skipped {}: not a text file", file.display());
+
+                    continue;
+                }
+            };
+            let relative_path = file.strip_prefix(&self.path)?;
+
+            let mut tags = front_matter_tags(&content);
+            tags.extend(find_hashtags(&content));
+
+            notes.push(ImportedNote {
+                note_identifier: self.note_identifier(into, relative_path),
+                content: self.convert_links(&content, &by_name),
+                tags,
+                source: file,
+            });
+        }
+
+        Ok(notes)
+    }
+}
+
+impl Run for ImportDirCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("import dir command {:?}", self);
+
+        if !self.path.is_dir() {
+            return Err(GnotesError::InvalidArgs(format!(
+                "directory '{}' not found",
+                self.path.display()
+            ))
+            .into());
+        }
+
+        let into = resolve_dir(&self.into);
+        let notes = self.prepare(&into)?;
+        let mut imported = 0;
+
+        // The notes are written while holding the lock, so they're checked against what's there,
+        // and a concurrent gnotes doesn't see them without their tags.
+        let notebook = Notebook::new(config.clone())?;
+        notebook.locked(&mut || {
+            let mut tags = Tags::new();
+
+            for note in &notes {
+                let tags_description = note
+                    .tags
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                if notebook.exists(&note.note_identifier) {
+                    println!("skipped {}: already exists", note.note_identifier);
+
+                    continue;
+                }
+
+                if self.dry_run {
+                    println!(
+                        "would import {} [{}]",
+                        note.note_identifier, tags_description
+                    );
+                } else {
+                    notebook
+                        .note_store()
+                        .write(&note.note_identifier, &note.content)?;
+
+                    let metadata = fs::metadata(&note.source)?;
+                    filetime::set_file_mtime(
+                        notebook.path(&note.note_identifier),
+                        FileTime::from_last_modification_time(&metadata),
+                    )?;
+
+                    for tag in &note.tags {
+                        tags.entry(tag.clone())
+                            .or_default()
+                            .insert(note.note_identifier.clone());
+                    }
+
+                    println!("imported {} [{}]", note.note_identifier, tags_description);
+                }
+
+                imported += 1;
+            }
+
+            if self.dry_run {
+                return Ok(());
+            }
+
+            notebook.modify_tags(&mut |all_tags| {
+                for (tag, note_identifiers) in &tags {
+                    all_tags
+                        .entry(tag.clone())
+                        .or_default()
+                        .extend(note_identifiers.iter().cloned());
+                }
+
+                Ok(())
+            })
+        })?;
+
+        println!("total {}", imported);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+mod archive;
+mod dir;
+
+use crate::config::Config;
+use crate::run::Run;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use log::debug;
+
+pub use archive::ImportArchiveCommand;
+pub use dir::ImportDirCommand;
+
+#[derive(Debug, Parser)]
+pub struct ImportCommand {
+    #[clap(subcommand)]
+    pub command: ImportSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ImportSubcommand {
+    /// Imports notes and their tags from an archive created by `export archive`
+    Archive(ImportArchiveCommand),
+    /// Imports a folder of Markdown or text notes, such as an Obsidian vault
+    Dir(ImportDirCommand),
+}
+
+impl Run for ImportCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("import command {:?}", self);
+
+        match &self.command {
+            ImportSubcommand::Archive(archive_command) => archive_command.run(config),
+            ImportSubcommand::Dir(dir_command) => dir_command.run(config),
+        }
+    }
+}
@@ -1,16 +1,14 @@
 use crate::common::notes::resolve_dir;
 use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::{NoteInfo, Notebook};
 use crate::run::Run;
 use anyhow::Result;
 use chrono::prelude::{DateTime, Utc};
 use chrono::Datelike;
 use clap::Parser;
 use log::debug;
-use std::fs;
-use std::fs::DirEntry;
-use std::path::Path;
 use std::path::PathBuf;
-use std::process;
 use std::time::SystemTime;
 use tabular::{Row, Table};
 
@@ -42,25 +40,7 @@ pub struct ListCommand {
 }
 
 impl ListCommand {
-    fn list_notes(&self, config: &Config) -> Result<Vec<DirEntry>> {
-        if self.all {
-            let entries = self.list_entries_in(&config.notes_dir)?;
-
-            let mut results = vec![];
-            for entry in entries {
-                results.extend(self.list_entries_in(&entry.path())?);
-            }
-
-            Ok(results)
-        } else {
-            let dir = resolve_dir(&self.dir);
-            let note_parent_dir = config.notes_dir.join(&dir);
-
-            self.list_entries_in(&note_parent_dir)
-        }
-    }
-
-    fn build_table(&self, config: &Config, entries: Vec<DirEntry>) -> Result<Table> {
+    fn build_table(&self, notes: Vec<NoteInfo>) -> Table {
         let mut table = Table::new("{:<} {:<} {:<} {:<}");
 
         if self.include_headers {
@@ -73,31 +53,23 @@ impl ListCommand {
             );
         }
 
-        for entry in entries {
-            let metadata = entry.metadata()?;
-            let path = entry.path();
-            let path = path.strip_prefix(&config.notes_dir)?.display();
+        for note in notes {
+            let path = if note.encrypted {
+                format!("{} (encrypted)", note.identifier)
+            } else {
+                note.identifier
+            };
 
             table.add_row(
                 Row::new()
-                    .with_cell(format_system_time(metadata.created()?))
-                    .with_cell(metadata.len())
-                    .with_cell(format_system_time(metadata.modified()?))
+                    .with_cell(format_system_time(note.created))
+                    .with_cell(note.len)
+                    .with_cell(format_system_time(note.modified))
                     .with_cell(path),
             );
         }
 
-        Ok(table)
-    }
-
-    fn list_entries_in(&self, dir: &Path) -> Result<Vec<DirEntry>> {
-        let results = if dir.exists() && dir.metadata()?.is_dir() {
-            fs::read_dir(dir)?.filter_map(|entry| entry.ok()).collect()
-        } else {
-            vec![]
-        };
-
-        Ok(results)
+        table
     }
 }
 
@@ -106,14 +78,16 @@ impl Run for ListCommand {
         debug!("list command {:?}", self);
 
         if self.dir.is_some() && self.all {
-            eprintln!("--dir can't be used with --all");
-
-            process::exit(1);
+            return Err(
+                GnotesError::InvalidArgs(String::from("--dir can't be used with --all")).into(),
+            );
         }
 
-        let results = self.list_notes(config)?;
-        let total = results.len();
-        let table = self.build_table(config, results)?;
+        let dir = resolve_dir(&self.dir);
+        let dir = if self.all { None } else { Some(dir.as_path()) };
+        let notes = Notebook::new(config.clone())?.list(dir)?;
+        let total = notes.len();
+        let table = self.build_table(notes);
 
         println!("total {}", total);
         if total > 0 {
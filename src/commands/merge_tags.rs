@@ -0,0 +1,52 @@
+use crate::common::tags::{format_tags, merge_tags, Tags};
+use crate::error::GnotesError;
+use anyhow::Result;
+use clap::Parser;
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Parser)]
+pub struct MergeTagsCommand {
+    /// The tags file of the common ancestor
+    pub base: PathBuf,
+    /// Our tags file, which is overwritten with the merged tags
+    pub ours: PathBuf,
+    /// Their tags file
+    pub theirs: PathBuf,
+}
+
+/// Reads a tags file given by git, which is empty when the file doesn't exist on that side.
+fn read_tags_file(tags_file_path: &Path) -> Result<Tags> {
+    let data = fs::read_to_string(tags_file_path)?;
+
+    if data.trim().is_empty() {
+        return Ok(Tags::new());
+    }
+
+    serde_json::from_str(&data).map_err(|error| {
+        GnotesError::TagStoreCorrupt {
+            path: String::from(tags_file_path.to_string_lossy()),
+            reason: error.to_string(),
+        }
+        .into()
+    })
+}
+
+impl MergeTagsCommand {
+    /// Merges the tags files, as the git merge driver of the `.tags` and `.inline-tags` files.
+    /// A failure leaves the merge conflicted.
+    pub fn merge(&self) -> Result<()> {
+        debug!("merge-tags command {:?}", self);
+
+        let merged = merge_tags(
+            &read_tags_file(&self.base)?,
+            &read_tags_file(&self.ours)?,
+            &read_tags_file(&self.theirs)?,
+        );
+
+        fs::write(&self.ours, format_tags(&merged)?)?;
+
+        Ok(())
+    }
+}
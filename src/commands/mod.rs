@@ -1,23 +1,53 @@
 mod add;
+mod agenda;
+mod backlinks;
 mod clone;
+mod completions;
 mod edit;
+mod export;
+mod graph;
+mod import;
 mod list;
+mod merge_tags;
+mod mv;
 mod new;
+mod outline;
+mod plugin;
 mod remove;
 mod save;
 mod search;
 mod show;
+mod store;
 mod tag;
+mod tags;
+mod todo;
+mod ui;
 mod untag;
+mod watch;
 
 pub use self::edit::EditCommand;
 pub use add::AddCommand;
+pub use agenda::AgendaCommand;
+pub use backlinks::BacklinksCommand;
 pub use clone::CloneCommand;
+pub use completions::{CompleteCommand, CompletionsCommand};
+pub use export::ExportCommand;
+pub use graph::GraphCommand;
+pub use import::ImportCommand;
 pub use list::ListCommand;
+pub use merge_tags::MergeTagsCommand;
+pub use mv::MoveCommand;
 pub use new::NewCommand;
+pub use outline::OutlineCommand;
+pub use plugin::{find_plugin, find_plugins, PluginCommand};
 pub use remove::RemoveCommand;
 pub use save::SaveCommand;
 pub use search::SearchCommand;
 pub use show::ShowCommand;
+pub use store::StoreCommand;
 pub use tag::TagCommand;
+pub use tags::TagsCommand;
+pub use todo::TodoCommand;
+pub use ui::UiCommand;
 pub use untag::UntagCommand;
+pub use watch::WatchCommand;
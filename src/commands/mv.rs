@@ -0,0 +1,46 @@
+use crate::common::notes::resolve_dir;
+use crate::config::Config;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::Parser;
+use log::debug;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct MoveCommand {
+    /// The name of the note
+    pub name: String,
+    /// The new name of the note
+    pub new_name: String,
+    /// The note directory. defaults to "notes"
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+    /// The directory to move the note to. defaults to the note directory
+    #[clap(long)]
+    pub to_dir: Option<PathBuf>,
+}
+
+impl Run for MoveCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("mv command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+        let note_identifier = notebook.existing_identifier(&self.name, &self.dir)?;
+        let to_dir = self
+            .to_dir
+            .clone()
+            .unwrap_or_else(|| resolve_dir(&self.dir));
+        let new_note_identifier = Notebook::identifier(&self.new_name, &Some(to_dir));
+
+        if new_note_identifier == note_identifier {
+            return Ok(());
+        }
+
+        for skipped in notebook.rename(&note_identifier, &new_note_identifier)? {
+            eprintln!("links in {} weren't updated, as it's encrypted", skipped);
+        }
+
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
-use crate::common::notes::{resolve_dir, write_note};
+use crate::common::crypto::read_passphrase;
+use crate::common::hooks::{run_hook, Hook};
 use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
@@ -17,27 +20,84 @@ pub struct NewCommand {
     /// The note content
     #[clap(short, long)]
     pub message: Option<String>,
+    /// Encrypt the note with a passphrase
+    #[clap(long)]
+    pub encrypt: bool,
 }
 
 impl Run for NewCommand {
     fn run(&self, config: &Config) -> Result<()> {
         debug!("new command {:?}", self);
 
-        let dir = resolve_dir(&self.dir);
-        let note_parent_dir = config.notes_dir.join(&dir);
+        let notebook = Notebook::new(config.clone())?;
+        let note_identifier = Notebook::identifier(&self.name, &self.dir);
+        let note_file_path = notebook.path(&note_identifier);
+
+        if self.encrypt || notebook.needs_passphrase(&note_identifier) {
+            let exists = notebook.exists(&note_identifier);
+
+            // Encrypting an existing plain note would be a surprising side effect of `new`.
+            if exists && !notebook.needs_passphrase(&note_identifier) {
+                return Err(GnotesError::NoteExists(String::from(
+                    note_file_path.to_string_lossy(),
+                ))
+                .into());
+            }
+
+            let passphrase = read_passphrase()?;
+
+            match &self.message {
+                Some(message) if exists => {
+                    notebook.append(&note_identifier, message, None, Some(&passphrase))?;
+                }
+                Some(message) => {
+                    notebook.create(
+                        &note_identifier,
+                        &format!("{}\n", message),
+                        Some(&passphrase),
+                    )?;
+                }
+                _ => {
+                    let content = if exists {
+                        notebook.read(&note_identifier, Some(&passphrase))?.content
+                    } else {
+                        String::new()
+                    };
+
+                    debug!("Opening editor for encrypted note {:?}", self.name);
 
-        match &self.message {
-            Some(message) => write_note(&note_parent_dir, &self.name, &message)?,
-            _ => {
-                let note_file_path = note_parent_dir.join(&self.name);
+                    let content = edit::edit(content)?;
+
+                    if exists {
+                        notebook.update(&note_identifier, &content, Some(&passphrase))?;
+                    } else {
+                        notebook.create(&note_identifier, &content, Some(&passphrase))?;
+                    }
+                }
+            }
+        } else if let Some(message) = &self.message {
+            // An existing note is appended to, like `add`.
+            notebook.append(&note_identifier, message, None, None)?;
+        } else {
+            if let Some(note_parent_dir) = note_file_path.parent() {
                 fs::create_dir_all(note_parent_dir)?;
+            }
 
-                debug!("Opening editor for file {:?}", note_file_path);
+            debug!("Opening editor for file {:?}", note_file_path);
 
-                edit::edit_file(note_file_path)?;
+            edit::edit_file(&note_file_path)?;
+
+            // The editor may exit without saving the note.
+            if !note_file_path.exists() {
+                return Ok(());
             }
+
+            notebook
+                .refresh_inline_tags(&note_identifier, &fs::read_to_string(&note_file_path)?)?;
         }
 
+        run_hook(config, Hook::PostNew, "new", Some(&note_identifier))?;
+
         Ok(())
     }
 }
@@ -0,0 +1,53 @@
+use crate::common::crypto::read_passphrase;
+use crate::common::sections::find_headings;
+use crate::config::Config;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::Parser;
+use log::debug;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct OutlineCommand {
+    /// The name of the note
+    pub name: String,
+    /// The note directory. defaults to "notes"
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+}
+
+impl Run for OutlineCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("outline command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+        let note_identifier = notebook.existing_identifier(&self.name, &self.dir)?;
+        let passphrase = if notebook.needs_passphrase(&note_identifier) {
+            Some(read_passphrase()?)
+        } else {
+            None
+        };
+        let content = notebook
+            .read(&note_identifier, passphrase.as_deref())?
+            .content;
+
+        let headings = find_headings(&content);
+        // Notes which start at `##` are displayed without an extra indentation.
+        let min_level = headings
+            .iter()
+            .map(|heading| heading.level)
+            .min()
+            .unwrap_or(1);
+
+        for heading in headings {
+            println!(
+                "{}{}",
+                "  ".repeat(heading.level - min_level),
+                heading.title
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,119 @@
+use crate::config::{Config, StoreKind};
+use crate::error::GnotesError;
+use crate::run::Run;
+use anyhow::Result;
+use log::debug;
+use std::collections::BTreeSet;
+use std::env;
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const PLUGIN_PREFIX: &str = "gnotes-";
+
+/// An unknown subcommand, which is run by the `gnotes-<name>` executable on `PATH`, like git does.
+#[derive(Debug)]
+pub struct PluginCommand {
+    /// The name of the plugin, followed by its arguments
+    pub args: Vec<String>,
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    path.metadata()
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or_default()
+}
+
+/// Without an executable bit, any file can be a plugin.
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// The `gnotes-<name>` executable on `PATH`.
+pub fn find_plugin(name: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+
+    env::split_paths(&path)
+        .map(|dir| dir.join(format!("{}{}", PLUGIN_PREFIX, name)))
+        .find(|plugin_path| is_executable(plugin_path))
+}
+
+/// The names of the plugins on `PATH`, without the `gnotes-` prefix.
+pub fn find_plugins() -> BTreeSet<String> {
+    let path = env::var_os("PATH").unwrap_or_default();
+
+    env::split_paths(&path)
+        .filter_map(|dir| dir.read_dir().ok())
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| is_executable(&entry.path()))
+        .filter_map(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .strip_prefix(PLUGIN_PREFIX)
+                .map(String::from)
+        })
+        .collect()
+}
+
+impl Run for PluginCommand {
+    /// Runs the plugin with the config in the `GNOTES_` environment variables, which also
+    /// override the config file, so gnotes commands run by the plugin use the same config.
+    /// Fails with `PluginFailed`, which reports the exit code of the plugin, when it fails.
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("plugin command {:?}", self);
+
+        let (name, args) = match self.args.split_first() {
+            Some((name, args)) => (name, args),
+            _ => return Err(GnotesError::InvalidArgs(String::from("no command given")).into()),
+        };
+        let plugin_path = find_plugin(name).ok_or_else(|| {
+            GnotesError::InvalidArgs(format!(
+                "'{}' isn't a gnotes command, and no '{}{}' plugin was found on PATH",
+                name, PLUGIN_PREFIX, name
+            ))
+        })?;
+
+        let mut command = Command::new(plugin_path);
+        command
+            .args(args)
+            .env("GNOTES_NOTES_DIR", &config.notes_dir)
+            .env("GNOTES_AUTO_SAVE", config.auto_save.to_string())
+            .env("GNOTES_SSH_FILE_PATH", &config.ssh_file_path)
+            .env("GNOTES_INLINE_TAGS", config.inline_tags.to_string())
+            .env(
+                "GNOTES_TRUST_NOTES_HOOKS",
+                config.trust_notes_hooks.to_string(),
+            )
+            .env(
+                "GNOTES_STORE",
+                match config.store {
+                    StoreKind::Json => "json",
+                    StoreKind::Sqlite => "sqlite",
+                },
+            );
+
+        if let Some(repository) = &config.repository {
+            command.env("GNOTES_REPOSITORY", repository);
+        }
+        if let Some(hooks_dir) = &config.hooks_dir {
+            command.env("GNOTES_HOOKS_DIR", hooks_dir);
+        }
+
+        let status = command.status()?;
+
+        if !status.success() {
+            return Err(GnotesError::PluginFailed {
+                name: name.clone(),
+                code: status.code().unwrap_or(1),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
@@ -1,12 +1,11 @@
-use crate::common::notes::{get_note_identifier, resolve_dir};
-use crate::common::tags::{load_tags, update_tags, Tags};
+use crate::common::hooks::{run_hook, Hook};
+use crate::common::resolve::resolve_note;
 use crate::config::Config;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
 use log::debug;
-use std::collections::HashMap;
-use std::fs;
 use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
@@ -22,34 +21,10 @@ impl Run for RemoveCommand {
     fn run(&self, config: &Config) -> Result<()> {
         debug!("remove command {:?}", self);
 
-        let dir = resolve_dir(&self.dir);
-        let note_parent_dir = config.notes_dir.join(&dir);
-        let note_file_path = note_parent_dir.join(&self.name);
+        if let Some(note_identifier) = resolve_note(&config.notes_dir, &self.name, &self.dir)? {
+            Notebook::new(config.clone())?.remove(&note_identifier)?;
 
-        if note_file_path.exists() {
-            let note_identifier =
-                get_note_identifier("remove", &config.notes_dir, &self.name, &dir);
-
-            fs::remove_file(note_file_path)?;
-
-            let tags = load_tags(&config.notes_dir)?;
-            let mut new_tags: Tags = HashMap::new();
-
-            for (tag, tags_set) in tags {
-                if tags_set.contains(&note_identifier) {
-                    let mut update_tags_set = tags_set.clone();
-
-                    update_tags_set.remove(&note_identifier);
-
-                    if update_tags_set.len() > 0 {
-                        new_tags.insert(tag.clone(), update_tags_set);
-                    }
-                } else {
-                    new_tags.insert(tag.clone(), tags_set.clone());
-                }
-            }
-
-            update_tags(&config.notes_dir, &new_tags)?;
+            run_hook(config, Hook::PostRemove, "remove", Some(&note_identifier))?;
         }
 
         Ok(())
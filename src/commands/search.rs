@@ -1,13 +1,15 @@
+use crate::common::crypto::read_passphrase;
 use crate::common::notes::resolve_dir;
-use crate::common::tags::load_tags;
-use crate::common::writers::write_as_markdown;
+use crate::common::pager::page;
+use crate::common::writers::render_content_as_markdown;
 use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
 use log::debug;
-use std::path::{PathBuf, MAIN_SEPARATOR};
-use std::process;
+use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
 pub struct SearchCommand {
@@ -21,6 +23,12 @@ pub struct SearchCommand {
     /// Show the tags content, together with their metadata
     #[clap(long)]
     pub show: bool,
+    /// Match only the tag itself, without its nested tags
+    #[clap(long)]
+    pub exact: bool,
+    /// With --show, write the notes directly to stdout, without a pager
+    #[clap(long)]
+    pub no_pager: bool,
 }
 
 impl Run for SearchCommand {
@@ -28,45 +36,39 @@ impl Run for SearchCommand {
         debug!("search command {:?}", self);
 
         if self.dir.is_some() && self.all {
-            eprintln!("--dir can't be used with --all");
-
-            process::exit(1);
+            return Err(
+                GnotesError::InvalidArgs(String::from("--dir can't be used with --all")).into(),
+            );
         }
 
         let dir = resolve_dir(&self.dir);
-        let tags = load_tags(&config.notes_dir)?;
+        let dir = if self.all { None } else { Some(dir.as_path()) };
+        let notebook = Notebook::new(config.clone())?;
+        let results = notebook.search(&self.tag, dir, self.exact)?;
 
-        let results = match tags.get(&self.tag) {
-            Some(tags_set) => {
-                if self.all {
-                    tags_set.into_iter().collect()
-                } else {
-                    let note_identifier_prefix =
-                        format!("{}{}", String::from(dir.to_string_lossy()), MAIN_SEPARATOR);
+        let mut output = String::new();
 
-                    tags_set
-                        .into_iter()
-                        .filter(|note_identifier| {
-                            note_identifier.starts_with(&note_identifier_prefix)
-                        })
-                        .collect()
-                }
-            }
-            _ => vec![],
-        };
-
-        if results.len() > 0 {
-            println!("total {}", results.len());
+        if !results.is_empty() {
+            output.push_str(&format!("total {}\n", results.len()));
         }
 
         for result in results {
             if self.show {
-                write_as_markdown(&config.notes_dir, result)?;
+                let passphrase = if notebook.needs_passphrase(&result) {
+                    Some(read_passphrase()?)
+                } else {
+                    None
+                };
+                let content = notebook.read(&result, passphrase.as_deref())?.content;
+
+                output.push_str(&render_content_as_markdown(&result, &content));
             } else {
-                println!("{}", result);
+                output.push_str(&format!("{}\n", result));
             }
         }
 
+        page(&output, self.show && !self.no_pager)?;
+
         Ok(())
     }
 }
@@ -1,12 +1,17 @@
-use crate::common::notes::{get_note_identifier, resolve_dir};
-use crate::common::writers::write_as_markdown;
+use crate::common::crypto::read_passphrase;
+use crate::common::links::find_linked_notes;
+use crate::common::pager::page;
+use crate::common::resolve::resolve_existing_note;
+use crate::common::sections::{find_headings, find_section};
+use crate::common::writers::render_content_as_markdown;
 use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
 use log::debug;
 use std::path::PathBuf;
-use std::process;
 
 #[derive(Debug, Parser)]
 pub struct ShowCommand {
@@ -15,27 +20,58 @@ pub struct ShowCommand {
     /// The note directory. defaults to "notes"
     #[clap(long)]
     pub dir: Option<PathBuf>,
+    /// Show only this section, by its heading title, e.g. "Action items" or "## Action items"
+    #[clap(long)]
+    pub section: Option<String>,
+    /// Write the note directly to stdout, without a pager
+    #[clap(long)]
+    pub no_pager: bool,
 }
 
 impl Run for ShowCommand {
     fn run(&self, config: &Config) -> Result<()> {
         debug!("show command {:?}", self);
 
-        let dir = resolve_dir(&self.dir);
-        let note_file_path = config.notes_dir.join(&dir).join(&self.name);
-        let note_identifier = get_note_identifier("show", &config.notes_dir, &self.name, &dir);
-
-        if note_file_path.exists() {
-            write_as_markdown(&config.notes_dir, &note_identifier)?;
+        let notebook = Notebook::new(config.clone())?;
+        let note_identifier = resolve_existing_note(&config.notes_dir, &self.name, &self.dir)?;
+        let passphrase = if notebook.needs_passphrase(&note_identifier) {
+            Some(read_passphrase()?)
         } else {
-            eprintln!(
-                "show failed: file '{}' not found",
-                String::from(note_file_path.to_string_lossy())
-            );
+            None
+        };
+
+        let content = notebook
+            .read(&note_identifier, passphrase.as_deref())?
+            .content;
+        let content = match &self.section {
+            Some(section) => {
+                let headings = find_headings(&content);
 
-            process::exit(1);
+                match find_section(&headings, section) {
+                    Some(heading) => String::from(&content[heading.start..heading.end]),
+                    _ => return Err(GnotesError::SectionNotFound(section.clone()).into()),
+                }
+            }
+            _ => content,
+        };
+
+        let mut output = render_content_as_markdown(&note_identifier, &content);
+
+        let linked_notes = find_linked_notes(&content);
+        if !linked_notes.is_empty() {
+            output.push_str("links:\n");
+
+            for linked_note in linked_notes {
+                if notebook.exists(&linked_note) {
+                    output.push_str(&format!("  {}\n", linked_note));
+                } else {
+                    output.push_str(&format!("  {} (missing)\n", linked_note));
+                }
+            }
         }
 
+        page(&output, !self.no_pager)?;
+
         Ok(())
     }
 }
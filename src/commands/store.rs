@@ -0,0 +1,43 @@
+use crate::config::{Config, StoreKind};
+use crate::error::GnotesError;
+use crate::run::Run;
+use crate::store::open_tag_store;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use log::debug;
+
+#[derive(Debug, Parser)]
+pub struct StoreCommand {
+    #[clap(subcommand)]
+    pub command: StoreSubcommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StoreSubcommand {
+    /// Replaces the tags in the database with the ones in the `.tags` and `.inline-tags` files
+    Import,
+    /// Writes the tags in the database to the `.tags` and `.inline-tags` files
+    Export,
+}
+
+impl Run for StoreCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("store command {:?}", self);
+
+        if config.store != StoreKind::Sqlite {
+            return Err(GnotesError::InvalidArgs(String::from(
+                "the tags are already kept in JSON. Set store = \"sqlite\" in the config file to use the database",
+            ))
+            .into());
+        }
+
+        let tag_store = open_tag_store(config)?;
+
+        match self.command {
+            StoreSubcommand::Import => tag_store.import_json(&config.notes_dir)?,
+            StoreSubcommand::Export => tag_store.export_json(&config.notes_dir)?,
+        }
+
+        Ok(())
+    }
+}
@@ -1,11 +1,11 @@
-use crate::common::notes::{get_note_identifier, resolve_dir};
-use crate::common::tags::{load_tags, update_tags};
+use crate::common::hooks::{run_hook, Hook};
+use crate::common::resolve::resolve_existing_note;
 use crate::config::Config;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
 use log::debug;
-use std::collections::HashSet;
 use std::path::PathBuf;
 
 #[derive(Debug, Parser)]
@@ -23,26 +23,11 @@ impl Run for TagCommand {
     fn run(&self, config: &Config) -> Result<()> {
         debug!("tag command {:?}", self);
 
-        let dir = resolve_dir(&self.dir);
-        let note_identifier = get_note_identifier("tag", &config.notes_dir, &self.name, &dir);
+        let note_identifier = resolve_existing_note(&config.notes_dir, &self.name, &self.dir)?;
 
-        let mut tags = load_tags(&config.notes_dir)?;
+        Notebook::new(config.clone())?.tag(&note_identifier, &self.tags)?;
 
-        for tag in &self.tags {
-            match tags.get_mut(tag) {
-                Some(tags_set) => {
-                    tags_set.insert(note_identifier.clone());
-                }
-                _ => {
-                    tags.insert(
-                        tag.clone(),
-                        HashSet::from_iter(vec![note_identifier.clone()]),
-                    );
-                }
-            };
-        }
-
-        update_tags(&config.notes_dir, &tags)?;
+        run_hook(config, Hook::PostTag, "tag", Some(&note_identifier))?;
 
         Ok(())
     }
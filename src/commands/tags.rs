@@ -0,0 +1,221 @@
+use crate::common::notes::resolve_dir;
+use crate::common::tags::{is_tag_or_descendant, Tags, TAG_SEPARATOR};
+use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use log::debug;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use tabular::{Row, Table};
+
+#[derive(Debug, Parser)]
+pub struct TagsCommand {
+    /// Count only notes under this directory. Not supported by the subcommands
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+    /// Display nested tags as a tree, with counts rolled up to their parents
+    #[clap(long)]
+    pub tree: bool,
+    #[clap(subcommand)]
+    pub command: Option<TagsSubcommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TagsSubcommand {
+    /// Renames a tag, together with its nested tags
+    Rename {
+        /// The tag to rename
+        old: String,
+        /// The new tag name
+        new: String,
+    },
+    /// Merges tags into a single tag
+    Merge {
+        /// The tags to merge
+        #[clap(required = true)]
+        tags: Vec<String>,
+        /// The tag to merge into
+        #[clap(long)]
+        into: String,
+    },
+    /// Deletes a tag from all notes
+    Delete {
+        /// The tag to delete
+        tag: String,
+    },
+}
+
+fn assert_tag_exists(tags: &Tags, tag: &str) -> Result<()> {
+    if !tags.contains_key(tag) {
+        return Err(GnotesError::TagNotFound(String::from(tag)).into());
+    }
+
+    Ok(())
+}
+
+impl TagsCommand {
+    fn filter_by_dir<'a>(&self, tags: &'a Tags) -> BTreeMap<&'a String, HashSet<&'a String>> {
+        let note_identifier_prefix = self
+            .dir
+            .as_ref()
+            .map(|_| String::from(resolve_dir(&self.dir).join("").to_string_lossy()));
+
+        tags.iter()
+            .map(|(tag, tags_set)| {
+                let tags_set = tags_set
+                    .iter()
+                    .filter(|note_identifier| match &note_identifier_prefix {
+                        Some(prefix) => note_identifier.starts_with(prefix),
+                        _ => true,
+                    })
+                    .collect::<HashSet<&String>>();
+
+                (tag, tags_set)
+            })
+            .filter(|(_, tags_set)| !tags_set.is_empty())
+            .collect()
+    }
+
+    fn build_tree<'a>(
+        &self,
+        tags: &BTreeMap<&'a String, HashSet<&'a String>>,
+    ) -> BTreeMap<Vec<&'a str>, HashSet<&'a String>> {
+        let mut tree: BTreeMap<Vec<&str>, HashSet<&String>> = BTreeMap::new();
+
+        for (tag, tags_set) in tags {
+            let segments: Vec<&str> = tag.split(TAG_SEPARATOR).collect();
+
+            for depth in 1..=segments.len() {
+                tree.entry(segments[..depth].to_vec())
+                    .or_default()
+                    .extend(tags_set.iter());
+            }
+        }
+
+        tree
+    }
+
+    fn list(&self, tags: &Tags) {
+        let tags = self.filter_by_dir(tags);
+
+        println!("total {}", tags.len());
+
+        if self.tree {
+            for (segments, tags_set) in self.build_tree(&tags) {
+                println!(
+                    "{}{} ({})",
+                    "  ".repeat(segments.len() - 1),
+                    segments[segments.len() - 1],
+                    tags_set.len()
+                );
+            }
+        } else if !tags.is_empty() {
+            let mut table = Table::new("{:<} {:>}");
+            for (tag, tags_set) in &tags {
+                table.add_row(Row::new().with_cell(tag).with_cell(tags_set.len()));
+            }
+
+            print!("{}", table);
+        }
+    }
+
+    fn rename(&self, tags: &mut Tags, old: &str, new: &str) -> Result<()> {
+        let renamed: Vec<String> = tags
+            .keys()
+            .filter(|tag| is_tag_or_descendant(tag, old))
+            .cloned()
+            .collect();
+
+        if renamed.is_empty() {
+            return Err(GnotesError::TagNotFound(String::from(old)).into());
+        }
+
+        let new_names: Vec<String> = renamed
+            .iter()
+            .map(|tag| format!("{}{}", new, &tag[old.len()..]))
+            .collect();
+
+        for new_name in &new_names {
+            if tags.contains_key(new_name) && !renamed.contains(new_name) {
+                return Err(GnotesError::TagExists(new_name.clone()).into());
+            }
+        }
+
+        let tags_sets: Vec<HashSet<String>> =
+            renamed.iter().filter_map(|tag| tags.remove(tag)).collect();
+
+        for (new_name, tags_set) in new_names.into_iter().zip(tags_sets) {
+            tags.entry(new_name).or_default().extend(tags_set);
+        }
+
+        Ok(())
+    }
+
+    fn merge(&self, tags: &mut Tags, sources: &[String], into: &str) -> Result<()> {
+        for tag in sources {
+            assert_tag_exists(tags, tag)?;
+        }
+
+        let mut merged: HashSet<String> = tags.remove(into).unwrap_or_default();
+        for tag in sources {
+            if let Some(tags_set) = tags.remove(tag) {
+                merged.extend(tags_set);
+            }
+        }
+
+        tags.insert(String::from(into), merged);
+
+        Ok(())
+    }
+
+    fn delete(&self, tags: &mut Tags, tag: &str) -> Result<()> {
+        assert_tag_exists(tags, tag)?;
+
+        tags.remove(tag);
+
+        Ok(())
+    }
+}
+
+impl Run for TagsCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("tags command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+
+        let subcommand = match &self.command {
+            Some(subcommand) => subcommand,
+            None => {
+                self.list(&notebook.tags()?);
+
+                return Ok(());
+            }
+        };
+
+        // The subcommands change the tags of all the notes, so they can't be scoped to a directory.
+        if self.dir.is_some() {
+            return Err(GnotesError::InvalidArgs(String::from(
+                "--dir can only be used to list the tags",
+            ))
+            .into());
+        }
+
+        notebook.modify_tags(&mut |tags| {
+            match subcommand {
+                TagsSubcommand::Rename { old, new } => self.rename(tags, old, new)?,
+                TagsSubcommand::Merge {
+                    tags: sources,
+                    into,
+                } => self.merge(tags, sources, into)?,
+                TagsSubcommand::Delete { tag } => self.delete(tags, tag)?,
+            }
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+}
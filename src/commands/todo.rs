@@ -0,0 +1,175 @@
+use crate::common::crypto::read_passphrase;
+use crate::common::notes::split_identifier;
+use crate::common::todos::{append_todo, find_todos, toggle_todo, Todo};
+use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use log::debug;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+pub struct TodoCommand {
+    /// Include only notes under this directory
+    #[clap(long)]
+    pub dir: Option<PathBuf>,
+    /// Include only notes tagged with this tag (or its nested tags)
+    #[clap(long)]
+    pub tag: Option<String>,
+    /// Display only done items
+    #[clap(long, conflicts_with = "open")]
+    pub done: bool,
+    /// Display only open items
+    #[clap(long)]
+    pub open: bool,
+    #[clap(subcommand)]
+    pub command: Option<TodoSubcommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TodoSubcommand {
+    /// Toggles an item between open and done
+    Done {
+        /// The item id, in the form of "dir/name:line"
+        id: String,
+    },
+    /// Appends an open item to a note
+    Add {
+        /// The name of the note
+        name: String,
+        /// The item text
+        text: String,
+        /// The note directory. defaults to "notes"
+        #[clap(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+fn format_todo(note_identifier: &str, todo: &Todo) -> String {
+    format!(
+        "{}:{} [{}] {}",
+        note_identifier,
+        todo.line,
+        if todo.done { "x" } else { " " },
+        todo.text
+    )
+}
+
+/// Applies `modify` to the note content, asking for the passphrase when the note is encrypted.
+fn modify_note(
+    notebook: &Notebook,
+    note_identifier: &str,
+    modify: &mut dyn FnMut(&str) -> Option<String>,
+) -> Result<Option<String>> {
+    let passphrase = if notebook.is_encrypted(note_identifier) {
+        Some(read_passphrase()?)
+    } else {
+        None
+    };
+
+    Ok(notebook
+        .modify(note_identifier, passphrase.as_deref(), modify)?
+        .map(|note| note.content))
+}
+
+impl TodoCommand {
+    fn list(&self, notebook: &Notebook) -> Result<()> {
+        let note_identifiers = notebook.select(self.dir.as_deref(), self.tag.as_deref())?;
+
+        let mut results = vec![];
+        for note_identifier in &note_identifiers {
+            // Encrypted notes can't be scanned without their passphrase.
+            let content = notebook.read_plain(note_identifier).unwrap_or_default();
+
+            for todo in find_todos(&content) {
+                if (self.done && !todo.done) || (self.open && todo.done) {
+                    continue;
+                }
+
+                results.push(format_todo(note_identifier, &todo));
+            }
+        }
+
+        if !results.is_empty() {
+            println!("total {}", results.len());
+        }
+
+        for result in results {
+            println!("{}", result);
+        }
+
+        Ok(())
+    }
+
+    fn toggle(&self, notebook: &Notebook, id: &str) -> Result<()> {
+        let (note_identifier, line) = match id
+            .rsplit_once(':')
+            .and_then(|(note_identifier, line)| Some((note_identifier, line.parse().ok()?)))
+        {
+            Some((note_identifier, line)) if line > 0 => (note_identifier, line),
+            _ => {
+                return Err(GnotesError::InvalidArgs(String::from(
+                    "id must be in the form of \"dir/name:line\"",
+                ))
+                .into())
+            }
+        };
+
+        let (name, dir) = split_identifier(note_identifier);
+        let note_identifier =
+            notebook.existing_identifier(&name, &Some(dir.unwrap_or_default()))?;
+
+        let mut toggled = None;
+        modify_note(notebook, &note_identifier, &mut |content| {
+            toggle_todo(content, line).map(|(content, todo)| {
+                toggled = Some(todo);
+
+                content
+            })
+        })?;
+
+        match toggled {
+            Some(todo) => println!("{}", format_todo(&note_identifier, &todo)),
+            _ => return Err(GnotesError::TodoNotFound(String::from(id)).into()),
+        }
+
+        Ok(())
+    }
+
+    fn add(
+        &self,
+        notebook: &Notebook,
+        name: &str,
+        text: &str,
+        dir: &Option<PathBuf>,
+    ) -> Result<()> {
+        let note_identifier = notebook.existing_identifier(name, dir)?;
+
+        let content = modify_note(notebook, &note_identifier, &mut |content| {
+            Some(append_todo(content, text))
+        })?
+        .unwrap_or_default();
+
+        if let Some(todo) = find_todos(&content).pop() {
+            println!("{}", format_todo(&note_identifier, &todo));
+        }
+
+        Ok(())
+    }
+}
+
+impl Run for TodoCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("todo command {:?}", self);
+
+        let notebook = Notebook::new(config.clone())?;
+
+        match &self.command {
+            None => self.list(&notebook),
+            Some(TodoSubcommand::Done { id }) => self.toggle(&notebook, id),
+            Some(TodoSubcommand::Add { name, text, dir }) => self.add(&notebook, name, text, dir),
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use crate::common::fuzzy::fuzzy_filter;
+use crate::common::tags::{find_tagged_notes, Tags};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prompt {
+    Tag,
+    Untag,
+    Rename,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Normal,
+    Filter,
+    TagPicker,
+    Prompt(Prompt),
+}
+
+/// What the UI should do after a key press.
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    None,
+    Quit,
+    Edit(String),
+    Tag(String, Vec<String>),
+    Untag(String, String),
+    Rename(String, String),
+    Remove(String),
+    Save,
+}
+
+/// A line in the notes list, which is either a directory header or a note.
+#[derive(Debug, PartialEq)]
+pub enum Row<'a> {
+    Dir(&'a str),
+    Note(&'a str),
+}
+
+#[derive(Debug)]
+pub struct App {
+    notes: Vec<String>,
+    tags: Tags,
+    /// The notes that match the filters, in display order
+    visible: Vec<String>,
+    selected: usize,
+    pub mode: Mode,
+    pub query: String,
+    pub tag_filter: Option<String>,
+    /// The tags offered by the tag picker, together with their note counts
+    pub tag_choices: Vec<(String, usize)>,
+    pub tag_choice: usize,
+    pub input: String,
+    pub status: String,
+    pub preview_scroll: usize,
+}
+
+impl App {
+    pub fn new(notes: Vec<String>, tags: Tags) -> Self {
+        let mut app = Self {
+            notes,
+            tags,
+            visible: vec![],
+            selected: 0,
+            mode: Mode::Normal,
+            query: String::new(),
+            tag_filter: None,
+            tag_choices: vec![],
+            tag_choice: 0,
+            input: String::new(),
+            status: String::new(),
+            preview_scroll: 0,
+        };
+        app.refresh();
+
+        app
+    }
+
+    /// Replaces the notes and tags after a change, keeping the selection on `select` if given.
+    pub fn reload(&mut self, notes: Vec<String>, tags: Tags, select: Option<&str>) {
+        let selected_note = select
+            .map(String::from)
+            .or_else(|| self.selected_note().map(String::from));
+
+        self.notes = notes;
+        self.tags = tags;
+
+        if self
+            .tag_filter
+            .as_ref()
+            .map(|tag| find_tagged_notes(&self.tags, tag, false).is_empty())
+            .unwrap_or_default()
+        {
+            self.tag_filter = None;
+        }
+
+        self.refresh();
+
+        if let Some(position) = selected_note
+            .and_then(|selected_note| self.visible.iter().position(|note| *note == selected_note))
+        {
+            self.selected = position;
+        }
+    }
+
+    fn refresh(&mut self) {
+        let tagged_notes = self
+            .tag_filter
+            .as_ref()
+            .map(|tag| find_tagged_notes(&self.tags, tag, false));
+        let candidates: Vec<&String> = self
+            .notes
+            .iter()
+            .filter(|note| {
+                tagged_notes
+                    .as_ref()
+                    .map(|tagged_notes| tagged_notes.contains(note))
+                    .unwrap_or(true)
+            })
+            .collect();
+
+        self.visible = if self.query.trim().is_empty() {
+            candidates.into_iter().cloned().collect()
+        } else {
+            fuzzy_filter(&self.query, &candidates)
+                .into_iter()
+                .map(|note| String::from(note.as_str()))
+                .collect()
+        };
+
+        self.selected = self.selected.min(self.visible.len().saturating_sub(1));
+        self.preview_scroll = 0;
+    }
+
+    pub fn selected_note(&self) -> Option<&str> {
+        self.visible.get(self.selected).map(String::as_str)
+    }
+
+    /// The notes list, grouped by directory when it isn't ranked by a fuzzy query.
+    /// Returns the rows together with the index of the selected row.
+    pub fn rows(&self) -> (Vec<Row<'_>>, usize) {
+        if !self.query.trim().is_empty() {
+            return (
+                self.visible.iter().map(|note| Row::Note(note)).collect(),
+                self.selected,
+            );
+        }
+
+        let mut rows = vec![];
+        let mut selected_row = 0;
+        let mut current_dir: Option<&str> = None;
+
+        for (index, note) in self.visible.iter().enumerate() {
+            let dir = Path::new(note)
+                .parent()
+                .and_then(|dir| dir.to_str())
+                .unwrap_or_default();
+
+            if current_dir != Some(dir) {
+                rows.push(Row::Dir(dir));
+                current_dir = Some(dir);
+            }
+
+            if index == self.selected {
+                selected_row = rows.len();
+            }
+
+            rows.push(Row::Note(note));
+        }
+
+        (rows, selected_row)
+    }
+
+    /// The tags of a note, sorted.
+    pub fn note_tags(&self, note: &str) -> Vec<&str> {
+        let mut note_tags: Vec<&str> = self
+            .tags
+            .iter()
+            .filter(|(_, tags_set)| tags_set.contains(note))
+            .map(|(tag, _)| tag.as_str())
+            .collect();
+        note_tags.sort_unstable();
+
+        note_tags
+    }
+
+    fn move_selection(&mut self, offset: isize) {
+        let last = self.visible.len().saturating_sub(1) as isize;
+
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+        self.preview_scroll = 0;
+    }
+
+    fn open_tag_picker(&mut self) {
+        let mut tag_choices: Vec<(String, usize)> = self
+            .tags
+            .iter()
+            .map(|(tag, tags_set)| (tag.clone(), tags_set.len()))
+            .collect();
+        tag_choices.sort();
+
+        self.tag_choice = self
+            .tag_filter
+            .as_ref()
+            .and_then(|tag_filter| tag_choices.iter().position(|(tag, _)| tag == tag_filter))
+            .map(|position| position + 1)
+            .unwrap_or(0);
+        self.tag_choices = tag_choices;
+        self.mode = Mode::TagPicker;
+    }
+
+    fn start_prompt(&mut self, prompt: Prompt) {
+        let note = match self.selected_note() {
+            Some(note) => String::from(note),
+            _ => {
+                self.status = String::from("No note selected");
+
+                return;
+            }
+        };
+
+        self.input = match prompt {
+            Prompt::Rename => note,
+            _ => String::new(),
+        };
+        self.mode = Mode::Prompt(prompt);
+    }
+
+    fn finish_prompt(&mut self, prompt: Prompt) -> Action {
+        self.mode = Mode::Normal;
+
+        let input = std::mem::take(&mut self.input);
+        let input = input.trim();
+        let note = match self.selected_note() {
+            Some(note) => String::from(note),
+            _ => return Action::None,
+        };
+
+        match prompt {
+            Prompt::Delete if input.eq_ignore_ascii_case("y") => Action::Remove(note),
+            Prompt::Delete => Action::None,
+            _ if input.is_empty() => Action::None,
+            Prompt::Tag => Action::Tag(
+                note,
+                input
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|tag| !tag.is_empty())
+                    .map(String::from)
+                    .collect(),
+            ),
+            Prompt::Untag => Action::Untag(note, String::from(input)),
+            Prompt::Rename if input == note => Action::None,
+            Prompt::Rename => Action::Rename(note, String::from(input)),
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::Quit;
+        }
+
+        match self.mode {
+            Mode::Normal => self.handle_normal_key(key),
+            Mode::Filter => {
+                match key.code {
+                    KeyCode::Enter => self.mode = Mode::Normal,
+                    KeyCode::Esc => {
+                        self.query.clear();
+                        self.mode = Mode::Normal;
+                        self.refresh();
+                    }
+                    KeyCode::Backspace => {
+                        self.query.pop();
+                        self.refresh();
+                    }
+                    KeyCode::Up => self.move_selection(-1),
+                    KeyCode::Down => self.move_selection(1),
+                    KeyCode::Char(c) => {
+                        self.query.push(c);
+                        self.selected = 0;
+                        self.refresh();
+                    }
+                    _ => {}
+                }
+
+                Action::None
+            }
+            Mode::TagPicker => {
+                match key.code {
+                    KeyCode::Esc => self.mode = Mode::Normal,
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.tag_choice = self.tag_choice.saturating_sub(1)
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        self.tag_choice = (self.tag_choice + 1).min(self.tag_choices.len())
+                    }
+                    KeyCode::Enter => {
+                        // The first choice shows all notes.
+                        self.tag_filter = self
+                            .tag_choice
+                            .checked_sub(1)
+                            .and_then(|index| self.tag_choices.get(index))
+                            .map(|(tag, _)| tag.clone());
+                        self.mode = Mode::Normal;
+                        self.selected = 0;
+                        self.refresh();
+                    }
+                    _ => {}
+                }
+
+                Action::None
+            }
+            Mode::Prompt(prompt) => match key.code {
+                KeyCode::Esc => {
+                    self.input.clear();
+                    self.mode = Mode::Normal;
+
+                    Action::None
+                }
+                KeyCode::Enter => self.finish_prompt(prompt),
+                KeyCode::Char(c) if prompt == Prompt::Delete => {
+                    self.input = c.to_string();
+
+                    self.finish_prompt(prompt)
+                }
+                KeyCode::Backspace => {
+                    self.input.pop();
+
+                    Action::None
+                }
+                KeyCode::Char(c) => {
+                    self.input.push(c);
+
+                    Action::None
+                }
+                _ => Action::None,
+            },
+        }
+    }
+
+    fn handle_normal_key(&mut self, key: KeyEvent) -> Action {
+        self.status.clear();
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Home | KeyCode::Char('g') => self.move_selection(isize::MIN / 2),
+            KeyCode::End | KeyCode::Char('G') => self.move_selection(isize::MAX / 2),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.preview_scroll += 10,
+            KeyCode::PageUp => self.preview_scroll = self.preview_scroll.saturating_sub(10),
+            KeyCode::Char('/') => self.mode = Mode::Filter,
+            KeyCode::Char('#') => self.open_tag_picker(),
+            KeyCode::Char('t') => self.start_prompt(Prompt::Tag),
+            KeyCode::Char('u') => self.start_prompt(Prompt::Untag),
+            KeyCode::Char('r') => self.start_prompt(Prompt::Rename),
+            KeyCode::Char('d') => self.start_prompt(Prompt::Delete),
+            KeyCode::Char('s') => return Action::Save,
+            KeyCode::Enter | KeyCode::Char('e') => {
+                if let Some(note) = self.selected_note() {
+                    return Action::Edit(String::from(note));
+                }
+            }
+            _ => {}
+        }
+
+        Action::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maplit::{hashmap, hashset};
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    fn app() -> App {
+        App::new(
+            vec![
+                String::from("notes/chores"),
+                String::from("notes/reminders"),
+                String::from("work/plan"),
+                String::from("work/standup"),
+            ],
+            hashmap! {
+                String::from("home") => hashset! {String::from("notes/chores")},
+                String::from("project/alpha") => hashset! {String::from("work/plan"), String::from("work/standup")},
+            },
+        )
+    }
+
+    #[test]
+    fn rows_test() {
+        let mut app = app();
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Down));
+
+        assert_eq!(
+            app.rows(),
+            (
+                vec![
+                    Row::Dir("notes"),
+                    Row::Note("notes/chores"),
+                    Row::Note("notes/reminders"),
+                    Row::Dir("work"),
+                    Row::Note("work/plan"),
+                    Row::Note("work/standup"),
+                ],
+                4
+            )
+        );
+        assert_eq!(app.selected_note(), Some("work/plan"));
+    }
+
+    #[test]
+    fn fuzzy_filter_test() {
+        let mut app = app();
+
+        app.handle_key(key(KeyCode::Char('/')));
+        type_text(&mut app, "stnd");
+
+        assert_eq!(app.rows(), (vec![Row::Note("work/standup")], 0));
+
+        app.handle_key(key(KeyCode::Esc));
+
+        assert_eq!(app.rows().0.len(), 6);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn tag_filter_test() {
+        let mut app = app();
+
+        app.handle_key(key(KeyCode::Char('#')));
+        assert_eq!(
+            app.tag_choices,
+            vec![
+                (String::from("home"), 1),
+                (String::from("project/alpha"), 2)
+            ]
+        );
+
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Down));
+        app.handle_key(key(KeyCode::Enter));
+
+        assert_eq!(app.tag_filter, Some(String::from("project/alpha")));
+        assert_eq!(
+            app.rows().0,
+            vec![
+                Row::Dir("work"),
+                Row::Note("work/plan"),
+                Row::Note("work/standup")
+            ]
+        );
+    }
+
+    #[test]
+    fn prompts_test() {
+        let mut app = app();
+
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            Action::Edit(String::from("notes/chores"))
+        );
+
+        app.handle_key(key(KeyCode::Char('t')));
+        type_text(&mut app, "a, b");
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            Action::Tag(
+                String::from("notes/chores"),
+                vec![String::from("a"), String::from("b")]
+            )
+        );
+
+        app.handle_key(key(KeyCode::Char('r')));
+        assert_eq!(app.input, "notes/chores");
+        app.handle_key(key(KeyCode::Backspace));
+        assert_eq!(
+            app.handle_key(key(KeyCode::Enter)),
+            Action::Rename(String::from("notes/chores"), String::from("notes/chore"))
+        );
+
+        app.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(app.handle_key(key(KeyCode::Char('n'))), Action::None);
+        app.handle_key(key(KeyCode::Char('d')));
+        assert_eq!(
+            app.handle_key(key(KeyCode::Char('y'))),
+            Action::Remove(String::from("notes/chores"))
+        );
+
+        assert_eq!(app.handle_key(key(KeyCode::Char('s'))), Action::Save);
+        assert_eq!(app.handle_key(key(KeyCode::Char('q'))), Action::Quit);
+    }
+
+    #[test]
+    fn reload_keeps_selection_test() {
+        let mut app = app();
+
+        app.reload(
+            vec![String::from("notes/chores"), String::from("work/renamed")],
+            Tags::new(),
+            Some("work/renamed"),
+        );
+
+        assert_eq!(app.selected_note(), Some("work/renamed"));
+        assert_eq!(app.tag_filter, None);
+    }
+}
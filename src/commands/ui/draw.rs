@@ -0,0 +1,176 @@
+use super::app::{App, Mode, Prompt, Row};
+use anyhow::Result;
+use crossterm::cursor::MoveTo;
+use crossterm::queue;
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{Clear, ClearType};
+use std::io::{Stdout, Write};
+
+const MIN_LIST_WIDTH: u16 = 24;
+const HELP: &str =
+    "enter edit  / filter  # tags  t tag  u untag  r rename  d delete  s save  q quit";
+
+/// Cuts the text to the given number of characters, padding it with spaces.
+fn fit(text: &str, width: usize) -> String {
+    format!(
+        "{:<width$}",
+        text.chars().take(width).collect::<String>(),
+        width = width
+    )
+}
+
+pub fn list_width(width: u16) -> u16 {
+    (width / 3).max(MIN_LIST_WIDTH).min(width.saturating_sub(1))
+}
+
+fn draw_line(stdout: &mut Stdout, x: u16, y: u16, text: &str, reverse: bool) -> Result<()> {
+    queue!(stdout, MoveTo(x, y))?;
+
+    if reverse {
+        queue!(stdout, SetAttribute(Attribute::Reverse))?;
+    }
+
+    queue!(stdout, Print(text), SetAttribute(Attribute::Reset))?;
+
+    Ok(())
+}
+
+/// The rows of the left pane: the notes list, or the tags while picking a tag filter.
+fn list_rows(app: &App) -> (Vec<String>, usize) {
+    if app.mode == Mode::TagPicker {
+        let mut rows = vec![String::from("(all notes)")];
+        rows.extend(
+            app.tag_choices
+                .iter()
+                .map(|(tag, count)| format!("{} ({})", tag, count)),
+        );
+
+        return (rows, app.tag_choice);
+    }
+
+    let (rows, selected) = app.rows();
+    let rows = rows
+        .into_iter()
+        .map(|row| match row {
+            Row::Dir(dir) => format!("{}/", dir),
+            Row::Note(note) => format!(
+                "  {}",
+                note.rsplit_once('/').map(|(_, name)| name).unwrap_or(note)
+            ),
+        })
+        .collect();
+
+    (rows, selected)
+}
+
+fn status_line(app: &App) -> String {
+    match app.mode {
+        Mode::Normal if !app.status.is_empty() => app.status.clone(),
+        Mode::Normal => String::from(HELP),
+        Mode::Filter => format!("/{}", app.query),
+        Mode::TagPicker => String::from("Pick a tag to filter by, enter to apply, esc to cancel"),
+        Mode::Prompt(Prompt::Tag) => format!("Tags to add: {}", app.input),
+        Mode::Prompt(Prompt::Untag) => format!("Tag to remove: {}", app.input),
+        Mode::Prompt(Prompt::Rename) => format!("Rename to: {}", app.input),
+        Mode::Prompt(Prompt::Delete) => {
+            format!("Delete {}? (y/n)", app.selected_note().unwrap_or_default())
+        }
+    }
+}
+
+/// Draws the whole screen. `preview` holds the rendered lines of the selected note.
+pub fn draw(
+    stdout: &mut Stdout,
+    app: &App,
+    preview: &[String],
+    width: u16,
+    height: u16,
+) -> Result<()> {
+    let list_width = list_width(width);
+    let preview_x = list_width + 1;
+    let preview_width = usize::from(width.saturating_sub(preview_x));
+    let body_height = usize::from(height.saturating_sub(2));
+
+    queue!(stdout, Clear(ClearType::All))?;
+
+    let mut header = String::from(" gnotes");
+    if !app.query.is_empty() {
+        header.push_str(&format!("  filter: {}", app.query));
+    }
+    if let Some(tag) = &app.tag_filter {
+        header.push_str(&format!("  tag: {}", tag));
+    }
+    draw_line(stdout, 0, 0, &fit(&header, usize::from(width)), true)?;
+
+    // Scrolls the list so the selected row stays visible.
+    let (rows, selected) = list_rows(app);
+    let top = (selected + 1).saturating_sub(body_height);
+
+    for (index, row) in rows.iter().enumerate().skip(top).take(body_height) {
+        let y = (index - top + 1) as u16;
+
+        draw_line(
+            stdout,
+            0,
+            y,
+            &fit(row, usize::from(list_width)),
+            index == selected,
+        )?;
+    }
+
+    for y in 1..=body_height as u16 {
+        draw_line(stdout, list_width, y, "│", false)?;
+    }
+
+    if let Some(note) = app.selected_note() {
+        let tags = app.note_tags(note);
+        let title = if tags.is_empty() {
+            String::from(note)
+        } else {
+            format!("{}  [{}]", note, tags.join(", "))
+        };
+
+        queue!(stdout, SetAttribute(Attribute::Bold))?;
+        draw_line(stdout, preview_x, 1, &fit(&title, preview_width), false)?;
+
+        for (index, line) in preview
+            .iter()
+            .skip(app.preview_scroll)
+            .take(body_height.saturating_sub(2))
+            .enumerate()
+        {
+            draw_line(stdout, preview_x, (index + 3) as u16, line, false)?;
+        }
+    }
+
+    draw_line(
+        stdout,
+        0,
+        height.saturating_sub(1),
+        &fit(&status_line(app), usize::from(width)),
+        app.mode == Mode::Normal && !app.status.is_empty(),
+    )?;
+
+    stdout.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_test() {
+        assert_eq!(fit("abc", 5), "abc  ");
+        assert_eq!(fit("abcdef", 3), "abc");
+        assert_eq!(fit("äöü", 2), "äö");
+    }
+
+    #[test]
+    fn list_width_test() {
+        assert_eq!(list_width(120), 40);
+        assert_eq!(list_width(30), 24);
+        assert_eq!(list_width(10), 9);
+    }
+}
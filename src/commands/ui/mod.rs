@@ -0,0 +1,219 @@
+mod app;
+mod draw;
+
+use crate::commands::{
+    EditCommand, MoveCommand, RemoveCommand, SaveCommand, TagCommand, UntagCommand,
+};
+use crate::common::links::render_links;
+use crate::common::notes::split_identifier;
+use crate::common::screen::ScreenGuard;
+use crate::config::Config;
+use crate::error::GnotesError;
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use app::{Action, App};
+use clap::Parser;
+use crossterm::event::{read, Event};
+use crossterm::terminal;
+use log::debug;
+use std::io::{self, IsTerminal};
+use termimad::{FmtText, MadSkin};
+
+#[derive(Debug, Parser)]
+pub struct UiCommand {}
+
+/// Renders the note with termimad. Encrypted notes aren't decrypted, to avoid a passphrase prompt.
+fn render_preview(notebook: &Notebook, note_identifier: &str, width: usize) -> Vec<String> {
+    let content = if notebook.is_encrypted(note_identifier) {
+        String::from("*(encrypted)*")
+    } else {
+        match notebook.read_plain(note_identifier) {
+            Some(content) => render_links(&content),
+            _ => String::from("*(can't be previewed)*"),
+        }
+    };
+
+    let skin = MadSkin::default();
+    let text = FmtText::from(&skin, &content, Some(width.max(1)));
+
+    text.to_string().lines().map(String::from).collect()
+}
+
+impl UiCommand {
+    fn load(&self, notebook: &Notebook) -> Result<App> {
+        Ok(App::new(notebook.identifiers(None)?, notebook.tags()?))
+    }
+
+    /// Runs a command, leaving the full-screen UI while it runs, since it may prompt or print,
+    /// e.g. in its hooks.
+    fn run_outside<R: Run>(
+        &self,
+        guard: &mut Option<ScreenGuard>,
+        config: &Config,
+        command: R,
+    ) -> Result<()> {
+        drop(guard.take());
+
+        let result = command.run(config);
+
+        *guard = Some(ScreenGuard::new()?);
+
+        result
+    }
+
+    /// Performs the action, and returns the note to select afterwards.
+    fn perform(
+        &self,
+        guard: &mut Option<ScreenGuard>,
+        notebook: &Notebook,
+        app: &mut App,
+        action: Action,
+    ) -> Result<Option<String>> {
+        let config = notebook.config();
+
+        match action {
+            Action::None | Action::Quit => {}
+            Action::Edit(note_identifier) => {
+                let (name, dir) = split_identifier(&note_identifier);
+
+                self.run_outside(guard, config, EditCommand { name, dir })?;
+            }
+            Action::Tag(note_identifier, tags) => {
+                let (name, dir) = split_identifier(&note_identifier);
+
+                self.run_outside(
+                    guard,
+                    config,
+                    TagCommand {
+                        name,
+                        tags: tags.clone(),
+                        dir,
+                    },
+                )?;
+                app.status = format!("Tagged {} with {}", note_identifier, tags.join(", "));
+            }
+            Action::Untag(note_identifier, tag) => {
+                let (name, dir) = split_identifier(&note_identifier);
+
+                self.run_outside(
+                    guard,
+                    config,
+                    UntagCommand {
+                        name,
+                        tag: tag.clone(),
+                        dir,
+                    },
+                )?;
+                app.status = format!("Removed tag {} from {}", tag, note_identifier);
+            }
+            Action::Rename(note_identifier, new_note_identifier) => {
+                let (name, dir) = split_identifier(&note_identifier);
+                let (new_name, to_dir) = split_identifier(&new_note_identifier);
+
+                // Checked here, so the screen isn't left for a rename which can't be done.
+                if to_dir.is_none() || new_name.is_empty() {
+                    app.status = String::from("Rename failed: the new name must be \"dir/name\"");
+                } else if notebook.exists(&new_note_identifier) {
+                    app.status = format!("Rename failed: {} already exists", new_note_identifier);
+                } else {
+                    self.run_outside(
+                        guard,
+                        config,
+                        MoveCommand {
+                            name,
+                            new_name,
+                            dir,
+                            to_dir,
+                        },
+                    )?;
+                    app.status = format!("Renamed {} to {}", note_identifier, new_note_identifier);
+
+                    return Ok(Some(new_note_identifier));
+                }
+            }
+            Action::Remove(note_identifier) => {
+                let (name, dir) = split_identifier(&note_identifier);
+
+                self.run_outside(guard, config, RemoveCommand { name, dir })?;
+                app.status = format!("Removed {}", note_identifier);
+            }
+            Action::Save => {
+                // Checked here, so the screen isn't left for a save which can't be done.
+                if config.repository.is_none() {
+                    app.status = String::from(
+                        "Can't save without a repository. Please specify a repository in the config file.",
+                    );
+                } else {
+                    self.run_outside(guard, config, SaveCommand { message: None })?;
+                    app.status = String::from("Saved");
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+impl Run for UiCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("ui command {:?}", self);
+
+        if !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+            return Err(GnotesError::InvalidArgs(String::from(
+                "gnotes ui must be run in a terminal",
+            ))
+            .into());
+        }
+
+        let notebook = Notebook::new(config.clone())?;
+        let mut app = self.load(&notebook)?;
+        let mut guard = Some(ScreenGuard::new()?);
+        // The preview is rendered again only when the selected note or the width changes.
+        let mut preview: (Option<String>, u16, Vec<String>) = (None, 0, vec![]);
+
+        loop {
+            let (width, height) = terminal::size()?;
+            let preview_width = width.saturating_sub(draw::list_width(width) + 1);
+            let selected_note = app.selected_note().map(String::from);
+
+            if (&selected_note, preview_width) != (&preview.0, preview.1) {
+                let lines = selected_note
+                    .as_ref()
+                    .map(|note| render_preview(&notebook, note, usize::from(preview_width)))
+                    .unwrap_or_default();
+
+                preview = (selected_note, preview_width, lines);
+            }
+
+            if let Some(guard) = guard.as_mut() {
+                draw::draw(&mut guard.stdout, &app, &preview.2, width, height)?;
+            }
+
+            if let Event::Key(key) = read()? {
+                let action = app.handle_key(key);
+
+                if action == Action::Quit {
+                    break;
+                }
+
+                let changed = action != Action::None;
+                match self.perform(&mut guard, &notebook, &mut app, action) {
+                    Ok(select) if changed => {
+                        app.reload(
+                            notebook.identifiers(None)?,
+                            notebook.tags()?,
+                            select.as_deref(),
+                        );
+                        // The note may have been edited.
+                        preview.0 = None;
+                    }
+                    Ok(_) => {}
+                    Err(error) => app.status = format!("Failed: {}", error),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
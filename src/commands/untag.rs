@@ -1,6 +1,6 @@
-use crate::common::notes::{get_note_identifier, resolve_dir};
-use crate::common::tags::{load_tags, update_tags};
+use crate::common::resolve::resolve_existing_note;
 use crate::config::Config;
+use crate::notebook::Notebook;
 use crate::run::Run;
 use anyhow::Result;
 use clap::Parser;
@@ -22,25 +22,9 @@ impl Run for UntagCommand {
     fn run(&self, config: &Config) -> Result<()> {
         debug!("untag command {:?}", self);
 
-        let dir = resolve_dir(&self.dir);
-        let note_identifier = get_note_identifier("untag", &config.notes_dir, &self.name, &dir);
+        let note_identifier = resolve_existing_note(&config.notes_dir, &self.name, &self.dir)?;
 
-        let mut tags = load_tags(&config.notes_dir)?;
-
-        match tags.get_mut(&self.tag) {
-            Some(tags_set) => {
-                if tags_set.contains(&note_identifier) {
-                    if tags_set.len() > 1 {
-                        tags_set.remove(&note_identifier);
-                    } else {
-                        tags.remove(&self.tag);
-                    }
-                }
-            }
-            _ => {}
-        }
-
-        update_tags(&config.notes_dir, &tags)?;
+        Notebook::new(config.clone())?.untag(&note_identifier, &self.tag)?;
 
         Ok(())
     }
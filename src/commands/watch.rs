@@ -0,0 +1,317 @@
+use crate::common::hooks::{run_hook, Hook};
+use crate::config::Config;
+use crate::error::{GnotesError, GnotesResult};
+use crate::notebook::Notebook;
+use crate::run::Run;
+use anyhow::Result;
+use clap::Parser;
+use log::{debug, info, warn};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::{Component, Path};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How often the debounce and push timers are checked.
+const TICK: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Parser)]
+pub struct WatchCommand {
+    /// The seconds without changes to wait for before committing them
+    #[clap(long, default_value = "5")]
+    pub debounce: u64,
+    /// The seconds between pushes, while changes keep coming
+    #[clap(long, default_value = "600")]
+    pub push_interval: u64,
+    /// The seconds without changes to wait for before pushing the commits
+    #[clap(long, default_value = "60")]
+    pub idle: u64,
+}
+
+enum WatchEvent {
+    Changed(notify::Result<Event>),
+    Stop,
+}
+
+/// The path relative to the notes directory, or `None` for the notes directory itself and for
+/// hidden files, like the tags files and `.git`, which aren't watched.
+fn relative_path(notes_dir: &Path, path: &Path) -> Option<String> {
+    let relative_path = path.strip_prefix(notes_dir).ok()?;
+    let is_hidden = relative_path.components().any(|component| match component {
+        Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+        _ => true,
+    });
+
+    if is_hidden || relative_path.as_os_str().is_empty() {
+        return None;
+    }
+
+    Some(String::from(relative_path.to_string_lossy()))
+}
+
+/// Whether the relative path is the note, or one of its parent directories.
+fn contains(path: &str, note_identifier: &str) -> bool {
+    match note_identifier.strip_prefix(path) {
+        Some(rest) => rest.is_empty() || rest.starts_with('/'),
+        _ => false,
+    }
+}
+
+/// The identifier of the note once `from` was renamed to `to`, if it was renamed with it.
+fn renamed(note_identifier: &str, from: &str, to: &str) -> Option<String> {
+    if contains(from, note_identifier) {
+        Some(format!("{}{}", to, &note_identifier[from.len()..]))
+    } else {
+        None
+    }
+}
+
+/// The changes seen since the last commit, as paths relative to the notes directory.
+#[derive(Debug, Default)]
+struct Changes {
+    paths: BTreeSet<String>,
+    renames: Vec<(String, String)>,
+}
+
+impl Changes {
+    /// Records the paths of the event. Returns whether it changed anything in the notes.
+    fn record(&mut self, notes_dir: &Path, event: &Event) -> bool {
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return false;
+        }
+
+        let paths: Vec<String> = event
+            .paths
+            .iter()
+            .filter_map(|path| relative_path(notes_dir, path))
+            .collect();
+
+        if let (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) =
+            (event.kind, paths.as_slice())
+        {
+            self.renames.push((from.clone(), to.clone()));
+        }
+
+        let changed = !paths.is_empty();
+        self.paths.extend(paths);
+
+        changed
+    }
+
+    fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+/// The notes with tags, which have to follow them when they are renamed or removed.
+fn tagged_notes(notebook: &Notebook) -> GnotesResult<BTreeSet<String>> {
+    let tag_store = notebook.tag_store();
+
+    Ok(tag_store
+        .load_tags()?
+        .into_values()
+        .chain(tag_store.load_inline_tags()?.into_values())
+        .flatten()
+        .collect())
+}
+
+impl WatchCommand {
+    /// Brings the tags up to date with the changes, and commits them.
+    fn save(&self, notebook: &Notebook, changes: &Changes) -> GnotesResult<()> {
+        let tag_store = notebook.tag_store();
+
+        for (from, to) in &changes.renames {
+            // Editors may rename the note to a backup before writing it again, so only
+            // renames that are still there are followed.
+            if notebook.path(from).exists() || !notebook.path(to).exists() {
+                continue;
+            }
+
+            for note_identifier in tagged_notes(notebook)? {
+                if let Some(new_note_identifier) = renamed(&note_identifier, from, to) {
+                    info!(
+                        "moved the tags of {} to {}",
+                        note_identifier, new_note_identifier
+                    );
+
+                    tag_store.rename_note(&note_identifier, &new_note_identifier)?;
+                }
+            }
+        }
+
+        for note_identifier in tagged_notes(notebook)? {
+            let removed = changes
+                .paths
+                .iter()
+                .any(|path| contains(path, &note_identifier));
+
+            if removed && !notebook.exists(&note_identifier) {
+                info!("removed the tags of {}", note_identifier);
+
+                tag_store.remove_note(&note_identifier)?;
+            }
+        }
+
+        // Files at the root of the notes directory aren't notes.
+        for path in changes.paths.iter().filter(|path| path.contains('/')) {
+            if let Some(content) = notebook.read_plain(path) {
+                notebook.refresh_inline_tags(path, &content)?;
+            }
+        }
+
+        run_hook(notebook.config(), Hook::PreSave, "watch", None)?;
+
+        let message = format!(
+            "gnotes watch save\n\n{}",
+            changes
+                .paths
+                .iter()
+                .cloned()
+                .collect::<Vec<String>>()
+                .join("\n")
+        );
+
+        if notebook.commit(&message)? {
+            info!("committed {} changed paths", changes.paths.len());
+        } else {
+            debug!("nothing to commit");
+        }
+
+        Ok(())
+    }
+}
+
+impl Run for WatchCommand {
+    fn run(&self, config: &Config) -> Result<()> {
+        debug!("watch command {:?}", self);
+
+        if config.repository.is_none() {
+            return Err(GnotesError::NoRepository.into());
+        }
+
+        let notebook = Notebook::new(config.clone())?;
+        // The events have the paths of the watched directory, which have to match the notes dir.
+        let notes_dir = config.notes_dir.canonicalize()?;
+        let debounce = Duration::from_secs(self.debounce);
+        let push_interval = Duration::from_secs(self.push_interval);
+        let idle = Duration::from_secs(self.idle);
+
+        let (sender, receiver) = mpsc::channel();
+        let stop_sender = sender.clone();
+        ctrlc::set_handler(move || {
+            stop_sender.send(WatchEvent::Stop).ok();
+        })?;
+
+        let mut watcher = notify::recommended_watcher(move |event| {
+            sender.send(WatchEvent::Changed(event)).ok();
+        })?;
+        watcher.watch(&notes_dir, RecursiveMode::Recursive)?;
+
+        info!("watching {:?}", notes_dir);
+
+        let mut changes = Changes::default();
+        let mut last_change = Instant::now();
+        let mut last_push = Instant::now();
+        let mut unpushed = false;
+
+        loop {
+            match receiver.recv_timeout(TICK) {
+                Ok(WatchEvent::Changed(Ok(event))) => {
+                    if changes.record(&notes_dir, &event) {
+                        debug!("changed {:?}", event);
+
+                        last_change = Instant::now();
+                    }
+                }
+                Ok(WatchEvent::Changed(Err(error))) => warn!("watch error: {}", error),
+                Ok(WatchEvent::Stop) | Err(RecvTimeoutError::Disconnected) => break,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            if !changes.is_empty() && last_change.elapsed() >= debounce {
+                // Failed changes are kept, and tried again after the next debounce.
+                match self.save(&notebook, &changes) {
+                    Ok(()) => {
+                        changes = Changes::default();
+                        unpushed = true;
+                    }
+                    Err(error) => {
+                        warn!("save failed: {}", error);
+
+                        last_change = Instant::now();
+                    }
+                }
+            }
+
+            if unpushed && (last_push.elapsed() >= push_interval || last_change.elapsed() >= idle) {
+                match notebook.push() {
+                    Ok(()) => {
+                        info!("pushed");
+
+                        unpushed = false;
+                    }
+                    Err(error) => warn!("push failed: {}", error),
+                }
+
+                last_push = Instant::now();
+            }
+        }
+
+        info!("stopping");
+
+        if !changes.is_empty() {
+            self.save(&notebook, &changes)?;
+            unpushed = true;
+        }
+
+        if unpushed {
+            notebook.push()?;
+
+            info!("pushed");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("/notes/notes/chores", Some("notes/chores"))]
+    #[case("/notes/work", Some("work"))]
+    #[case("/notes", None)]
+    #[case("/notes/.tags", None)]
+    #[case("/notes/.git/index", None)]
+    #[case("/notes/notes/.chores.swp", None)]
+    #[case("/other/chores", None)]
+    fn relative_path_test(#[case] path: &str, #[case] expected: Option<&str>) {
+        assert_eq!(
+            relative_path(Path::new("/notes"), Path::new(path)),
+            expected.map(String::from)
+        );
+    }
+
+    #[rstest]
+    #[case("notes/chores", "notes/chores", "notes/tasks", Some("notes/tasks"))]
+    #[case("notes/chores", "notes", "archive", Some("archive/chores"))]
+    #[case("notes/chores", "note", "archive", None)]
+    #[case("notes/chores", "work", "archive", None)]
+    fn renamed_test(
+        #[case] note_identifier: &str,
+        #[case] from: &str,
+        #[case] to: &str,
+        #[case] expected: Option<&str>,
+    ) {
+        assert_eq!(
+            renamed(note_identifier, from, to),
+            expected.map(String::from)
+        );
+    }
+}
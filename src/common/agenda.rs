@@ -0,0 +1,227 @@
+use crate::common::markdown::lines_outside_code;
+use crate::common::todos::find_todos;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::HashMap;
+use std::fmt;
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATE_TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AgendaKind {
+    Due,
+    Remind,
+}
+
+impl AgendaKind {
+    fn annotation(&self) -> &str {
+        match self {
+            AgendaKind::Due => "@due(",
+            AgendaKind::Remind => "@remind(",
+        }
+    }
+}
+
+impl fmt::Display for AgendaKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AgendaKind::Due => write!(f, "due"),
+            AgendaKind::Remind => write!(f, "remind"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct AgendaItem {
+    /// The line number, starting from 1
+    pub line: usize,
+    pub kind: AgendaKind,
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub text: String,
+}
+
+struct Annotation {
+    start: usize,
+    end: usize,
+    kind: AgendaKind,
+    date: NaiveDate,
+    time: Option<NaiveTime>,
+}
+
+fn parse_date(value: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let value = value.trim();
+
+    NaiveDateTime::parse_from_str(value, DATE_TIME_FORMAT)
+        .map(|date_time| (date_time.date(), Some(date_time.time())))
+        .or_else(|_| NaiveDate::parse_from_str(value, DATE_FORMAT).map(|date| (date, None)))
+        .ok()
+}
+
+/// Finds the `@due(...)` and `@remind(...)` annotations in a line. Invalid dates are ignored.
+fn find_annotations(line: &str) -> Vec<Annotation> {
+    let mut annotations = vec![];
+
+    for kind in [AgendaKind::Due, AgendaKind::Remind] {
+        for (start, annotation) in line.match_indices(kind.annotation()) {
+            let starts_word = line[..start]
+                .chars()
+                .last()
+                .map(|previous| !previous.is_alphanumeric())
+                .unwrap_or(true);
+            let value_start = start + annotation.len();
+
+            if let (true, Some(value_len)) = (starts_word, line[value_start..].find(')')) {
+                if let Some((date, time)) = parse_date(&line[value_start..value_start + value_len])
+                {
+                    annotations.push(Annotation {
+                        start,
+                        end: value_start + value_len + 1,
+                        kind,
+                        date,
+                        time,
+                    });
+                }
+            }
+        }
+    }
+
+    annotations.sort_by_key(|annotation| annotation.start);
+
+    annotations
+}
+
+/// Removes the annotations from the line, together with the list and checkbox markers.
+fn describe(line: &str, annotations: &[Annotation], is_todo: bool) -> String {
+    let mut text = String::with_capacity(line.len());
+    let mut offset = 0;
+
+    for annotation in annotations {
+        text.push_str(&line[offset..annotation.start]);
+        offset = annotation.end;
+    }
+    text.push_str(&line[offset..]);
+
+    // Todo items are described by their text, which starts after the checkbox.
+    let text = if is_todo {
+        text.split_once(']').map(|(_, rest)| rest).unwrap_or(&text)
+    } else {
+        text.trim_start().trim_start_matches(['-', '*', '+'])
+    };
+
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Finds the dated items in a note, outside of code blocks. Done todo items are skipped.
+pub fn find_agenda_items(content: &str) -> Vec<AgendaItem> {
+    let todos: HashMap<usize, bool> = find_todos(content)
+        .into_iter()
+        .map(|todo| (todo.line, todo.done))
+        .collect();
+
+    let mut items = vec![];
+    for (line_number, line) in lines_outside_code(content) {
+        let todo = todos.get(&line_number);
+
+        if todo.copied().unwrap_or_default() {
+            continue;
+        }
+
+        let annotations = find_annotations(line);
+        if annotations.is_empty() {
+            continue;
+        }
+
+        let text = describe(line, &annotations, todo.is_some());
+
+        for annotation in annotations {
+            items.push(AgendaItem {
+                line: line_number,
+                kind: annotation.kind,
+                date: annotation.date,
+                time: annotation.time,
+                text: text.clone(),
+            });
+        }
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("2026-10-20", Some("2026-10-20"), None)]
+    #[case(" 2026-10-20 09:30 ", Some("2026-10-20"), Some("09:30"))]
+    #[case("2026-13-01", None, None)]
+    #[case("tomorrow", None, None)]
+    fn parse_date_test(
+        #[case] value: &str,
+        #[case] expected_date: Option<&str>,
+        #[case] expected_time: Option<&str>,
+    ) {
+        let parsed = parse_date(value);
+
+        assert_eq!(
+            parsed.map(|(date, _)| date.format(DATE_FORMAT).to_string()),
+            expected_date.map(String::from)
+        );
+        assert_eq!(
+            parsed
+                .and_then(|(_, time)| time)
+                .map(|time| time.format("%H:%M").to_string()),
+            expected_time.map(String::from)
+        );
+    }
+
+    #[test]
+    fn find_agenda_items_test() {
+        let content = "# Plan @due(2026-10-01)
+- [ ] pay rent @due(2026-10-20)
+- [x] done already @due(2026-10-18)
+* call mom @remind(2026-10-21 09:00) and @due(2026-10-22)
+```
+@due(2026-10-20)
+```
+email@due(2026-10-20) @due(someday)";
+
+        let date = |day| NaiveDate::from_ymd(2026, 10, day);
+
+        assert_eq!(
+            find_agenda_items(content),
+            vec![
+                AgendaItem {
+                    line: 1,
+                    kind: AgendaKind::Due,
+                    date: date(1),
+                    time: None,
+                    text: String::from("# Plan"),
+                },
+                AgendaItem {
+                    line: 2,
+                    kind: AgendaKind::Due,
+                    date: date(20),
+                    time: None,
+                    text: String::from("pay rent"),
+                },
+                AgendaItem {
+                    line: 4,
+                    kind: AgendaKind::Remind,
+                    date: date(21),
+                    time: Some(NaiveTime::from_hms(9, 0, 0)),
+                    text: String::from("call mom and"),
+                },
+                AgendaItem {
+                    line: 4,
+                    kind: AgendaKind::Due,
+                    date: date(22),
+                    time: None,
+                    text: String::from("call mom and"),
+                },
+            ]
+        );
+    }
+}
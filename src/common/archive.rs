@@ -0,0 +1,213 @@
+use crate::error::GnotesError;
+use anyhow::Result;
+use clap::ArgEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Component, Path};
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+pub const NOTES_DIR_NAME: &str = "notes";
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq)]
+pub enum ArchiveFormat {
+    Tar,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Picks the format by the file extension, defaulting to tar.
+    pub fn detect(file_path: &Path) -> Self {
+        match file_path.extension() {
+            Some(extension) if extension.eq_ignore_ascii_case("zip") => ArchiveFormat::Zip,
+            _ => ArchiveFormat::Tar,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ManifestNote {
+    pub identifier: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Manifest {
+    pub version: u32,
+    pub notes: Vec<ManifestNote>,
+}
+
+impl Manifest {
+    pub fn new(notes: Vec<ManifestNote>) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            notes,
+        }
+    }
+}
+
+/// Whether a note identifier read from an archive stays inside the notes directory, and names a
+/// note. Hidden files, like `.git` or the tags files, aren't notes, and are never written.
+pub fn is_safe_identifier(note_identifier: &str) -> bool {
+    let path = Path::new(note_identifier);
+
+    path.components().count() > 1
+        && path.components().all(|component| match component {
+            Component::Normal(name) => !name.to_string_lossy().starts_with('.'),
+            _ => false,
+        })
+}
+
+/// Writes the files, given as path and content pairs, to a new archive.
+pub fn write_archive(
+    file_path: &Path,
+    format: ArchiveFormat,
+    files: &BTreeMap<String, Vec<u8>>,
+) -> Result<()> {
+    let file = File::create(file_path)?;
+
+    match format {
+        ArchiveFormat::Tar => {
+            let mut builder = tar::Builder::new(file);
+
+            for (path, content) in files {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(content.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+
+                builder.append_data(&mut header, path, content.as_slice())?;
+            }
+
+            builder.finish()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut writer = zip::ZipWriter::new(file);
+
+            for (path, content) in files {
+                writer.start_file(path, zip::write::FileOptions::default())?;
+                writer.write_all(content)?;
+            }
+
+            writer.finish()?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads all the files in the archive, keyed by their path.
+pub fn read_archive(file_path: &Path, format: ArchiveFormat) -> Result<BTreeMap<String, Vec<u8>>> {
+    let file = File::open(file_path)?;
+    let mut files = BTreeMap::new();
+
+    match format {
+        ArchiveFormat::Tar => {
+            let mut archive = tar::Archive::new(file);
+
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+
+                let path = String::from(entry.path()?.to_string_lossy());
+                let mut content = vec![];
+                entry.read_to_end(&mut content)?;
+
+                files.insert(path, content);
+            }
+        }
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(file)?;
+
+            for index in 0..archive.len() {
+                let mut entry = archive.by_index(index)?;
+
+                if !entry.is_file() {
+                    continue;
+                }
+
+                let path = String::from(entry.name());
+                let mut content = vec![];
+                entry.read_to_end(&mut content)?;
+
+                files.insert(path, content);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+pub fn read_manifest(files: &BTreeMap<String, Vec<u8>>) -> Result<Manifest> {
+    let manifest = files.get(MANIFEST_FILE_NAME).ok_or_else(|| {
+        GnotesError::InvalidArchive(format!("'{}' not found", MANIFEST_FILE_NAME))
+    })?;
+
+    let manifest: Manifest = serde_json::from_slice(manifest).map_err(|error| {
+        GnotesError::InvalidArchive(format!("bad '{}': {}", MANIFEST_FILE_NAME, error))
+    })?;
+
+    if manifest.version != MANIFEST_VERSION {
+        return Err(GnotesError::InvalidArchive(format!(
+            "unsupported version '{}'",
+            manifest.version
+        ))
+        .into());
+    }
+
+    Ok(manifest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+    use std::path::PathBuf;
+    use tempdir::TempDir;
+
+    #[rstest]
+    #[case("notes/chores", true)]
+    #[case("work/project/plan", true)]
+    #[case("chores", false)]
+    #[case("/etc/passwd", false)]
+    #[case("notes/../../escape", false)]
+    #[case(".git/config", false)]
+    #[case(".git/hooks/post-commit", false)]
+    #[case(".hooks/pre-save", false)]
+    #[case("notes/.tags", false)]
+    fn is_safe_identifier_test(#[case] note_identifier: &str, #[case] expected: bool) {
+        assert_eq!(is_safe_identifier(note_identifier), expected);
+    }
+
+    #[rstest]
+    #[case("notes.zip", ArchiveFormat::Zip)]
+    #[case("notes.ZIP", ArchiveFormat::Zip)]
+    #[case("notes.tar", ArchiveFormat::Tar)]
+    #[case("notes", ArchiveFormat::Tar)]
+    fn detect_format_test(#[case] file_path: PathBuf, #[case] expected: ArchiveFormat) {
+        assert_eq!(ArchiveFormat::detect(&file_path), expected);
+    }
+
+    #[rstest]
+    #[case(ArchiveFormat::Tar)]
+    #[case(ArchiveFormat::Zip)]
+    fn archive_roundtrip(#[case] format: ArchiveFormat) -> Result<()> {
+        let temp_dir = TempDir::new("gnotes_archive_test")?;
+        let file_path = temp_dir.path().join("archive");
+        let files = BTreeMap::from([
+            (String::from("manifest.json"), b"{}".to_vec()),
+            (String::from("notes/work/plan"), b"content".to_vec()),
+        ]);
+
+        write_archive(&file_path, format, &files)?;
+
+        assert_eq!(read_archive(&file_path, format)?, files);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::GnotesError;
+use anyhow::{anyhow, Context, Result};
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use std::env;
+
+pub const ENCRYPTED_NOTE_HEADER: &str = "gnotes-encrypted:v1";
+const PASSPHRASE_ENV_VAR: &str = "GNOTES_PASSPHRASE";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+pub fn is_encrypted(content: &str) -> bool {
+    content.starts_with(ENCRYPTED_NOTE_HEADER)
+}
+
+/// Reads the passphrase from `GNOTES_PASSPHRASE`, or prompts for it on the terminal.
+pub fn read_passphrase() -> Result<String> {
+    match env::var(PASSPHRASE_ENV_VAR) {
+        Ok(passphrase) => Ok(passphrase),
+        _ => Ok(rpassword::prompt_password("Passphrase: ")?),
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Key::default();
+
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive key: {}", e))?;
+
+    Ok(key)
+}
+
+/// Encrypts the note content, returning an armored text that is safe to commit.
+pub fn encrypt(content: &str, passphrase: &str) -> Result<String> {
+    let mut salt = [0u8; SALT_LENGTH];
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, &salt)?);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), content.as_bytes())
+        .map_err(|_| anyhow!("Failed to encrypt note"))?;
+
+    let mut payload = Vec::with_capacity(SALT_LENGTH + NONCE_LENGTH + ciphertext.len());
+    payload.extend_from_slice(&salt);
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&ciphertext);
+
+    Ok(format!(
+        "{}\n{}\n",
+        ENCRYPTED_NOTE_HEADER,
+        STANDARD.encode(payload)
+    ))
+}
+
+pub fn decrypt(content: &str, passphrase: &str) -> Result<String> {
+    let encoded = content
+        .strip_prefix(ENCRYPTED_NOTE_HEADER)
+        .context("Note is not encrypted")?;
+    let payload = STANDARD
+        .decode(encoded.trim())
+        .map_err(|_| GnotesError::DecryptionFailed)?;
+
+    if payload.len() < SALT_LENGTH + NONCE_LENGTH {
+        return Err(GnotesError::DecryptionFailed.into());
+    }
+
+    let (salt, rest) = payload.split_at(SALT_LENGTH);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LENGTH);
+
+    let cipher = ChaCha20Poly1305::new(&derive_key(passphrase, salt)?);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| GnotesError::DecryptionFailed)?;
+
+    Ok(String::from_utf8(plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() -> Result<()> {
+        let encrypted = encrypt("secret content\n", "passphrase")?;
+
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("secret content"));
+        assert_eq!(decrypt(&encrypted, "passphrase")?, "secret content\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn decrypt_with_wrong_passphrase() -> Result<()> {
+        let encrypted = encrypt("secret content\n", "passphrase")?;
+
+        assert!(decrypt(&encrypted, "wrong").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn decrypt_tampered_content() -> Result<()> {
+        let encrypted = encrypt("secret content\n", "passphrase")?;
+        let mut tampered: Vec<char> = encrypted.chars().collect();
+        let index = ENCRYPTED_NOTE_HEADER.len() + 40;
+        tampered[index] = if tampered[index] == 'A' { 'B' } else { 'A' };
+        let tampered: String = tampered.into_iter().collect();
+
+        assert!(decrypt(&tampered, "passphrase").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn decrypt_corrupted_content() {
+        for content in [
+            format!("{}\nnot base64!\n", ENCRYPTED_NOTE_HEADER),
+            format!("{}\nAAAA\n", ENCRYPTED_NOTE_HEADER),
+        ] {
+            let error: GnotesError = decrypt(&content, "passphrase").unwrap_err().into();
+
+            assert_eq!(error.exit_code(), 17);
+        }
+    }
+}
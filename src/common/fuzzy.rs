@@ -0,0 +1,130 @@
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 8;
+const WORD_START_BONUS: i64 = 12;
+const GAP_PENALTY: i64 = 1;
+
+fn is_word_start(previous: Option<char>) -> bool {
+    previous
+        .map(|previous| !previous.is_alphanumeric())
+        .unwrap_or(true)
+}
+
+/// Scores how well the candidate matches the query, where the query characters must appear in
+/// the candidate in order, ignoring case. Consecutive matches and matches at the start of words
+/// (after `/`, `-`, `_`, etc.) score higher. Returns `None` if the candidate doesn't match.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let mut query_chars = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+    let mut gap = 0;
+
+    for c in candidate.chars() {
+        let expected = match query_chars.peek() {
+            Some(expected) => *expected,
+            _ => break,
+        };
+
+        if c.to_lowercase().eq(std::iter::once(expected)) {
+            score += MATCH_SCORE;
+
+            if previous_matched {
+                score += CONSECUTIVE_BONUS;
+            }
+            if is_word_start(previous) {
+                score += WORD_START_BONUS;
+            }
+
+            score -= gap * GAP_PENALTY;
+            gap = 0;
+            previous_matched = true;
+            query_chars.next();
+        } else {
+            // Leading characters don't count as a gap.
+            if score > 0 {
+                gap += 1;
+            }
+            previous_matched = false;
+        }
+
+        previous = Some(c);
+    }
+
+    match query_chars.peek() {
+        Some(_) => None,
+        // Shorter candidates rank higher among equal matches.
+        _ => Some(score * 1000 - candidate.chars().count() as i64),
+    }
+}
+
+/// Filters the candidates which match the query, best matches first.
+/// Candidates with an equal score keep their original order.
+pub fn fuzzy_filter<'a, T: AsRef<str>>(query: &str, candidates: &'a [T]) -> Vec<&'a T> {
+    let mut matches: Vec<(i64, &T)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            fuzzy_score(query, candidate.as_ref()).map(|score| (score, candidate))
+        })
+        .collect();
+
+    matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("", "notes/chores", true)]
+    #[case("chr", "notes/chores", true)]
+    #[case("NC", "notes/chores", true)]
+    #[case("work stand", "work/standup", true)]
+    #[case("rc", "notes/chores", false)]
+    #[case("choresx", "notes/chores", false)]
+    fn fuzzy_score_matches_test(
+        #[case] query: &str,
+        #[case] candidate: &str,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(fuzzy_score(query, candidate).is_some(), expected);
+    }
+
+    #[test]
+    fn fuzzy_filter_test() {
+        let candidates = vec![
+            "notes/chores-archive",
+            "work/standup",
+            "notes/chores",
+            "notes/choir",
+            "notes/church",
+        ];
+
+        assert_eq!(
+            fuzzy_filter("chores", &candidates),
+            vec![&"notes/chores", &"notes/chores-archive"]
+        );
+        assert_eq!(
+            fuzzy_filter("ch", &candidates),
+            vec![
+                &"notes/choir",
+                &"notes/chores",
+                &"notes/church",
+                &"notes/chores-archive"
+            ]
+        );
+        assert_eq!(
+            fuzzy_filter("su", &candidates),
+            vec![&"work/standup", &"notes/church"]
+        );
+    }
+}
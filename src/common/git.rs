@@ -1,11 +1,21 @@
-use anyhow::{Context, Result};
-use git2::{Cred, IndexAddOption, PushOptions, RemoteCallbacks, Repository, Signature};
+use crate::common::lock::is_lock_file;
+use crate::common::tags::{INLINE_TAGS_FILE_NAME, TAGS_FILE_NAME};
+use crate::error::{GnotesError, GnotesResult};
+use crate::store::is_database_file;
+use git2::{
+    Commit, Cred, ErrorClass, ErrorCode, IndexAddOption, PushOptions, RemoteCallbacks, Repository,
+    Signature,
+};
+use std::cell::RefCell;
+use std::fs;
 use std::path::Path;
 
-const GNOTES_GIT_USER_NAME: &'static str = "gnotes";
-const GNOTES_GIT_EMAIL: &'static str = "gnotes@gnotes.com";
+const GNOTES_GIT_USER_NAME: &str = "gnotes";
+const GNOTES_GIT_EMAIL: &str = "gnotes@gnotes.com";
+const MERGE_DRIVER_NAME: &str = "gnotes-tags";
+const GIT_ATTRIBUTES_FILE_NAME: &str = ".gitattributes";
 
-fn open_repository(notes_path: &Path, remote: &str) -> Result<Repository> {
+fn open_repository(notes_path: &Path, remote: &str) -> GnotesResult<Repository> {
     let repository = match Repository::open(notes_path) {
         Ok(repository) => repository,
         Err(_) => {
@@ -21,74 +31,141 @@ fn open_repository(notes_path: &Path, remote: &str) -> Result<Repository> {
     Ok(repository)
 }
 
-pub fn auth_callbacks(ssh_file_path: &Path) -> RemoteCallbacks {
+/// Merges the tags files with `gnotes merge-tags`, rather than line by line. The driver is set
+/// in the repository config, which stays local, and assigned to the files in `.gitattributes`,
+/// which is saved with the notes.
+pub fn register_merge_driver(repository: &Repository) -> GnotesResult<()> {
+    let mut config = repository.config()?;
+    config.set_str(
+        &format!("merge.{}.name", MERGE_DRIVER_NAME),
+        "gnotes tags merge",
+    )?;
+    config.set_str(
+        &format!("merge.{}.driver", MERGE_DRIVER_NAME),
+        "gnotes merge-tags %O %A %B",
+    )?;
+
+    let workdir = match repository.workdir() {
+        Some(workdir) => workdir,
+        _ => return Ok(()),
+    };
+    let attributes_file_path = workdir.join(GIT_ATTRIBUTES_FILE_NAME);
+    let mut attributes = fs::read_to_string(&attributes_file_path).unwrap_or_default();
+    let mut changed = false;
+
+    for file_name in [TAGS_FILE_NAME, INLINE_TAGS_FILE_NAME] {
+        let line = format!("{} merge={}", file_name, MERGE_DRIVER_NAME);
+
+        if !attributes.lines().any(|existing| existing == line) {
+            if !attributes.is_empty() && !attributes.ends_with('\n') {
+                attributes.push('\n');
+            }
+            attributes.push_str(&line);
+            attributes.push('\n');
+            changed = true;
+        }
+    }
+
+    if changed {
+        fs::write(&attributes_file_path, attributes)?;
+    }
+
+    Ok(())
+}
+
+pub fn auth_callbacks(ssh_file_path: &Path) -> RemoteCallbacks<'_> {
     let mut callbacks = RemoteCallbacks::new();
 
     // TODO: This part is not covered in the clone/save tests.
     callbacks.credentials(|_url, username_from_url, _allowed_types| {
-        Cred::ssh_key(
-            username_from_url.expect("Failed to extract username from git repository"),
-            None,
-            ssh_file_path,
-            None,
-        )
+        let username = username_from_url.ok_or_else(|| {
+            git2::Error::new(
+                ErrorCode::Auth,
+                ErrorClass::Ssh,
+                "Failed to extract username from git repository",
+            )
+        })?;
+
+        Cred::ssh_key(username, None, ssh_file_path, None)
     });
 
     callbacks
 }
 
-pub fn commit_and_push(
-    notes_path: &Path,
-    ssh_file_path: &Path,
-    remote: &str,
-    message: &str,
-) -> Result<()> {
+/// Commits all the changes in the notes directory, including deleted notes. Returns whether
+/// anything was committed, as nothing is when there are no changes.
+pub fn commit(notes_path: &Path, remote: &str, message: &str) -> GnotesResult<bool> {
     let repository = open_repository(notes_path, remote)?;
-
-    let remotes_list = repository.remotes()?;
-    let remote_name = remotes_list.get(0).context("Failed to find remote")?;
-    let mut remote = repository.find_remote(remote_name)?;
+    register_merge_driver(&repository)?;
 
     let mut index = repository.index()?;
-    index.add_all(["."].iter(), IndexAddOption::CHECK_PATHSPEC, None)?;
+    // The tags are saved as JSON, rather than as the database of the SQLite store, and the lock
+    // only matters locally.
+    index.add_all(
+        ["."].iter(),
+        IndexAddOption::CHECK_PATHSPEC,
+        Some(&mut |path: &Path, _: &[u8]| i32::from(is_database_file(path) || is_lock_file(path))),
+    )?;
+    index.update_all(["."].iter(), None)?;
     index.write()?;
 
-    let tree_id = index.write_tree()?;
+    let tree = repository.find_tree(index.write_tree()?)?;
+    let parent = match repository.head().ok().and_then(|h| h.target()) {
+        Some(parent) => Some(repository.find_commit(parent)?),
+        _ => None,
+    };
+
+    if let Some(parent) = &parent {
+        if parent.tree_id() == tree.id() {
+            return Ok(false);
+        }
+    }
 
     let signature = Signature::now(GNOTES_GIT_USER_NAME, GNOTES_GIT_EMAIL)?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+
+    repository.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+
+    Ok(true)
+}
 
-    match repository.head().ok().and_then(|h| h.target()) {
-        Some(parent) => {
-            let parent = repository
-                .find_commit(parent)
-                .context("Failed to find commit for parent")?;
-
-            repository.commit(
-                Some("HEAD"),
-                &signature,
-                &signature,
-                &message,
-                &repository.find_tree(tree_id)?,
-                &[&parent],
-            )?;
-        }
-        _ => {
-            repository.commit(
-                Some("HEAD"),
-                &signature,
-                &signature,
-                &message,
-                &repository.find_tree(tree_id)?,
-                &[],
-            )?;
+/// Pushes the commits to the remote of the notes repository.
+pub fn push(notes_path: &Path, ssh_file_path: &Path, remote: &str) -> GnotesResult<()> {
+    let repository = open_repository(notes_path, remote)?;
+
+    let remotes_list = repository.remotes()?;
+    let remote_name = remotes_list
+        .get(0)
+        .ok_or_else(|| GnotesError::Git(String::from("Failed to find remote")))?;
+    let mut remote = repository.find_remote(remote_name)?;
+
+    // The remote reports rejected references here, rather than failing the push.
+    let rejection = RefCell::new(None);
+    let mut callbacks = auth_callbacks(ssh_file_path);
+    callbacks.push_update_reference(|reference, status| {
+        if let Some(status) = status {
+            *rejection.borrow_mut() = Some(format!("{}: {}", reference, status));
         }
-    };
+
+        Ok(())
+    });
 
     let mut push_options = PushOptions::new();
-    push_options.remote_callbacks(auth_callbacks(ssh_file_path));
+    push_options.remote_callbacks(callbacks);
 
     // TODO: find the ref dynamically?
     remote.push::<&str>(&["refs/heads/main"], Some(&mut push_options))?;
 
-    Ok(())
+    let rejection = rejection.borrow().clone();
+    match rejection {
+        Some(rejection) => Err(GnotesError::PushRejected(rejection)),
+        _ => Ok(()),
+    }
 }
@@ -0,0 +1,127 @@
+use crate::common::markdown::lines_outside_code;
+use std::collections::BTreeSet;
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-' || c == '/'
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+
+    if !(1..=6).contains(&hashes) {
+        return false;
+    }
+
+    let rest = &line[hashes..];
+
+    rest.is_empty() || rest.starts_with(char::is_whitespace)
+}
+
+/// Removes inline code spans, so `#tags` inside them are ignored.
+fn strip_inline_code(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+
+    for (index, part) in line.split('`').enumerate() {
+        if index % 2 == 0 {
+            result.push_str(part);
+        } else {
+            result.push(' ');
+        }
+    }
+
+    result
+}
+
+fn find_hashtags_in_line(line: &str, hashtags: &mut BTreeSet<String>) {
+    let line = strip_inline_code(line);
+    let mut previous: Option<char> = None;
+    let mut before_previous: Option<char> = None;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        // The anchor of a Markdown link, e.g. `[see](#setup)`, isn't a tag.
+        let is_link_anchor = previous == Some('(') && before_previous == Some(']');
+        let starts_tag = c == '#'
+            && !is_link_anchor
+            && previous
+                .map(|previous| !is_tag_char(previous) && previous != '#' && previous != '&')
+                .unwrap_or(true);
+
+        if starts_tag {
+            let tag: String = line[index + 1..]
+                .chars()
+                .take_while(|c| is_tag_char(*c))
+                .collect();
+            let tag = tag.trim_end_matches('/');
+
+            // Like in most Markdown tools, a number such as `#1` isn't a tag.
+            if !tag.is_empty() && !tag.chars().all(|c| c.is_ascii_digit() || c == '/') {
+                hashtags.insert(String::from(tag));
+            }
+
+            for _ in 0..tag.chars().count() {
+                chars.next();
+            }
+        }
+
+        before_previous = previous;
+        previous = Some(c);
+    }
+}
+
+/// Finds the inline `#hashtags` in a note, skipping headings and code.
+pub fn find_hashtags(content: &str) -> BTreeSet<String> {
+    let mut hashtags = BTreeSet::new();
+
+    for (_, line) in lines_outside_code(content) {
+        let trimmed = line.trim_start();
+
+        // Indented code blocks.
+        if line.starts_with("    ") || line.starts_with('\t') || is_heading(trimmed) {
+            continue;
+        }
+
+        find_hashtags_in_line(line, &mut hashtags);
+    }
+
+    hashtags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_hashtags_test() {
+        let content = "# Heading #nottag
+## Another #heading
+#oncall and #project/alpha, also(#paren), but not [see](#setup)
+issue #123 and a#b and &#39; and ##double
+`#inline` code
+```
+#code
+```
+~~~rust
+#code
+~~~
+    #indented
+#trailing/ #with-dash #with_underscore";
+
+        assert_eq!(
+            find_hashtags(content).into_iter().collect::<Vec<String>>(),
+            vec![
+                "oncall",
+                "paren",
+                "project/alpha",
+                "trailing",
+                "with-dash",
+                "with_underscore"
+            ]
+        );
+    }
+
+    #[test]
+    fn find_hashtags_unclosed_code_block() {
+        assert!(find_hashtags("```\n#code").is_empty());
+    }
+}
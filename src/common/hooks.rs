@@ -0,0 +1,110 @@
+use crate::config::Config;
+use crate::error::{GnotesError, GnotesResult};
+use log::{debug, warn};
+use std::process::Command;
+
+/// The directory of the hook scripts which come with the notebook, only run when trusted.
+const NOTES_HOOKS_DIR_NAME: &str = ".hooks";
+
+/// The points around the commands where hooks are run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hook {
+    PreSave,
+    PostNew,
+    PostAdd,
+    PostTag,
+    PostRemove,
+}
+
+impl Hook {
+    /// The name of the hook, which is the name of its script and of its config entry.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::PreSave => "pre-save",
+            Hook::PostNew => "post-new",
+            Hook::PostAdd => "post-add",
+            Hook::PostTag => "post-tag",
+            Hook::PostRemove => "post-remove",
+        }
+    }
+
+    /// Whether the hook runs before the command, which is then aborted when the hook fails.
+    pub fn is_pre(&self) -> bool {
+        self.name().starts_with("pre-")
+    }
+}
+
+/// The commands of the hook: the script in the `.hooks` directory of the notes directory, when
+/// `trust_notes_hooks` is set, the script in the configured `hooks_dir`, then the shell command
+/// configured under `[hooks]`. The notes directory is synced with git, so its scripts may come
+/// from anyone with access to the repository, and aren't run unless trusted.
+fn hook_commands(config: &Config, hook: Hook) -> Vec<Command> {
+    let notes_hooks_dir = config.notes_dir.join(NOTES_HOOKS_DIR_NAME);
+    let hooks_dirs = [
+        Some(&notes_hooks_dir).filter(|_| config.trust_notes_hooks),
+        config.hooks_dir.as_ref(),
+    ];
+    let mut commands = vec![];
+
+    for hooks_dir in hooks_dirs.into_iter().flatten() {
+        let script_path = hooks_dir.join(hook.name());
+
+        if script_path.is_file() {
+            commands.push(Command::new(script_path));
+        }
+    }
+
+    if let Some(shell_command) = config.hooks.get(hook.name()) {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(shell_command);
+
+        commands.push(command);
+    }
+
+    commands
+}
+
+/// Runs the hook of the command in the notes directory, with the note, when there is one, in
+/// `GNOTES_NOTE_IDENTIFIER` and `GNOTES_NOTE_PATH`. A `pre-` hook which fails, or can't be
+/// started, fails with `HookFailed`, while a `post-` hook is only logged, as the command
+/// already ran.
+pub fn run_hook(
+    config: &Config,
+    hook: Hook,
+    command_name: &str,
+    note_identifier: Option<&str>,
+) -> GnotesResult<()> {
+    for mut command in hook_commands(config, hook) {
+        command
+            .current_dir(&config.notes_dir)
+            .env("GNOTES_HOOK", hook.name())
+            .env("GNOTES_COMMAND", command_name)
+            .env("GNOTES_NOTES_DIR", &config.notes_dir);
+
+        if let Some(note_identifier) = note_identifier {
+            command
+                .env("GNOTES_NOTE_IDENTIFIER", note_identifier)
+                .env("GNOTES_NOTE_PATH", config.notes_dir.join(note_identifier));
+        }
+
+        debug!("running hook {} {:?}", hook.name(), command);
+
+        let status = match command.status() {
+            Ok(status) if status.success() => continue,
+            Ok(status) => status.to_string(),
+            Err(error) => format!("error: {}", error),
+        };
+        let error = GnotesError::HookFailed {
+            hook: String::from(hook.name()),
+            status,
+        };
+
+        if hook.is_pre() {
+            return Err(error);
+        }
+
+        warn!("{}", error);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use crate::common::notes::resolve_dir;
+use std::path::MAIN_SEPARATOR;
+
+const LINK_START: &str = "[[";
+const LINK_END: &str = "]]";
+
+/// A `[[target]]` link found in a note body.
+#[derive(Debug, PartialEq)]
+pub struct Link<'a> {
+    /// The byte range of the whole link, including the brackets.
+    pub start: usize,
+    pub end: usize,
+    pub target: &'a str,
+}
+
+pub fn find_links(content: &str) -> Vec<Link<'_>> {
+    let mut links = vec![];
+    let mut offset = 0;
+
+    while let Some(start) = content[offset..].find(LINK_START) {
+        let start = offset + start;
+        let target_start = start + LINK_START.len();
+
+        match content[target_start..].find(LINK_END) {
+            Some(length) => {
+                let target = content[target_start..target_start + length].trim();
+                let end = target_start + length + LINK_END.len();
+
+                if !target.is_empty() && !target.contains('\n') {
+                    links.push(Link { start, end, target });
+                }
+
+                offset = end;
+            }
+            _ => break,
+        }
+    }
+
+    links
+}
+
+/// Resolves a link target to a note identifier, the same way `--dir` and `name` are resolved.
+pub fn resolve_link(target: &str) -> String {
+    if target.contains('/') || target.contains(MAIN_SEPARATOR) {
+        String::from(target)
+    } else {
+        String::from(resolve_dir(&None).join(target).to_string_lossy())
+    }
+}
+
+/// The identifiers of the notes linked from the content, in order of appearance.
+pub fn find_linked_notes(content: &str) -> Vec<String> {
+    let mut note_identifiers: Vec<String> = vec![];
+
+    for link in find_links(content) {
+        let note_identifier = resolve_link(link.target);
+
+        if !note_identifiers.contains(&note_identifier) {
+            note_identifiers.push(note_identifier);
+        }
+    }
+
+    note_identifiers
+}
+
+/// Formats a link to the note, using the short `[[name]]` form for notes in the default directory.
+pub fn format_link(note_identifier: &str) -> String {
+    let default_dir_prefix = String::from(resolve_dir(&None).join("").to_string_lossy());
+
+    match note_identifier.strip_prefix(&default_dir_prefix) {
+        Some(name) if !name.contains('/') => format!("{}{}{}", LINK_START, name, LINK_END),
+        _ => format!("{}{}{}", LINK_START, note_identifier, LINK_END),
+    }
+}
+
+/// Replaces each link for which `f` returns a value, given the link's note identifier.
+pub fn map_links<F>(content: &str, f: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut result = String::with_capacity(content.len());
+    let mut offset = 0;
+
+    for link in find_links(content) {
+        if let Some(replacement) = f(&resolve_link(link.target)) {
+            result.push_str(&content[offset..link.start]);
+            result.push_str(&replacement);
+            offset = link.end;
+        }
+    }
+
+    result.push_str(&content[offset..]);
+
+    result
+}
+
+/// Rewrites the links that point to `from` so they point to `to`. Other links are left untouched.
+pub fn rewrite_links(content: &str, from: &str, to: &str) -> String {
+    map_links(content, |note_identifier| {
+        if note_identifier == from {
+            Some(format_link(to))
+        } else {
+            None
+        }
+    })
+}
+
+/// Replaces links with a distinct Markdown rendering, for displaying a note.
+pub fn render_links(content: &str) -> String {
+    map_links(content, |note_identifier| {
+        Some(format!("*→ {}*", note_identifier))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("chores", "notes/chores")]
+    #[case("work/standup", "work/standup")]
+    fn resolve_link_test(#[case] target: &str, #[case] expected: &str) {
+        assert_eq!(resolve_link(target), expected);
+    }
+
+    #[test]
+    fn find_linked_notes_test() {
+        let content = "see [[chores]] and [[work/standup]], again [[ chores ]]\n[[]] [[unclosed";
+
+        assert_eq!(
+            find_linked_notes(content),
+            vec![String::from("notes/chores"), String::from("work/standup")]
+        );
+    }
+
+    #[test]
+    fn rewrite_links_test() {
+        let content = "see [[chores]], [[notes/chores]] and [[work/standup]]";
+
+        assert_eq!(
+            rewrite_links(content, "notes/chores", "home/chores"),
+            "see [[home/chores]], [[home/chores]] and [[work/standup]]"
+        );
+        assert_eq!(
+            rewrite_links(content, "notes/chores", "notes/todo"),
+            "see [[todo]], [[todo]] and [[work/standup]]"
+        );
+    }
+
+    #[test]
+    fn render_links_test() {
+        assert_eq!(render_links("see [[chores]]."), "see *→ notes/chores*.");
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::Result;
+use log::debug;
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+
+const LOCK_FILE_NAME: &str = ".gnotes.lock";
+
+/// Whether the path, relative to the notes directory, is the lock file, which isn't saved to git.
+pub fn is_lock_file(path: &Path) -> bool {
+    path == Path::new(LOCK_FILE_NAME)
+}
+
+/// Writes the file through a temporary file which is renamed over it, so a crash never leaves it
+/// half written. A symlink is written through, to its target. The file keeps its permissions, but
+/// as it's replaced, it gets a new creation time.
+pub fn write_atomically(path: &Path, content: &[u8]) -> Result<()> {
+    let path = if path.is_symlink() {
+        path.canonicalize()?
+    } else {
+        path.to_path_buf()
+    };
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_file_path = path.with_file_name(format!(".{}.{}.tmp", file_name, process::id()));
+
+    let result = write_temp_file(&temp_file_path, &path, content)
+        .and_then(|_| Ok(fs::rename(&temp_file_path, &path)?));
+
+    if result.is_err() {
+        fs::remove_file(&temp_file_path).ok();
+    }
+
+    result
+}
+
+/// Writes the content to the temporary file, with the permissions of the file it replaces.
+fn write_temp_file(temp_file_path: &Path, path: &Path, content: &[u8]) -> Result<()> {
+    let mut file = File::create(temp_file_path)?;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+
+    file.write_all(content)?;
+    file.sync_all()?;
+
+    Ok(())
+}
+
+/// An exclusive advisory lock on the notes directory, so concurrent gnotes don't overwrite each
+/// other's changes. Nested calls to `run` share the lock, which is released by the outermost one.
+#[derive(Debug)]
+pub struct NotebookLock {
+    notes_dir: PathBuf,
+    state: RefCell<(usize, Option<File>)>,
+}
+
+impl NotebookLock {
+    pub fn new(notes_dir: &Path) -> Self {
+        Self {
+            notes_dir: notes_dir.to_path_buf(),
+            state: RefCell::new((0, None)),
+        }
+    }
+
+    fn acquire(&self) -> Result<()> {
+        let mut state = self.state.borrow_mut();
+
+        if state.0 == 0 {
+            let lock_file_path = self.notes_dir.join(LOCK_FILE_NAME);
+
+            debug!("Locking {:?}", lock_file_path);
+
+            fs::create_dir_all(&self.notes_dir)?;
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(lock_file_path)?;
+            file.lock()?;
+
+            state.1 = Some(file);
+        }
+
+        state.0 += 1;
+
+        Ok(())
+    }
+
+    fn release(&self) {
+        let mut state = self.state.borrow_mut();
+
+        state.0 -= 1;
+
+        // Closing the file releases the lock.
+        if state.0 == 0 {
+            state.1 = None;
+        }
+    }
+
+    /// Runs `f` while holding the lock, waiting for other gnotes to release it first.
+    pub fn run<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        E: From<anyhow::Error>,
+        F: FnOnce() -> Result<T, E>,
+    {
+        self.acquire()?;
+
+        let result = f();
+
+        self.release();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn write_atomically_test() {
+        let dir = TempDir::new("gnotes").unwrap();
+        let path = dir.path().join(".tags");
+
+        write_atomically(&path, b"{}").unwrap();
+        write_atomically(&path, b"{\"home\":[]}").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{\"home\":[]}");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomically_through_symlink_test() {
+        let dir = TempDir::new("gnotes").unwrap();
+        let target_path = dir.path().join("target");
+        let link_path = dir.path().join("link");
+
+        fs::write(&target_path, "hello").unwrap();
+        std::os::unix::fs::symlink(&target_path, &link_path).unwrap();
+
+        write_atomically(&link_path, b"bye").unwrap();
+
+        assert!(link_path.is_symlink());
+        assert_eq!(fs::read_to_string(&target_path).unwrap(), "bye");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomically_keeps_permissions_test() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("gnotes").unwrap();
+        let path = dir.path().join("note");
+
+        fs::write(&path, "hello").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomically(&path, b"bye").unwrap();
+
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+    }
+
+    #[test]
+    fn nested_run_test() {
+        let dir = TempDir::new("gnotes").unwrap();
+        let lock = NotebookLock::new(dir.path());
+        let other = NotebookLock::new(dir.path());
+
+        lock.run(|| lock.run(|| Ok::<_, anyhow::Error>(())))
+            .unwrap();
+        // Released by the outermost run, so it can be taken again.
+        other.run(|| Ok::<_, anyhow::Error>(())).unwrap();
+
+        assert!(dir.path().join(LOCK_FILE_NAME).exists());
+    }
+}
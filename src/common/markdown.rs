@@ -0,0 +1,126 @@
+const CODE_FENCES: [&str; 2] = ["```", "~~~"];
+
+/// Where a line is, relative to the fenced code blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Text,
+    /// The fence which opens a code block
+    FenceStart,
+    Code,
+    /// The fence which closes a code block
+    FenceEnd,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkdownLine<'a> {
+    pub kind: LineKind,
+    /// The line number, starting from 1
+    pub number: usize,
+    /// The line, without its line ending
+    pub text: &'a str,
+    /// The byte offset where the line starts
+    pub start: usize,
+    /// The byte offset where the next line starts
+    pub end: usize,
+}
+
+impl<'a> MarkdownLine<'a> {
+    /// The info string of an opening fence, e.g. the language in "```rust".
+    pub fn fence_info(&self) -> Option<&'a str> {
+        if self.kind != LineKind::FenceStart {
+            return None;
+        }
+
+        let trimmed = self.text.trim_start();
+
+        CODE_FENCES
+            .iter()
+            .find(|fence| trimmed.starts_with(*fence))
+            .map(|fence| trimmed[fence.len()..].trim())
+    }
+}
+
+/// Splits the content into lines, telling which ones are in fenced code blocks. A code block
+/// which isn't closed runs until the end of the content.
+pub fn markdown_lines(content: &str) -> Vec<MarkdownLine<'_>> {
+    let mut lines = vec![];
+    let mut code_fence: Option<&str> = None;
+    let mut offset = 0;
+
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let start = offset;
+        offset += line.len();
+
+        let text = line.strip_suffix('\n').unwrap_or(line);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let trimmed = text.trim_start();
+
+        let kind = match code_fence {
+            Some(fence) if trimmed.starts_with(fence) => {
+                code_fence = None;
+
+                LineKind::FenceEnd
+            }
+            Some(_) => LineKind::Code,
+            _ => match CODE_FENCES.iter().find(|fence| trimmed.starts_with(*fence)) {
+                Some(fence) => {
+                    code_fence = Some(fence);
+
+                    LineKind::FenceStart
+                }
+                _ => LineKind::Text,
+            },
+        };
+
+        lines.push(MarkdownLine {
+            kind,
+            number: index + 1,
+            text,
+            start,
+            end: offset,
+        });
+    }
+
+    lines
+}
+
+/// Lists the lines of the content, skipping fenced code blocks, together with their line numbers.
+pub fn lines_outside_code(content: &str) -> Vec<(usize, &str)> {
+    markdown_lines(content)
+        .into_iter()
+        .filter(|line| line.kind == LineKind::Text)
+        .map(|line| (line.number, line.text))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markdown_lines_test() {
+        let content = "text\n```rust\ncode\n```\n~~~\n```\nstill code\r\n";
+        let lines = markdown_lines(content);
+
+        assert_eq!(
+            lines
+                .iter()
+                .map(|line| line.kind)
+                .collect::<Vec<LineKind>>(),
+            vec![
+                LineKind::Text,
+                LineKind::FenceStart,
+                LineKind::Code,
+                LineKind::FenceEnd,
+                LineKind::FenceStart,
+                LineKind::Code,
+                LineKind::Code,
+            ]
+        );
+        assert_eq!(lines[1].fence_info(), Some("rust"));
+        assert_eq!(lines[4].fence_info(), Some(""));
+        assert_eq!(lines[6].text, "still code");
+        assert_eq!(lines[6].end, content.len());
+        assert_eq!(lines_outside_code(content), vec![(1, "text")]);
+    }
+}
@@ -1,4 +1,18 @@
+pub mod agenda;
+pub mod archive;
+pub mod crypto;
+pub mod fuzzy;
 pub mod git;
+pub mod hashtags;
+pub mod hooks;
+pub mod links;
+pub mod lock;
+pub mod markdown;
 pub mod notes;
+pub mod pager;
+pub mod resolve;
+pub mod screen;
+pub mod sections;
 pub mod tags;
+pub mod todos;
 pub mod writers;
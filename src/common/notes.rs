@@ -1,12 +1,8 @@
 use anyhow::Result;
-use log::debug;
 use std::fs;
-use std::fs::OpenOptions;
-use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process;
 
-const DEFAULT_NOTES_DIR: &'static str = "notes";
+const DEFAULT_NOTES_DIR: &str = "notes";
 
 pub fn resolve_dir(dir: &Option<PathBuf>) -> PathBuf {
     match dir {
@@ -15,42 +11,50 @@ pub fn resolve_dir(dir: &Option<PathBuf>) -> PathBuf {
     }
 }
 
-pub fn write_note(note_parent_dir: &Path, note_file_name: &str, content: &str) -> Result<()> {
-    let note_file_path = note_parent_dir.join(note_file_name);
+/// Lists the identifiers of all notes under the notes directory, skipping hidden entries.
+pub fn list_note_identifiers(notes_dir: &Path) -> Result<Vec<String>> {
+    let mut note_identifiers = vec![];
+    let mut pending = vec![notes_dir.to_path_buf()];
 
-    debug!("Writing message '{}' to {:?}", content, note_file_path);
+    while let Some(dir) = pending.pop() {
+        if !dir.is_dir() {
+            continue;
+        }
 
-    fs::create_dir_all(note_parent_dir)?;
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(note_file_path)?;
+        for entry in fs::read_dir(&dir)?.filter_map(|entry| entry.ok()) {
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
 
-    writeln!(file, "{}", content)?;
+            let path = entry.path();
 
-    Ok(())
-}
+            if path.is_dir() {
+                pending.push(path);
+            } else if dir != notes_dir {
+                note_identifiers.push(String::from(
+                    path.strip_prefix(notes_dir)?.to_string_lossy(),
+                ));
+            }
+        }
+    }
 
-fn assert_note_exists(command: &str, notes_dir: &Path, note_relative_path: &Path) {
-    let note_file_path = notes_dir.join(&note_relative_path);
+    note_identifiers.sort();
 
-    if !note_file_path.exists() {
-        eprintln!(
-            "{} failed: file '{}' not found",
-            command,
-            String::from(note_file_path.to_string_lossy())
-        );
-
-        process::exit(1);
-    }
+    Ok(note_identifiers)
 }
 
-pub fn get_note_identifier(command: &str, notes_dir: &Path, name: &str, dir: &Path) -> String {
-    let note_relative_path = dir.join(name);
+/// Splits a note identifier into its name and directory.
+pub fn split_identifier(note_identifier: &str) -> (String, Option<PathBuf>) {
+    let path = Path::new(note_identifier);
 
-    assert_note_exists(command, notes_dir, &note_relative_path);
-
-    String::from(note_relative_path.to_string_lossy())
+    (
+        path.file_name()
+            .map(|name| String::from(name.to_string_lossy()))
+            .unwrap_or_default(),
+        path.parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .map(Path::to_path_buf),
+    )
 }
 
 #[cfg(test)]
@@ -64,4 +68,19 @@ mod tests {
     fn resolve_dir_test(#[case] dir: Option<PathBuf>, #[case] expected: PathBuf) {
         assert_eq!(resolve_dir(&dir), expected);
     }
+
+    #[rstest]
+    #[case("notes/chores", "chores", Some("notes"))]
+    #[case("work/project/plan", "plan", Some("work/project"))]
+    #[case("chores", "chores", None)]
+    fn split_identifier_test(
+        #[case] note_identifier: &str,
+        #[case] expected_name: &str,
+        #[case] expected_dir: Option<&str>,
+    ) {
+        assert_eq!(
+            split_identifier(note_identifier),
+            (String::from(expected_name), expected_dir.map(PathBuf::from))
+        );
+    }
 }
@@ -0,0 +1,146 @@
+use crate::common::screen::ScreenGuard;
+use anyhow::Result;
+use crossterm::cursor::MoveTo;
+use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::queue;
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, Clear, ClearType};
+use log::debug;
+use std::env;
+use std::io::{self, ErrorKind, IsTerminal, Stdout, Write};
+use std::process::{Command, Stdio};
+
+/// Makes `less` keep the colors, and exit right away when the output fits the screen.
+const DEFAULT_LESS_OPTIONS: &str = "FRX";
+
+/// Writes the output through `$PAGER`, or through the built-in pager when `$PAGER` isn't set.
+/// The output is written directly when the pager is disabled or stdout isn't a terminal.
+pub fn page(output: &str, use_pager: bool) -> Result<()> {
+    if !use_pager || !io::stdout().is_terminal() {
+        print!("{}", output);
+
+        return Ok(());
+    }
+
+    match env::var("PAGER") {
+        Ok(pager) => run_external_pager(&pager, output),
+        _ => run_builtin_pager(output),
+    }
+}
+
+fn run_external_pager(pager: &str, output: &str) -> Result<()> {
+    let mut args = pager.split_whitespace();
+
+    let program = match args.next() {
+        Some(program) if program != "cat" => program,
+        _ => {
+            print!("{}", output);
+
+            return Ok(());
+        }
+    };
+
+    let mut command = Command::new(program);
+    command.args(args).stdin(Stdio::piped());
+
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", DEFAULT_LESS_OPTIONS);
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            debug!("Failed to run the pager '{}': {}", pager, error);
+
+            print!("{}", output);
+
+            return Ok(());
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        match stdin.write_all(output.as_bytes()) {
+            // The pager was closed before reading the whole output.
+            Err(error) if error.kind() == ErrorKind::BrokenPipe => {}
+            result => result?,
+        }
+    }
+
+    child.wait()?;
+
+    Ok(())
+}
+
+fn draw(stdout: &mut Stdout, lines: &[&str], top: usize, page_height: usize) -> Result<()> {
+    queue!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+
+    for line in lines.iter().skip(top).take(page_height) {
+        queue!(
+            stdout,
+            Print(line),
+            SetAttribute(Attribute::Reset),
+            Print("\r\n")
+        )?;
+    }
+
+    let bottom = (top + page_height).min(lines.len());
+    let status = if bottom == lines.len() {
+        String::from("(END)")
+    } else {
+        format!("lines {}-{} of {}", top + 1, bottom, lines.len())
+    };
+
+    queue!(
+        stdout,
+        MoveTo(0, page_height as u16),
+        SetAttribute(Attribute::Reverse),
+        Print(status),
+        SetAttribute(Attribute::Reset)
+    )?;
+
+    stdout.flush()?;
+
+    Ok(())
+}
+
+/// A minimal `less`-like pager: arrows or `j`/`k` scroll by a line, space/`b` or page up/down
+/// by a page, `g`/`G` jump to the top or bottom, and `q` quits.
+fn run_builtin_pager(output: &str) -> Result<()> {
+    let lines: Vec<&str> = output.lines().collect();
+    let (_, height) = terminal::size()?;
+    let mut page_height = usize::from(height.max(2)) - 1;
+
+    if lines.len() <= page_height {
+        print!("{}", output);
+
+        return Ok(());
+    }
+
+    let mut guard = ScreenGuard::new()?;
+    let mut top = 0;
+
+    loop {
+        let max_top = lines.len().saturating_sub(page_height);
+        top = top.min(max_top);
+
+        draw(&mut guard.stdout, &lines, top, page_height)?;
+
+        match read()? {
+            Event::Key(KeyEvent { code, modifiers }) => match code {
+                KeyCode::Char('q') | KeyCode::Esc => break,
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => break,
+                KeyCode::Down | KeyCode::Enter | KeyCode::Char('j') => top += 1,
+                KeyCode::Up | KeyCode::Char('k') => top = top.saturating_sub(1),
+                KeyCode::PageDown | KeyCode::Char(' ') | KeyCode::Char('f') => top += page_height,
+                KeyCode::PageUp | KeyCode::Char('b') => top = top.saturating_sub(page_height),
+                KeyCode::Home | KeyCode::Char('g') => top = 0,
+                KeyCode::End | KeyCode::Char('G') => top = max_top,
+                _ => {}
+            },
+            Event::Resize(_, height) => page_height = usize::from(height.max(2)) - 1,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
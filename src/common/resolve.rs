@@ -0,0 +1,182 @@
+use crate::common::fuzzy::fuzzy_filter;
+use crate::common::notes::{list_note_identifiers, resolve_dir};
+use crate::error::GnotesError;
+use anyhow::Result;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+const MAX_SUGGESTIONS: usize = 10;
+
+/// Finds the notes matching the name, best matches first. The note at `dir/name` matches on its
+/// own, then notes with that exact file name in any directory, then fuzzy matches. Only the
+/// notes under `dir` are searched when it's given.
+pub fn find_matching_notes(
+    notes_dir: &Path,
+    name: &str,
+    dir: &Option<PathBuf>,
+) -> Result<Vec<String>> {
+    Ok(match_notes(list_note_identifiers(notes_dir)?, name, dir))
+}
+
+/// Like `find_matching_notes`, but over the given note identifiers.
+pub fn match_notes(
+    mut note_identifiers: Vec<String>,
+    name: &str,
+    dir: &Option<PathBuf>,
+) -> Vec<String> {
+    let note_identifier = String::from(resolve_dir(dir).join(name).to_string_lossy());
+
+    if note_identifiers.contains(&note_identifier) {
+        return vec![note_identifier];
+    }
+
+    if let Some(dir) = dir {
+        let note_identifier_prefix = String::from(dir.join("").to_string_lossy());
+
+        note_identifiers
+            .retain(|note_identifier| note_identifier.starts_with(&note_identifier_prefix));
+    }
+
+    let same_name: Vec<String> = note_identifiers
+        .iter()
+        .filter(|note_identifier| {
+            Path::new(note_identifier)
+                .file_name()
+                .map(|file_name| file_name == name)
+                .unwrap_or(false)
+        })
+        .cloned()
+        .collect();
+
+    if !same_name.is_empty() {
+        return same_name;
+    }
+
+    fuzzy_filter(name, &note_identifiers)
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+/// Asks which of the notes to use, by number. Fails with `AmbiguousName` when nothing is chosen.
+fn choose_note(name: &str, matches: &[String]) -> Result<String> {
+    let matches = &matches[..matches.len().min(MAX_SUGGESTIONS)];
+
+    eprintln!("'{}' matches several notes:", name);
+    for (index, note_identifier) in matches.iter().enumerate() {
+        eprintln!("  {}) {}", index + 1, note_identifier);
+    }
+    eprint!("Choose a note [1-{}]: ", matches.len());
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+
+    match answer.trim().parse::<usize>() {
+        Ok(number) if (1..=matches.len()).contains(&number) => Ok(matches[number - 1].clone()),
+        _ => Err(GnotesError::AmbiguousName {
+            name: String::from(name),
+            matches: matches.to_vec(),
+        }
+        .into()),
+    }
+}
+
+/// Resolves a partial or fuzzy name to a note identifier, or `None` if no note matches.
+/// When several notes match, asks which one to use, or fails with the best matches as
+/// suggestions when not running in a terminal.
+pub fn resolve_note(notes_dir: &Path, name: &str, dir: &Option<PathBuf>) -> Result<Option<String>> {
+    let matches = find_matching_notes(notes_dir, name, dir)?;
+
+    let note_identifier = match matches.len() {
+        0 => return Ok(None),
+        1 => matches[0].clone(),
+        _ if io::stdin().is_terminal() && io::stderr().is_terminal() => {
+            choose_note(name, &matches)?
+        }
+        _ => {
+            return Err(GnotesError::AmbiguousName {
+                name: String::from(name),
+                matches: matches.into_iter().take(MAX_SUGGESTIONS).collect(),
+            }
+            .into())
+        }
+    };
+
+    Ok(Some(note_identifier))
+}
+
+/// Like `resolve_note`, but fails when no note matches.
+pub fn resolve_existing_note(
+    notes_dir: &Path,
+    name: &str,
+    dir: &Option<PathBuf>,
+) -> Result<String> {
+    match resolve_note(notes_dir, name, dir)? {
+        Some(note_identifier) => Ok(note_identifier),
+        _ => Err(GnotesError::NoteNotFound(String::from(
+            notes_dir
+                .join(resolve_dir(dir))
+                .join(name)
+                .to_string_lossy(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    fn setup_notes(notes: &[&str]) -> TempDir {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+
+        for note in notes {
+            let note_file_path = notes_dir.path().join(note);
+
+            fs::create_dir_all(note_file_path.parent().unwrap()).unwrap();
+            fs::write(note_file_path, "").unwrap();
+        }
+
+        notes_dir
+    }
+
+    #[test]
+    fn find_matching_notes_test() {
+        let notes_dir = setup_notes(&[
+            "notes/chores",
+            "notes/choir",
+            "notes/plan",
+            "work/plan",
+            "work/report",
+        ]);
+        let notes_dir = notes_dir.path();
+
+        assert_eq!(
+            find_matching_notes(notes_dir, "plan", &None).unwrap(),
+            vec!["notes/plan"]
+        );
+        assert_eq!(
+            find_matching_notes(notes_dir, "plan", &Some(PathBuf::from("work"))).unwrap(),
+            vec!["work/plan"]
+        );
+        assert_eq!(
+            find_matching_notes(notes_dir, "report", &None).unwrap(),
+            vec!["work/report"]
+        );
+        assert_eq!(
+            find_matching_notes(notes_dir, "chres", &None).unwrap(),
+            vec!["notes/chores"]
+        );
+        assert_eq!(
+            find_matching_notes(notes_dir, "cho", &None).unwrap(),
+            vec!["notes/choir", "notes/chores"]
+        );
+        assert_eq!(
+            find_matching_notes(notes_dir, "cho", &Some(PathBuf::from("work"))).unwrap(),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use crossterm::cursor::{Hide, Show};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use std::io::{self, Stdout};
+
+/// Switches to a full-screen, raw mode terminal, and restores it when dropped, including on errors.
+pub struct ScreenGuard {
+    pub stdout: Stdout,
+}
+
+impl ScreenGuard {
+    pub fn new() -> Result<Self> {
+        let mut stdout = io::stdout();
+
+        enable_raw_mode()?;
+        execute!(stdout, EnterAlternateScreen, DisableLineWrap, Hide)?;
+
+        Ok(Self { stdout })
+    }
+}
+
+impl Drop for ScreenGuard {
+    fn drop(&mut self) {
+        let _ = execute!(self.stdout, Show, EnableLineWrap, LeaveAlternateScreen);
+        let _ = disable_raw_mode();
+    }
+}
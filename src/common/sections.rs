@@ -0,0 +1,210 @@
+use crate::common::markdown::{markdown_lines, LineKind};
+
+#[derive(Debug, PartialEq)]
+pub struct Heading {
+    pub level: usize,
+    pub title: String,
+    /// The line number, starting from 1
+    pub line: usize,
+    /// The byte offset where the heading line starts
+    pub start: usize,
+    /// The byte offset where the section ends, which is the start of the next heading of the same
+    /// or a higher level, or the end of the content
+    pub end: usize,
+}
+
+/// Parses an ATX heading, such as `## Action items ##`, into its level and title.
+fn parse_heading(line: &str) -> Option<(usize, String)> {
+    let trimmed = line.trim_end();
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let title = rest.trim();
+    // A closing sequence of `#`s is not part of the title.
+    let title = match title.trim_end_matches('#') {
+        stripped if stripped.is_empty() || stripped.ends_with(char::is_whitespace) => {
+            stripped.trim_end()
+        }
+        _ => title,
+    };
+
+    Some((level, String::from(title)))
+}
+
+/// Finds the headings in the content, skipping code blocks.
+pub fn find_headings(content: &str) -> Vec<Heading> {
+    let mut headings: Vec<Heading> = vec![];
+
+    for line in markdown_lines(content) {
+        if line.kind != LineKind::Text {
+            continue;
+        }
+
+        // Headings can be indented by up to 3 spaces.
+        let trimmed = line.text.trim_start();
+        let indent = line.text.len() - trimmed.len();
+
+        if indent > 3 {
+            continue;
+        }
+
+        if let Some((level, title)) = parse_heading(trimmed) {
+            for heading in headings.iter_mut().rev() {
+                if heading.end == content.len() && heading.level >= level {
+                    heading.end = line.start;
+                }
+            }
+
+            headings.push(Heading {
+                level,
+                title,
+                line: line.number,
+                start: line.start,
+                end: content.len(),
+            });
+        }
+    }
+
+    headings
+}
+
+/// Finds the first section matching the query, either by its title, such as `Action items`, or
+/// by its level and title, such as `## Action items`. Titles are matched regardless of case.
+pub fn find_section<'a>(headings: &'a [Heading], query: &str) -> Option<&'a Heading> {
+    let (level, title) = match parse_heading(query.trim()) {
+        Some((level, title)) => (Some(level), title),
+        _ => (None, String::from(query.trim())),
+    };
+
+    headings.iter().find(|heading| {
+        level.map(|level| level == heading.level).unwrap_or(true)
+            && heading.title.to_lowercase() == title.to_lowercase()
+    })
+}
+
+/// Inserts the text at the end of the section, before the blank lines which separate it from the
+/// next section.
+pub fn insert_under(content: &str, heading: &Heading, text: &str) -> String {
+    let section = &content[heading.start..heading.end];
+    let position = heading.start + section.trim_end().len();
+
+    let mut inserted = String::with_capacity(content.len() + text.len() + 2);
+    inserted.push_str(&content[..position]);
+    inserted.push('\n');
+    inserted.push_str(text);
+
+    if position == content.len() {
+        inserted.push('\n');
+    } else {
+        inserted.push_str(&content[position..]);
+    }
+
+    inserted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    const CONTENT: &str = "# Meeting
+intro
+## Notes ##
+some notes
+```
+## not a heading
+```
+### Details
+details
+
+## Action items
+- call mom
+
+# Appendix #1
+";
+
+    #[rstest]
+    #[case("# Title", Some((1, "Title")))]
+    #[case("###   Spaced   ###", Some((3, "Spaced")))]
+    #[case("## C# tips", Some((2, "C# tips")))]
+    #[case("# Issue #1", Some((1, "Issue #1")))]
+    #[case("##", Some((2, "")))]
+    #[case("#hashtag", None)]
+    #[case("####### Too deep", None)]
+    fn parse_heading_test(#[case] line: &str, #[case] expected: Option<(usize, &str)>) {
+        assert_eq!(
+            parse_heading(line),
+            expected.map(|(level, title)| (level, String::from(title)))
+        );
+    }
+
+    #[test]
+    fn find_headings_test() {
+        let headings = find_headings(CONTENT);
+        let summary: Vec<(usize, &str, usize, &str)> = headings
+            .iter()
+            .map(|heading| {
+                (
+                    heading.level,
+                    heading.title.as_str(),
+                    heading.line,
+                    &CONTENT[heading.start..heading.end],
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    1,
+                    "Meeting",
+                    1,
+                    &CONTENT[..CONTENT.find("# Appendix").unwrap()]
+                ),
+                (
+                    2,
+                    "Notes",
+                    3,
+                    "## Notes ##\nsome notes\n```\n## not a heading\n```\n### Details\ndetails\n\n"
+                ),
+                (3, "Details", 8, "### Details\ndetails\n\n"),
+                (2, "Action items", 11, "## Action items\n- call mom\n\n"),
+                (1, "Appendix #1", 14, "# Appendix #1\n"),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case("action ITEMS", Some(11))]
+    #[case("## Action items", Some(11))]
+    #[case("### Action items", None)]
+    #[case("Missing", None)]
+    fn find_section_test(#[case] query: &str, #[case] expected: Option<usize>) {
+        let headings = find_headings(CONTENT);
+
+        assert_eq!(
+            find_section(&headings, query).map(|heading| heading.line),
+            expected
+        );
+    }
+
+    #[test]
+    fn insert_under_test() {
+        let headings = find_headings(CONTENT);
+
+        let inserted = insert_under(CONTENT, &headings[3], "- buy milk");
+        assert!(inserted.contains("## Action items\n- call mom\n- buy milk\n\n# Appendix #1\n"));
+
+        let inserted = insert_under(CONTENT, &headings[4], "more");
+        assert!(inserted.ends_with("# Appendix #1\nmore\n"));
+    }
+}
@@ -1,31 +1,318 @@
+use crate::common::lock::write_atomically;
+use crate::error::GnotesError;
 use anyhow::Result;
+use log::warn;
 use serde::Serialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
-const TAGS_FILE_NAME: &'static str = ".tags";
+pub const TAGS_FILE_NAME: &str = ".tags";
+pub const INLINE_TAGS_FILE_NAME: &str = ".inline-tags";
+
+pub const TAG_SEPARATOR: char = '/';
 
 pub type Tags = HashMap<String, HashSet<String>>;
 
-pub fn load_tags(notes_dir: &Path) -> Result<Tags> {
-    let tags_file_path = notes_dir.join(TAGS_FILE_NAME);
+/// Whether `tag` is `parent` itself or nested under it, e.g. `project/alpha/backend` under `project`.
+pub fn is_tag_or_descendant(tag: &str, parent: &str) -> bool {
+    match tag.strip_prefix(parent) {
+        Some(rest) => rest.is_empty() || rest.starts_with(TAG_SEPARATOR),
+        _ => false,
+    }
+}
 
-    let tags = if tags_file_path.exists() {
-        let data = fs::read_to_string(&tags_file_path)?;
+/// Collects the notes tagged with `tag`, including notes tagged with its descendants unless `exact` is set.
+pub fn find_tagged_notes<'a>(tags: &'a Tags, tag: &str, exact: bool) -> BTreeSet<&'a String> {
+    tags.iter()
+        .filter(|(candidate, _)| {
+            if exact {
+                candidate.as_str() == tag
+            } else {
+                is_tag_or_descendant(candidate, tag)
+            }
+        })
+        .flat_map(|(_, tags_set)| tags_set.iter())
+        .collect()
+}
 
-        serde_json::from_str::<Tags>(&data)?
-    } else {
-        HashMap::new()
-    };
+/// Recovers the tags of a truncated tags file, by parsing the longest prefix that ends after a
+/// complete tag. Returns `None` when nothing can be recovered.
+fn recover_tags(data: &str) -> Option<Tags> {
+    if data.trim().is_empty() {
+        return Some(HashMap::new());
+    }
 
-    Ok(tags)
+    data.char_indices()
+        .filter(|(_, c)| *c == ']')
+        .map(|(index, _)| index)
+        .rev()
+        .find_map(|index| serde_json::from_str::<Tags>(&format!("{}}}", &data[..=index])).ok())
+}
+
+fn load_tags_file(tags_file_path: &Path) -> Result<Tags> {
+    if !tags_file_path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let data = fs::read_to_string(tags_file_path)?;
+
+    match serde_json::from_str::<Tags>(&data) {
+        Ok(tags) => Ok(tags),
+        Err(error) => match recover_tags(&data) {
+            Some(tags) => {
+                let corrupt_file_path = tags_file_path.with_extension("corrupt");
+
+                warn!(
+                    "{:?} is corrupt ({}). Recovered {} tags, and kept the corrupt file as {:?}",
+                    tags_file_path,
+                    error,
+                    tags.len(),
+                    corrupt_file_path
+                );
+
+                fs::copy(tags_file_path, corrupt_file_path)?;
+
+                Ok(tags)
+            }
+            _ => Err(GnotesError::TagStoreCorrupt {
+                path: String::from(tags_file_path.to_string_lossy()),
+                reason: error.to_string(),
+            }
+            .into()),
+        },
+    }
+}
+
+pub fn load_tags(notes_dir: &Path) -> Result<Tags> {
+    load_tags_file(&notes_dir.join(TAGS_FILE_NAME))
+}
+
+/// Formats the tags as JSON, sorted and with one note per line, so changes to the tags files
+/// make small diffs in git.
+pub fn format_tags<T: Serialize>(tags: &T) -> Result<String> {
+    let tags: BTreeMap<String, BTreeSet<String>> =
+        serde_json::from_value(serde_json::to_value(tags)?)?;
+
+    Ok(serde_json::to_string_pretty(&tags)? + "\n")
 }
 
 pub fn update_tags<T: Serialize>(notes_dir: &Path, tags: &T) -> Result<()> {
-    let tags_file_path = notes_dir.join(TAGS_FILE_NAME);
+    write_atomically(
+        &notes_dir.join(TAGS_FILE_NAME),
+        format_tags(tags)?.as_bytes(),
+    )
+}
+
+/// Loads the tags that were derived from inline `#hashtags`. These are a subset of the tags
+/// returned by `load_tags`, so tags added with `tag` aren't dropped when a hashtag is removed.
+pub fn load_inline_tags(notes_dir: &Path) -> Result<Tags> {
+    load_tags_file(&notes_dir.join(INLINE_TAGS_FILE_NAME))
+}
+
+pub fn update_inline_tags<T: Serialize>(notes_dir: &Path, tags: &T) -> Result<()> {
+    write_atomically(
+        &notes_dir.join(INLINE_TAGS_FILE_NAME),
+        format_tags(tags)?.as_bytes(),
+    )
+}
+
+/// Merges the tags changed on two sides since `base`: a note keeps a tag when both sides have it,
+/// or when one side added it. A tag removed on either side is removed.
+pub fn merge_tags(base: &Tags, ours: &Tags, theirs: &Tags) -> Tags {
+    let empty = HashSet::new();
+    let mut merged = Tags::new();
+
+    for tag in ours.keys().chain(theirs.keys()) {
+        let base_set = base.get(tag).unwrap_or(&empty);
+        let ours_set = ours.get(tag).unwrap_or(&empty);
+        let theirs_set = theirs.get(tag).unwrap_or(&empty);
+
+        let tags_set: HashSet<String> = ours_set
+            .union(theirs_set)
+            .filter(|note_identifier| {
+                (ours_set.contains(*note_identifier) && theirs_set.contains(*note_identifier))
+                    || !base_set.contains(*note_identifier)
+            })
+            .cloned()
+            .collect();
+
+        if !tags_set.is_empty() {
+            merged.insert(tag.clone(), tags_set);
+        }
+    }
+
+    merged
+}
+
+/// Removes the note from a tag, dropping the tag once it has no notes left.
+pub fn remove_from_tag(tags: &mut Tags, tag: &str, note_identifier: &str) {
+    if let Some(tags_set) = tags.get_mut(tag) {
+        tags_set.remove(note_identifier);
+
+        if tags_set.is_empty() {
+            tags.remove(tag);
+        }
+    }
+}
+
+/// Applies the note's hashtags to the tags: new hashtags are added as inline tags, and inline
+/// tags whose hashtag is gone are removed. Tags added with `tag` are left untouched.
+pub fn apply_inline_tags(
+    tags: &mut Tags,
+    inline_tags: &mut Tags,
+    note_identifier: &str,
+    hashtags: &BTreeSet<String>,
+) {
+    let previous: Vec<String> = inline_tags
+        .iter()
+        .filter(|(_, tags_set)| tags_set.contains(note_identifier))
+        .map(|(tag, _)| tag.clone())
+        .collect();
+
+    for tag in previous {
+        if !hashtags.contains(&tag) {
+            remove_from_tag(tags, &tag, note_identifier);
+            remove_from_tag(inline_tags, &tag, note_identifier);
+        }
+    }
+
+    for tag in hashtags {
+        let tags_set = tags.entry(tag.clone()).or_default();
+
+        if tags_set.insert(String::from(note_identifier)) {
+            inline_tags
+                .entry(tag.clone())
+                .or_default()
+                .insert(String::from(note_identifier));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maplit::{hashmap, hashset};
+    use rstest::*;
+
+    #[rstest]
+    #[case("project", "project", true)]
+    #[case("project/alpha", "project", true)]
+    #[case("project/alpha/backend", "project/alpha", true)]
+    #[case("projects", "project", false)]
+    #[case("project", "project/alpha", false)]
+    fn is_tag_or_descendant_test(#[case] tag: &str, #[case] parent: &str, #[case] expected: bool) {
+        assert_eq!(is_tag_or_descendant(tag, parent), expected);
+    }
+
+    #[test]
+    fn find_tagged_notes_includes_descendants() {
+        let tags = hashmap! {
+          String::from("project") => hashset! { String::from("notes/a") },
+          String::from("project/alpha") => hashset! { String::from("notes/b") },
+          String::from("project/alpha/backend") => hashset! { String::from("notes/c"), String::from("notes/b") },
+          String::from("projects") => hashset! { String::from("notes/d") },
+        };
+
+        let results: Vec<&String> = find_tagged_notes(&tags, "project/alpha", false)
+            .into_iter()
+            .collect();
+        assert_eq!(results, vec!["notes/b", "notes/c"]);
+
+        let results: Vec<&String> = find_tagged_notes(&tags, "project/alpha", true)
+            .into_iter()
+            .collect();
+        assert_eq!(results, vec!["notes/b"]);
+    }
+
+    #[test]
+    fn apply_inline_tags_keeps_manual_tags() {
+        let mut tags = hashmap! {
+          String::from("manual") => hashset! { String::from("notes/a") },
+          String::from("old") => hashset! { String::from("notes/a"), String::from("notes/b") },
+        };
+        let mut inline_tags = hashmap! {
+          String::from("old") => hashset! { String::from("notes/a") },
+        };
+
+        let hashtags = BTreeSet::from([String::from("manual"), String::from("new")]);
+        apply_inline_tags(&mut tags, &mut inline_tags, "notes/a", &hashtags);
+
+        assert_eq!(
+            tags,
+            hashmap! {
+              String::from("manual") => hashset! { String::from("notes/a") },
+              String::from("old") => hashset! { String::from("notes/b") },
+              String::from("new") => hashset! { String::from("notes/a") },
+            }
+        );
+        assert_eq!(
+            inline_tags,
+            hashmap! {
+              String::from("new") => hashset! { String::from("notes/a") },
+            }
+        );
+
+        apply_inline_tags(&mut tags, &mut inline_tags, "notes/a", &BTreeSet::new());
+
+        assert_eq!(
+            tags,
+            hashmap! {
+              String::from("manual") => hashset! { String::from("notes/a") },
+              String::from("old") => hashset! { String::from("notes/b") },
+            }
+        );
+        assert!(inline_tags.is_empty());
+    }
+
+    #[test]
+    fn recover_tags_test() {
+        assert_eq!(
+            recover_tags(r#"{"a":["notes/a"],"b":["notes/b","no"#),
+            Some(hashmap! {
+              String::from("a") => hashset! { String::from("notes/a") },
+            })
+        );
+        assert_eq!(recover_tags(""), Some(HashMap::new()));
+        assert_eq!(recover_tags("not json"), None);
+    }
+
+    #[test]
+    fn format_tags_test() {
+        let tags = hashmap! {
+          String::from("work") => hashset! { String::from("work/plan"), String::from("notes/a") },
+          String::from("home") => hashset! { String::from("notes/b") },
+        };
+
+        assert_eq!(
+            format_tags(&tags).unwrap(),
+            "{\n  \"home\": [\n    \"notes/b\"\n  ],\n  \"work\": [\n    \"notes/a\",\n    \"work/plan\"\n  ]\n}\n"
+        );
+    }
 
-    fs::write(tags_file_path, serde_json::to_string(tags)?)?;
+    #[test]
+    fn merge_tags_test() {
+        let base = hashmap! {
+          String::from("kept") => hashset! { String::from("notes/a"), String::from("notes/b") },
+          String::from("removed") => hashset! { String::from("notes/a") },
+        };
+        let ours = hashmap! {
+          String::from("kept") => hashset! { String::from("notes/a"), String::from("notes/c") },
+          String::from("removed") => hashset! { String::from("notes/a") },
+          String::from("ours") => hashset! { String::from("notes/a") },
+        };
+        let theirs = hashmap! {
+          String::from("kept") => hashset! { String::from("notes/a"), String::from("notes/b"), String::from("notes/d") },
+          String::from("theirs") => hashset! { String::from("notes/b") },
+        };
 
-    Ok(())
+        assert_eq!(
+            merge_tags(&base, &ours, &theirs),
+            hashmap! {
+              String::from("kept") => hashset! { String::from("notes/a"), String::from("notes/c"), String::from("notes/d") },
+              String::from("ours") => hashset! { String::from("notes/a") },
+              String::from("theirs") => hashset! { String::from("notes/b") },
+            }
+        );
+    }
 }
@@ -0,0 +1,171 @@
+use crate::common::markdown::lines_outside_code;
+const LIST_MARKERS: [&str; 3] = ["- ", "* ", "+ "];
+
+#[derive(Debug, PartialEq)]
+pub struct Todo {
+    /// The line number, starting from 1
+    pub line: usize,
+    pub done: bool,
+    pub text: String,
+}
+
+/// Finds the byte offset of the checkbox state, e.g. the `x` in `- [x] text`.
+fn find_checkbox(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+
+    let marker_len = LIST_MARKERS
+        .iter()
+        .find(|marker| trimmed.starts_with(*marker))
+        .map(|marker| marker.len())
+        .or_else(|| {
+            // Ordered list items, such as `1. [ ] text`.
+            let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+            let rest = &trimmed[digits..];
+
+            (digits > 0 && (rest.starts_with(". ") || rest.starts_with(") "))).then(|| digits + 2)
+        })?;
+
+    let checkbox = trimmed[marker_len..].get(..3)?;
+    let state = checkbox.chars().nth(1)?;
+
+    if checkbox.starts_with('[') && checkbox.ends_with(']') && " xX".contains(state) {
+        Some(indent + marker_len + 1)
+    } else {
+        None
+    }
+}
+
+/// Finds the Markdown checkbox items, e.g. `- [ ] text`, outside of code blocks.
+pub fn find_todos(content: &str) -> Vec<Todo> {
+    lines_outside_code(content)
+        .into_iter()
+        .filter_map(|(line_number, line)| {
+            find_checkbox(line).map(|offset| Todo {
+                line: line_number,
+                done: &line[offset..offset + 1] != " ",
+                text: String::from(line[offset + 2..].trim()),
+            })
+        })
+        .collect()
+}
+
+/// Toggles the checkbox on the given line, leaving the rest of the content untouched.
+/// Returns `None` if there's no checkbox item on that line.
+pub fn toggle_todo(content: &str, line_number: usize) -> Option<(String, Todo)> {
+    let todo = find_todos(content)
+        .into_iter()
+        .find(|todo| todo.line == line_number)?;
+
+    let line_start: usize = content
+        .split_inclusive('\n')
+        .take(line_number - 1)
+        .map(|line| line.len())
+        .sum();
+    let offset = line_start + find_checkbox(&content[line_start..])?;
+    let state = if todo.done { " " } else { "x" };
+
+    let mut toggled = String::with_capacity(content.len());
+    toggled.push_str(&content[..offset]);
+    toggled.push_str(state);
+    toggled.push_str(&content[offset + 1..]);
+
+    Some((
+        toggled,
+        Todo {
+            done: !todo.done,
+            ..todo
+        },
+    ))
+}
+
+/// Appends an open checkbox item to the end of the content.
+pub fn append_todo(content: &str, text: &str) -> String {
+    let mut appended = String::from(content);
+
+    if !appended.is_empty() && !appended.ends_with('\n') {
+        appended.push('\n');
+    }
+
+    appended.push_str(&format!("- [ ] {}\n", text));
+
+    appended
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::*;
+
+    #[rstest]
+    #[case("- [ ] open", Some(3))]
+    #[case("  * [x] done", Some(5))]
+    #[case("+ [X] done", Some(3))]
+    #[case("12. [ ] ordered", Some(5))]
+    #[case("1) [ ] ordered", Some(4))]
+    #[case("- [] missing state", None)]
+    #[case("- [?] unknown state", None)]
+    #[case("[ ] no marker", None)]
+    #[case("-[ ] no space", None)]
+    #[case("- [ ]", Some(3))]
+    fn find_checkbox_test(#[case] line: &str, #[case] expected: Option<usize>) {
+        assert_eq!(find_checkbox(line), expected);
+    }
+
+    #[test]
+    fn find_todos_test() {
+        let content = "# Todos
+- [ ] buy milk
+- [x] call mom
+```
+- [ ] in code
+```
+not a - [ ] todo
+  - [ ]   nested  ";
+
+        assert_eq!(
+            find_todos(content),
+            vec![
+                Todo {
+                    line: 2,
+                    done: false,
+                    text: String::from("buy milk")
+                },
+                Todo {
+                    line: 3,
+                    done: true,
+                    text: String::from("call mom")
+                },
+                Todo {
+                    line: 8,
+                    done: false,
+                    text: String::from("nested")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn toggle_todo_test() {
+        let content = "intro\r\n- [ ] buy milk\r\n- [x] call mom";
+
+        let (toggled, todo) = toggle_todo(content, 2).unwrap();
+        assert_eq!(toggled, "intro\r\n- [x] buy milk\r\n- [x] call mom");
+        assert!(todo.done);
+
+        let (toggled, todo) = toggle_todo(content, 3).unwrap();
+        assert_eq!(toggled, "intro\r\n- [ ] buy milk\r\n- [ ] call mom");
+        assert!(!todo.done);
+
+        assert_eq!(toggle_todo(content, 1), None);
+        assert_eq!(toggle_todo(content, 4), None);
+    }
+
+    #[rstest]
+    #[case("", "- [ ] text\n")]
+    #[case("intro\n", "intro\n- [ ] text\n")]
+    #[case("intro", "intro\n- [ ] text\n")]
+    fn append_todo_test(#[case] content: &str, #[case] expected: &str) {
+        assert_eq!(append_todo(content, "text"), expected);
+    }
+}
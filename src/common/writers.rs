@@ -1,13 +1,206 @@
-use anyhow::Result;
-use std::fs;
-use std::path::Path;
+use crate::common::links::render_links;
+use crate::common::markdown::{markdown_lines, LineKind};
+use std::sync::OnceLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
 
-pub fn write_as_markdown(notes_dir: &Path, note_identifier: &str) -> Result<()> {
-    let note_file_path = notes_dir.join(note_identifier);
-    let content = fs::read_to_string(note_file_path)?;
+const THEME: &str = "base16-ocean.dark";
+const RESET: &str = "\x1b[0m";
 
-    println!("{}:", note_identifier);
-    termimad::print_text(&content);
+static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
 
-    Ok(())
+#[derive(Debug, PartialEq)]
+enum Block<'a> {
+    Markdown(&'a str),
+    Code {
+        language: &'a str,
+        code: String,
+        /// The block as written, including its fences
+        source: String,
+    },
+}
+
+/// Splits the content into Markdown text and fenced code blocks which have a language tag.
+/// Code blocks without a language tag are left in the Markdown text.
+fn split_code_blocks(content: &str) -> Vec<Block<'_>> {
+    let mut blocks = vec![];
+    let mut markdown_start = 0;
+    // The language of the open code block, when it's highlighted.
+    let mut language: Option<&str> = None;
+    let mut code = String::new();
+    let mut source = String::new();
+
+    for line in markdown_lines(content) {
+        let source_line = &content[line.start..line.end];
+
+        match line.kind {
+            LineKind::FenceStart => {
+                language = line
+                    .fence_info()
+                    .and_then(|info| info.split_whitespace().next());
+
+                if language.is_some() {
+                    if markdown_start < line.start {
+                        blocks.push(Block::Markdown(&content[markdown_start..line.start]));
+                    }
+
+                    source.push_str(source_line);
+                }
+            }
+            LineKind::Code => {
+                if language.is_some() {
+                    source.push_str(source_line);
+                    code.push_str(source_line);
+                }
+            }
+            LineKind::FenceEnd => {
+                if let Some(language) = language.take() {
+                    source.push_str(source_line);
+                    blocks.push(Block::Code {
+                        language,
+                        code: std::mem::take(&mut code),
+                        source: std::mem::take(&mut source),
+                    });
+                    markdown_start = line.end;
+                }
+            }
+            LineKind::Text => {}
+        }
+    }
+
+    match language {
+        // An unclosed code block runs until the end of the content.
+        Some(language) => blocks.push(Block::Code {
+            language,
+            code,
+            source,
+        }),
+        _ => {
+            if markdown_start < content.len() {
+                blocks.push(Block::Markdown(&content[markdown_start..]));
+            }
+        }
+    }
+
+    blocks
+}
+
+fn theme() -> &'static Theme {
+    &THEME_SET.get_or_init(ThemeSet::load_defaults).themes[THEME]
+}
+
+/// Highlights the code for the terminal. Returns `None` if the language isn't known.
+fn highlight(language: &str, code: &str) -> Option<String> {
+    let syntax_set = SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
+    let syntax = syntax_set.find_syntax_by_token(language)?;
+    let mut highlighter = HighlightLines::new(syntax, theme());
+    let mut highlighted = String::with_capacity(code.len());
+
+    for line in LinesWithEndings::from(code) {
+        let ranges = highlighter.highlight_line(line, syntax_set).ok()?;
+
+        highlighted.push_str(&as_24_bit_terminal_escaped(&ranges, false));
+    }
+
+    if !highlighted.ends_with('\n') {
+        highlighted.push('\n');
+    }
+    highlighted.push_str(RESET);
+
+    Some(highlighted)
+}
+
+fn render_markdown(text: &str) -> String {
+    if text.is_empty() {
+        return String::new();
+    }
+
+    termimad::term_text(&render_links(text)).to_string()
+}
+
+/// Renders the note for the terminal, highlighting the fenced code blocks by their language.
+pub fn render_content_as_markdown(note_identifier: &str, content: &str) -> String {
+    let mut output = format!("{}:\n", note_identifier);
+    let mut markdown = String::new();
+
+    for block in split_code_blocks(content) {
+        match block {
+            Block::Markdown(text) => markdown.push_str(text),
+            Block::Code {
+                language,
+                code,
+                source,
+            } => match highlight(language, &code) {
+                Some(highlighted) => {
+                    output.push_str(&render_markdown(&markdown));
+                    output.push_str(&highlighted);
+                    markdown.clear();
+                }
+                _ => markdown.push_str(&source),
+            },
+        }
+    }
+
+    output.push_str(&render_markdown(&markdown));
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_code_blocks_test() {
+        let content = "intro
+```rust
+fn main() {}
+```
+```
+plain
+```
+~~~python extra
+print()";
+
+        assert_eq!(
+            split_code_blocks(content),
+            vec![
+                Block::Markdown("intro\n"),
+                Block::Code {
+                    language: "rust",
+                    code: String::from("fn main() {}\n"),
+                    source: String::from("```rust\nfn main() {}\n```\n"),
+                },
+                Block::Markdown("```\nplain\n```\n"),
+                Block::Code {
+                    language: "python",
+                    code: String::from("print()"),
+                    source: String::from("~~~python extra\nprint()"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight_test() {
+        let highlighted = highlight("rust", "fn main() {}\n").unwrap();
+
+        assert!(highlighted.contains("\x1b[38;2;"));
+        assert!(highlighted.contains("main"));
+        assert!(highlighted.ends_with(RESET));
+
+        assert_eq!(highlight("unknown-language", "code"), None);
+    }
+
+    #[test]
+    fn render_content_as_markdown_unknown_language() {
+        let rendered = render_content_as_markdown("notes/a", "```unknown\nsome code\n```\n");
+
+        assert!(rendered.starts_with("notes/a:\n"));
+        assert!(rendered.contains("some code"));
+        assert!(!rendered.contains("```"));
+    }
 }
@@ -1,36 +1,44 @@
+use crate::error::GnotesError;
 use log::debug;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
-use thiserror::Error;
 
-const GNOTES_DIR_NAME: &'static str = ".gnotes";
-const CONFIG_FILE_NAME: &'static str = ".gnotes.toml";
+const GNOTES_DIR_NAME: &str = ".gnotes";
+const CONFIG_FILE_NAME: &str = ".gnotes.toml";
 
-#[derive(Debug, Error)]
-pub enum ConfigError {
-    #[error("invalid config: '{0}'")]
-    InvalidConfig(String),
-}
-
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct ExternalConfig {
     notes_dir: Option<PathBuf>,
     auto_save: Option<bool>,
     repository: Option<String>,
     ssh_file_path: Option<PathBuf>,
+    inline_tags: Option<bool>,
+    store: Option<StoreKind>,
+    hooks_dir: Option<PathBuf>,
+    trust_notes_hooks: Option<bool>,
+    dirs: Option<HashMap<String, DirConfig>>,
+    hooks: Option<HashMap<String, String>>,
 }
 
-impl Default for ExternalConfig {
-    fn default() -> Self {
-        Self {
-            notes_dir: None,
-            auto_save: None,
-            repository: None,
-            ssh_file_path: None,
-        }
-    }
+/// Where the tags and the note metadata are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoreKind {
+    /// The `.tags` and `.inline-tags` JSON files
+    #[default]
+    Json,
+    /// A SQLite database in the notes directory
+    Sqlite,
+}
+
+/// Per-directory options, configured under a `[dirs.<name>]` table.
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+pub struct DirConfig {
+    #[serde(default)]
+    pub encrypted: bool,
 }
 
 impl ExternalConfig {
@@ -40,40 +48,70 @@ impl ExternalConfig {
             auto_save: self.auto_save.or(other.auto_save),
             repository: self.repository.or(other.repository),
             ssh_file_path: self.ssh_file_path.or(other.ssh_file_path),
+            inline_tags: self.inline_tags.or(other.inline_tags),
+            store: self.store.or(other.store),
+            dirs: self.dirs.or(other.dirs),
+            hooks_dir: self.hooks_dir.or(other.hooks_dir),
+            trust_notes_hooks: self.trust_notes_hooks.or(other.trust_notes_hooks),
+            hooks: self.hooks.or(other.hooks),
         }
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Config {
     pub notes_dir: PathBuf,
     pub auto_save: bool,
     pub repository: Option<String>,
     pub ssh_file_path: PathBuf,
+    pub inline_tags: bool,
+    pub store: StoreKind,
+    /// The directory of the hook scripts, named after their hook. Outside of the notes directory
+    pub hooks_dir: Option<PathBuf>,
+    /// Whether to run the hook scripts in the `.hooks` directory of the notes directory
+    pub trust_notes_hooks: bool,
+    pub dirs: HashMap<String, DirConfig>,
+    /// Shell commands run around the commands, by hook name, e.g. `pre-save`
+    pub hooks: HashMap<String, String>,
 }
 
-fn load_config_from_env() -> ExternalConfig {
-    match envy::prefixed("GNOTES_").from_env::<ExternalConfig>() {
-        Ok(config) => config,
-        _ => ExternalConfig::default(),
+impl Config {
+    /// Whether the directory, or one of its parent directories, is configured as encrypted.
+    pub fn is_encrypted_dir(&self, dir: &Path) -> bool {
+        self.dirs
+            .iter()
+            .any(|(name, dir_config)| dir_config.encrypted && dir.starts_with(name))
     }
 }
 
-fn load_config_from_file(home_dir: &Path) -> ExternalConfig {
+fn load_config_from_env() -> ExternalConfig {
+    envy::prefixed("GNOTES_")
+        .from_env::<ExternalConfig>()
+        .unwrap_or_default()
+}
+
+/// The config of the config file, or the default config when there's no config file.
+fn load_config_from_file(home_dir: &Path) -> Result<ExternalConfig, GnotesError> {
     let config_file_path = home_dir.join(CONFIG_FILE_NAME);
 
-    if let Ok(content) = std::fs::read_to_string(config_file_path) {
-        if let Ok(result) = toml::from_str::<ExternalConfig>(&content) {
-            return result;
-        }
+    match std::fs::read_to_string(&config_file_path) {
+        Ok(content) => toml::from_str::<ExternalConfig>(&content).map_err(|error| {
+            GnotesError::InvalidConfig(format!("{}: {}", config_file_path.display(), error))
+        }),
+        _ => Ok(ExternalConfig::default()),
     }
+}
+
+/// Whether the path is the directory or inside it, once their symlinks are resolved.
+fn is_inside(path: &Path, dir: &Path) -> bool {
+    let canonicalize = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
 
-    ExternalConfig::default()
+    canonicalize(path).starts_with(canonicalize(dir))
 }
 
-pub fn load_config(home_dir: &Path) -> Result<Config, ConfigError> {
+pub fn load_config(home_dir: &Path) -> Result<Config, GnotesError> {
     let env_config = load_config_from_env();
-    let file_config = load_config_from_file(&home_dir);
+    let file_config = load_config_from_file(home_dir)?;
 
     debug!("config loaded from env {:?}", env_config);
     debug!("config loaded from file {:?}", file_config);
@@ -94,17 +132,33 @@ pub fn load_config(home_dir: &Path) -> Result<Config, ConfigError> {
             auto_save: external_config.auto_save.unwrap_or_default(),
             repository: external_config.repository,
             ssh_file_path,
+            inline_tags: external_config.inline_tags.unwrap_or_default(),
+            store: external_config.store.unwrap_or_default(),
+            dirs: external_config.dirs.unwrap_or_default(),
+            hooks_dir: external_config.hooks_dir,
+            trust_notes_hooks: external_config.trust_notes_hooks.unwrap_or_default(),
+            hooks: external_config.hooks.unwrap_or_default(),
         };
 
         if config.auto_save && config.repository.is_none() {
-            Err(ConfigError::InvalidConfig(String::from(
+            Err(GnotesError::InvalidConfig(String::from(
                 "repository is mandatory when auto_save is enabled",
             )))
+        } else if config
+            .hooks_dir
+            .as_ref()
+            .is_some_and(|hooks_dir| is_inside(hooks_dir, &config.notes_dir))
+        {
+            // The notes directory is synced with git, so its scripts may come from anyone with
+            // access to the repository.
+            Err(GnotesError::InvalidConfig(String::from(
+                "hooks_dir can't be inside notes_dir",
+            )))
         } else {
             Ok(config)
         }
     } else {
-        Err(ConfigError::InvalidConfig(format!(
+        Err(GnotesError::InvalidConfig(format!(
             "Failed to canonicalize ssh_file_path: '{:?}'",
             ssh_file_path,
         )))
@@ -132,7 +186,7 @@ mod tests {
     fn write_config_file(home_dir: &TempDir, content: String) -> Result<()> {
         let config_file = home_dir.path().join(".gnotes.toml");
 
-        fs::write(config_file, format!("{}", content)).context("Failed to write config file")?;
+        fs::write(config_file, content).context("Failed to write config file")?;
 
         Ok(())
     }
@@ -169,7 +223,7 @@ mod tests {
 
         let config = load_config(home_dir.path()).context("Couldn't load config")?;
 
-        assert_eq!(config.auto_save, false);
+        assert!(!config.auto_save);
 
         Ok(())
     }
@@ -358,6 +412,133 @@ mod tests {
         )
     }
 
+    #[test]
+    #[serial]
+    fn test_inline_tags_default() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        let config = load_config(home_dir.path()).context("Couldn't load config")?;
+
+        assert!(!config.inline_tags);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_inline_tags_from_config_file() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        write_config_file(&home_dir, String::from("inline_tags = true"))?;
+
+        let config = load_config(home_dir.path()).context("Couldn't load config")?;
+
+        assert!(config.inline_tags);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_store_default() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        let config = load_config(home_dir.path()).context("Couldn't load config")?;
+
+        assert_eq!(config.store, StoreKind::Json);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_store_from_config_file() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        write_config_file(&home_dir, String::from("store = \"sqlite\""))?;
+
+        let config = load_config(home_dir.path()).context("Couldn't load config")?;
+
+        assert_eq!(config.store, StoreKind::Sqlite);
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_hooks_from_config_file() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        write_config_file(
+            &home_dir,
+            String::from("[hooks]\npre-save = \"./lint.sh\"\n"),
+        )?;
+
+        let config = load_config(home_dir.path()).context("Couldn't load config")?;
+
+        assert_eq!(
+            config.hooks.get("pre-save").map(String::as_str),
+            Some("./lint.sh")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_hooks_dir_inside_notes_dir() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        write_config_file(
+            &home_dir,
+            format!(
+                "notes_dir = {:?}\nhooks_dir = {:?}\n",
+                home_dir.path().join("notes"),
+                home_dir.path().join("notes").join(".hooks")
+            ),
+        )?;
+
+        assert!(matches!(
+            load_config(home_dir.path()),
+            Err(GnotesError::InvalidConfig(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_dirs_default() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        let config = load_config(home_dir.path()).context("Couldn't load config")?;
+
+        assert!(config.dirs.is_empty());
+        assert!(!config.is_encrypted_dir(Path::new("notes")));
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_encrypted_dir_from_config_file() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        write_config_file(
+            &home_dir,
+            String::from("[dirs.secrets]\nencrypted = true\n\n[dirs.notes]\n"),
+        )?;
+
+        let config = load_config(home_dir.path()).context("Couldn't load config")?;
+
+        assert!(config.is_encrypted_dir(Path::new("secrets")));
+        assert!(config.is_encrypted_dir(Path::new("secrets/work")));
+        assert!(!config.is_encrypted_dir(Path::new("secrets-old")));
+        assert!(!config.is_encrypted_dir(Path::new("notes")));
+
+        Ok(())
+    }
+
     #[test]
     #[serial]
     fn test_auto_save_on_without_repository() -> Result<()> {
@@ -367,7 +548,22 @@ mod tests {
 
         assert!(matches!(
             load_config(home_dir.path()),
-            Err(ConfigError::InvalidConfig(_))
+            Err(GnotesError::InvalidConfig(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    #[serial]
+    fn test_invalid_config_file() -> Result<()> {
+        let home_dir = create_temp_dir()?;
+
+        write_config_file(&home_dir, String::from("auto_save = \"yes\""))?;
+
+        assert!(matches!(
+            load_config(home_dir.path()),
+            Err(GnotesError::InvalidConfig(_))
         ));
 
         Ok(())
@@ -384,7 +580,7 @@ mod tests {
 
         assert!(matches!(
             load_config(home_dir.path()),
-            Err(ConfigError::InvalidConfig(_))
+            Err(GnotesError::InvalidConfig(_))
         ));
 
         Ok(())
@@ -0,0 +1,207 @@
+use clap::ArgEnum;
+use serde_json::json;
+use std::io;
+use thiserror::Error;
+
+/// The errors gnotes reports. Each one exits with its own code, see `exit_code`.
+#[derive(Debug, Error)]
+pub enum GnotesError {
+    #[error("{0}")]
+    InvalidArgs(String),
+    #[error("'{name}' matches several notes. Did you mean:\n  {}", .matches.join("\n  "))]
+    AmbiguousName { name: String, matches: Vec<String> },
+    /// The note file, by its path
+    #[error("file '{0}' not found")]
+    NoteNotFound(String),
+    /// The note file, by its path
+    #[error("file '{0}' already exists")]
+    NoteExists(String),
+    #[error("section '{0}' not found")]
+    SectionNotFound(String),
+    #[error("tag '{0}' not found")]
+    TagNotFound(String),
+    #[error("tag '{0}' already exists. Use 'tags merge' instead")]
+    TagExists(String),
+    #[error("no item found at '{0}'")]
+    TodoNotFound(String),
+    #[error("invalid config: '{0}'")]
+    InvalidConfig(String),
+    #[error("no repository is configured. Please specify a repository in the config file.")]
+    NoRepository,
+    #[error("git authentication failed: {0}")]
+    GitAuth(String),
+    #[error("push rejected: {0}")]
+    PushRejected(String),
+    #[error("git error: {0}")]
+    Git(String),
+    #[error("the tags file '{path}' is corrupt: {reason}")]
+    TagStoreCorrupt { path: String, reason: String },
+    #[error("note '{0}' is encrypted, and needs a passphrase")]
+    PassphraseRequired(String),
+    #[error("failed to decrypt the note: wrong passphrase or corrupted note")]
+    DecryptionFailed,
+    #[error("database error: {0}")]
+    Database(String),
+    #[error("hook '{hook}' failed with {status}")]
+    HookFailed { hook: String, status: String },
+    #[error("plugin '{name}' failed with exit code {code}")]
+    PluginFailed { name: String, code: i32 },
+    #[error("invalid archive: {0}")]
+    InvalidArchive(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+pub type GnotesResult<T> = Result<T, GnotesError>;
+
+/// Keeps a `GnotesError` that was passed through `anyhow`, so it isn't reported as `Other`.
+impl From<anyhow::Error> for GnotesError {
+    fn from(error: anyhow::Error) -> Self {
+        match error.downcast::<GnotesError>() {
+            Ok(error) => error,
+            Err(error) => GnotesError::Other(error),
+        }
+    }
+}
+
+impl From<git2::Error> for GnotesError {
+    fn from(error: git2::Error) -> Self {
+        match (error.code(), error.class()) {
+            (git2::ErrorCode::Auth, _) | (_, git2::ErrorClass::Ssh) => {
+                GnotesError::GitAuth(String::from(error.message()))
+            }
+            (git2::ErrorCode::NotFastForward, _) => {
+                GnotesError::PushRejected(String::from(error.message()))
+            }
+            _ => GnotesError::Git(String::from(error.message())),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for GnotesError {
+    fn from(error: rusqlite::Error) -> Self {
+        GnotesError::Database(error.to_string())
+    }
+}
+
+impl GnotesError {
+    /// The exit code of the error, as documented in the README.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GnotesError::Io(_) | GnotesError::Other(_) => 1,
+            GnotesError::InvalidArgs(_) => 2,
+            GnotesError::AmbiguousName { .. } => 3,
+            GnotesError::NoteNotFound(_) => 4,
+            GnotesError::NoteExists(_) => 5,
+            GnotesError::SectionNotFound(_) => 6,
+            GnotesError::TagNotFound(_) => 7,
+            GnotesError::TagExists(_) => 8,
+            GnotesError::TodoNotFound(_) => 9,
+            GnotesError::InvalidConfig(_) => 10,
+            GnotesError::NoRepository => 11,
+            GnotesError::GitAuth(_) => 12,
+            GnotesError::PushRejected(_) => 13,
+            GnotesError::Git(_) => 14,
+            GnotesError::TagStoreCorrupt { .. } => 15,
+            GnotesError::PassphraseRequired(_) => 16,
+            GnotesError::DecryptionFailed => 17,
+            GnotesError::Database(_) => 18,
+            GnotesError::HookFailed { .. } => 19,
+            GnotesError::PluginFailed { .. } => 20,
+            GnotesError::InvalidArchive(_) => 21,
+        }
+    }
+
+    /// A stable name for the error, used by `--error-format json`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            GnotesError::InvalidArgs(_) => "invalid_args",
+            GnotesError::AmbiguousName { .. } => "ambiguous_name",
+            GnotesError::NoteNotFound(_) => "note_not_found",
+            GnotesError::NoteExists(_) => "note_exists",
+            GnotesError::SectionNotFound(_) => "section_not_found",
+            GnotesError::TagNotFound(_) => "tag_not_found",
+            GnotesError::TagExists(_) => "tag_exists",
+            GnotesError::TodoNotFound(_) => "todo_not_found",
+            GnotesError::InvalidConfig(_) => "invalid_config",
+            GnotesError::NoRepository => "no_repository",
+            GnotesError::GitAuth(_) => "git_auth",
+            GnotesError::PushRejected(_) => "push_rejected",
+            GnotesError::Git(_) => "git",
+            GnotesError::TagStoreCorrupt { .. } => "tag_store_corrupt",
+            GnotesError::PassphraseRequired(_) => "passphrase_required",
+            GnotesError::DecryptionFailed => "decryption_failed",
+            GnotesError::Database(_) => "database",
+            GnotesError::HookFailed { .. } => "hook_failed",
+            GnotesError::PluginFailed { .. } => "plugin_failed",
+            GnotesError::InvalidArchive(_) => "invalid_archive",
+            GnotesError::Io(_) => "io",
+            GnotesError::Other(_) => "other",
+        }
+    }
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Text,
+    Json,
+}
+
+/// Prints the error of the command to stderr, and returns the exit code.
+pub fn report(error: &anyhow::Error, command: &str, format: ErrorFormat) -> i32 {
+    let gnotes_error = error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<GnotesError>());
+    let (kind, exit_code) = gnotes_error
+        .map(|error| (error.kind(), error.exit_code()))
+        .unwrap_or(("other", 1));
+    let message = format!("{:#}", error);
+
+    match format {
+        ErrorFormat::Text => eprintln!("{} failed: {}", command, message),
+        ErrorFormat::Json => {
+            let mut output = json!({
+                "command": command,
+                "kind": kind,
+                "message": message,
+                "exit_code": exit_code,
+            });
+
+            if let Some(GnotesError::AmbiguousName { matches, .. }) = gnotes_error {
+                output["matches"] = json!(matches);
+            }
+
+            eprintln!("{}", output);
+        }
+    }
+
+    exit_code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn from_anyhow_test() {
+        let error: GnotesError = anyhow::Error::from(GnotesError::NoRepository).into();
+
+        assert_eq!(error.exit_code(), 11);
+
+        let error: GnotesError = anyhow::anyhow!("unexpected").into();
+
+        assert_eq!(error.exit_code(), 1);
+    }
+
+    #[test]
+    fn report_finds_the_error_behind_context() {
+        let error = Err::<(), _>(GnotesError::SectionNotFound(String::from("Todo")))
+            .context("outer")
+            .unwrap_err();
+
+        assert_eq!(report(&error, "show", ErrorFormat::Text), 6);
+    }
+}
@@ -1,4 +1,7 @@
 pub mod commands;
 pub mod common;
 pub mod config;
+pub mod error;
+pub mod notebook;
 pub mod run;
+pub mod store;
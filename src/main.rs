@@ -1,20 +1,28 @@
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{ArgMatches, CommandFactory, ErrorKind, FromArgMatches, Parser, Subcommand};
 use gnotes::commands::{
-    AddCommand, CloneCommand, EditCommand, ListCommand, NewCommand, RemoveCommand, SaveCommand,
-    SearchCommand, ShowCommand, TagCommand, UntagCommand,
+    find_plugin, find_plugins, AddCommand, AgendaCommand, BacklinksCommand, CloneCommand,
+    CompleteCommand, CompletionsCommand, EditCommand, ExportCommand, GraphCommand, ImportCommand,
+    ListCommand, MergeTagsCommand, MoveCommand, NewCommand, OutlineCommand, PluginCommand,
+    RemoveCommand, SaveCommand, SearchCommand, ShowCommand, StoreCommand, TagCommand, TagsCommand,
+    TodoCommand, UiCommand, UntagCommand, WatchCommand,
 };
 use gnotes::config::load_config;
+use gnotes::error::{report, ErrorFormat, GnotesError};
 use gnotes::run::Run;
 use log::{debug, LevelFilter};
 use std::env;
 use std::path::PathBuf;
+use std::process;
 
 #[derive(Debug, Parser)]
 struct Cli {
     /// Turn of verbose logging
     #[clap(long)]
     debug: bool,
+    /// How errors are printed to stderr
+    #[clap(long, global = true, arg_enum, default_value = "text")]
+    error_format: ErrorFormat,
     // The sub command to run
     #[clap(subcommand)]
     command: Option<Command>,
@@ -26,15 +34,34 @@ enum Command {
     Add(AddCommand),
     Remove(RemoveCommand),
     Rm(RemoveCommand),
+    Move(MoveCommand),
+    Mv(MoveCommand),
     List(ListCommand),
     Ls(ListCommand),
     Show(ShowCommand),
+    Outline(OutlineCommand),
     Edit(EditCommand),
+    Backlinks(BacklinksCommand),
+    Graph(GraphCommand),
+    Export(ExportCommand),
+    Import(ImportCommand),
     Tag(TagCommand),
     Untag(UntagCommand),
+    Tags(TagsCommand),
     Search(SearchCommand),
+    Todo(TodoCommand),
+    Agenda(AgendaCommand),
+    Ui(UiCommand),
     Clone(CloneCommand),
     Save(SaveCommand),
+    Watch(WatchCommand),
+    Store(StoreCommand),
+    MergeTags(MergeTagsCommand),
+    Completions(CompletionsCommand),
+    #[clap(name = "__complete", hide = true)]
+    Complete(CompleteCommand),
+    #[clap(external_subcommand)]
+    Plugin(Vec<String>),
 }
 
 fn init_logger(debug: bool) {
@@ -57,13 +84,145 @@ fn get_home_dir() -> Result<PathBuf> {
     Ok(home_dir)
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// The name of the command being run, with its sub commands, e.g. "todo done".
+fn get_command_name(matches: &ArgMatches) -> String {
+    let mut names = vec![];
+    let mut matches = matches;
+
+    while let Some((name, sub_matches)) = matches.subcommand() {
+        names.push(name);
+        matches = sub_matches;
+    }
+
+    if names.is_empty() {
+        String::from("gnotes")
+    } else {
+        names.join(" ")
+    }
+}
+
+/// Whether the json error format was asked for. Used when the arguments can't be parsed.
+fn is_json_error_format() -> bool {
+    let args: Vec<String> = env::args().collect();
+
+    args.iter().any(|arg| arg == "--error-format=json")
+        || args
+            .windows(2)
+            .any(|args| args[0] == "--error-format" && args[1] == "json")
+}
+
+/// Whether the help of gnotes itself is asked for, which lists the plugins.
+fn is_help() -> bool {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    args.is_empty() || args == ["help"] || args == ["--help"] || args == ["-h"]
+}
+
+/// Lists the plugins found on `PATH` after the commands, in the help.
+fn plugins_help() -> String {
+    let plugins = find_plugins();
+
+    if plugins.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "PLUGINS:\n{}",
+        plugins
+            .iter()
+            .map(|plugin| format!("    {}", plugin))
+            .collect::<Vec<String>>()
+            .join("\n")
+    )
+}
+
+fn parse_cli() -> (Cli, String) {
+    let plugins_help = if is_help() {
+        plugins_help()
+    } else {
+        String::new()
+    };
+    let mut cli = Cli::command();
+    if !plugins_help.is_empty() {
+        cli = cli.after_help(plugins_help.as_str());
+    }
+
+    let parsed = cli
+        .clone()
+        .try_get_matches()
+        .and_then(|matches| Ok((Cli::from_arg_matches(&matches)?, get_command_name(&matches))));
+
+    let (parsed_cli, command_name) = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => exit_with_parse_error(error),
+    };
+
+    // Without a plugin, a mistyped command gets the suggestion of clap instead, which it only
+    // makes when unknown subcommands aren't allowed.
+    if let Some(Command::Plugin(args)) = &parsed_cli.command {
+        if args.first().is_some_and(|name| find_plugin(name).is_none()) {
+            if let Err(error) = cli.allow_external_subcommands(false).try_get_matches() {
+                if error.kind() == ErrorKind::InvalidSubcommand {
+                    exit_with_parse_error(error);
+                }
+            }
+        }
+    }
+
+    (parsed_cli, command_name)
+}
+
+/// Exits with the error of clap, printed in the asked error format.
+fn exit_with_parse_error(error: clap::Error) -> ! {
+    match error.kind() {
+        ErrorKind::DisplayHelp
+        | ErrorKind::DisplayVersion
+        | ErrorKind::DisplayHelpOnMissingArgumentOrSubcommand => error.exit(),
+        _ if is_json_error_format() => {
+            // Only the first line and the suggestion are kept, without the usage that follows.
+            let message = error.to_string();
+            let mut lines = message.lines();
+            let mut message = String::from(
+                lines
+                    .next()
+                    .unwrap_or_default()
+                    .trim_start_matches("error: "),
+            );
+            if let Some(suggestion) = lines.find(|line| line.trim().starts_with("Did you mean")) {
+                message = format!("{}. {}", message, suggestion.trim());
+            }
+            let error = GnotesError::InvalidArgs(message);
+
+            process::exit(report(&error.into(), "gnotes", ErrorFormat::Json));
+        }
+        _ => error.exit(),
+    }
+}
+
+fn main() {
+    let (cli, command_name) = parse_cli();
+    let error_format = cli.error_format;
 
     init_logger(cli.debug);
 
     debug!("cli options {:?}", cli);
 
+    if let Err(error) = run(cli) {
+        process::exit(report(&error, &command_name, error_format));
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    // The completions don't need the config, so they can be generated before gnotes is set up.
+    if let Some(Command::Completions(completions_command)) = &cli.command {
+        return completions_command.generate(&Cli::command());
+    }
+
+    // Git runs the merge driver in the notes repository, which doesn't need the config either.
+    if let Some(Command::MergeTags(merge_tags_command)) = &cli.command {
+        return merge_tags_command.merge();
+    }
+
     let home_dir = get_home_dir()?;
     let config = load_config(&home_dir)?;
 
@@ -76,14 +235,31 @@ fn main() -> Result<()> {
             Command::Remove(remove_command) | Command::Rm(remove_command) => {
                 remove_command.run(&config)?
             }
+            Command::Move(move_command) | Command::Mv(move_command) => move_command.run(&config)?,
             Command::List(list_command) | Command::Ls(list_command) => list_command.run(&config)?,
             Command::Show(show_command) => show_command.run(&config)?,
+            Command::Outline(outline_command) => outline_command.run(&config)?,
             Command::Edit(edit_command) => edit_command.run(&config)?,
+            Command::Backlinks(backlinks_command) => backlinks_command.run(&config)?,
+            Command::Graph(graph_command) => graph_command.run(&config)?,
+            Command::Export(export_command) => export_command.run(&config)?,
+            Command::Import(import_command) => import_command.run(&config)?,
             Command::Tag(tag_command) => tag_command.run(&config)?,
             Command::Untag(untag_command) => untag_command.run(&config)?,
+            Command::Tags(tags_command) => tags_command.run(&config)?,
             Command::Search(search_command) => search_command.run(&config)?,
+            Command::Todo(todo_command) => todo_command.run(&config)?,
+            Command::Agenda(agenda_command) => agenda_command.run(&config)?,
+            Command::Ui(ui_command) => ui_command.run(&config)?,
             Command::Clone(clone_command) => clone_command.run(&config)?,
             Command::Save(save_command) => save_command.run(&config)?,
+            Command::Watch(watch_command) => watch_command.run(&config)?,
+            Command::Store(store_command) => store_command.run(&config)?,
+            Command::Plugin(args) => PluginCommand { args }.run(&config)?,
+            Command::Completions(_) | Command::MergeTags(_) => {}
+            Command::Complete(complete_command) => {
+                complete_command.complete(&Cli::command(), &config)?
+            }
         }
     }
 
@@ -0,0 +1,812 @@
+use crate::common::crypto::{decrypt, encrypt, is_encrypted, ENCRYPTED_NOTE_HEADER};
+use crate::common::git;
+use crate::common::links::{find_linked_notes, rewrite_links};
+use crate::common::notes::resolve_dir;
+use crate::common::resolve::match_notes;
+use crate::common::sections::{find_headings, find_section, insert_under};
+use crate::common::tags::Tags;
+use crate::config::{Config, StoreKind};
+use crate::error::{GnotesError, GnotesResult};
+use crate::store::{open_stores, NoteStore, TagStore};
+use chrono::prelude::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// A note with its content, which is decrypted if the note is encrypted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Note {
+    pub identifier: String,
+    pub content: String,
+    pub encrypted: bool,
+}
+
+/// The metadata of a note, as returned by `Notebook::list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteInfo {
+    pub identifier: String,
+    pub len: u64,
+    pub created: SystemTime,
+    pub modified: SystemTime,
+    pub encrypted: bool,
+}
+
+/// The notes directory, with its notes and tags. Notes are addressed by their identifier,
+/// "dir/name", and encrypted notes are read and written with the given passphrase.
+#[derive(Debug)]
+pub struct Notebook {
+    config: Config,
+    notes: Box<dyn NoteStore>,
+    tags: Box<dyn TagStore>,
+}
+
+fn now() -> String {
+    let date_time: DateTime<Utc> = SystemTime::now().into();
+
+    format!("{}", date_time.format("[%Y-%m-%d][%H:%M:%S]"))
+}
+
+fn links_to(content: &str, note_identifier: &str) -> bool {
+    find_linked_notes(content)
+        .iter()
+        .any(|linked_note| linked_note == note_identifier)
+}
+
+impl Notebook {
+    /// A notebook over the configured notes directory, with the configured stores.
+    pub fn new(config: Config) -> GnotesResult<Self> {
+        let (notes, tags) = open_stores(&config)?;
+
+        Ok(Self::with_stores(config, notes, tags))
+    }
+
+    /// A notebook over the given stores, e.g. `MemoryNoteStore` and `MemoryTagStore` in tests.
+    /// The notes directory of the config is still used by `path` and `save`.
+    pub fn with_stores(config: Config, notes: Box<dyn NoteStore>, tags: Box<dyn TagStore>) -> Self {
+        Self {
+            config,
+            notes,
+            tags,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn note_store(&self) -> &dyn NoteStore {
+        self.notes.as_ref()
+    }
+
+    pub fn tag_store(&self) -> &dyn TagStore {
+        self.tags.as_ref()
+    }
+
+    /// The identifier of the note `name` under `dir`, or under the default directory.
+    pub fn identifier(name: &str, dir: &Option<PathBuf>) -> String {
+        String::from(resolve_dir(dir).join(name).to_string_lossy())
+    }
+
+    pub fn path(&self, note_identifier: &str) -> PathBuf {
+        self.config.notes_dir.join(note_identifier)
+    }
+
+    pub fn exists(&self, note_identifier: &str) -> bool {
+        self.notes.exists(note_identifier)
+    }
+
+    /// The identifier of the existing note `name` under `dir`, or under the default directory.
+    pub fn existing_identifier(&self, name: &str, dir: &Option<PathBuf>) -> GnotesResult<String> {
+        let note_identifier = Self::identifier(name, dir);
+
+        self.assert_exists(&note_identifier)?;
+
+        Ok(note_identifier)
+    }
+
+    /// Whether the note is encrypted. Only its header is read.
+    pub fn is_encrypted(&self, note_identifier: &str) -> bool {
+        self.notes
+            .read_prefix(note_identifier, ENCRYPTED_NOTE_HEADER.len())
+            .map(|prefix| prefix == ENCRYPTED_NOTE_HEADER.as_bytes())
+            .unwrap_or_default()
+    }
+
+    /// Whether the note is encrypted, or would be encrypted when created.
+    pub fn needs_passphrase(&self, note_identifier: &str) -> bool {
+        if self.notes.exists(note_identifier) {
+            return self.is_encrypted(note_identifier);
+        }
+
+        Path::new(note_identifier)
+            .parent()
+            .map(|dir| self.config.is_encrypted_dir(dir))
+            .unwrap_or_default()
+    }
+
+    /// The identifiers of the notes under `dir`, or of all the notes when it's not given, sorted.
+    pub fn identifiers(&self, dir: Option<&Path>) -> GnotesResult<Vec<String>> {
+        let mut note_identifiers = self.notes.list()?;
+
+        if let Some(dir) = dir {
+            let note_identifier_prefix = String::from(dir.join("").to_string_lossy());
+
+            note_identifiers
+                .retain(|note_identifier| note_identifier.starts_with(&note_identifier_prefix));
+        }
+
+        Ok(note_identifiers)
+    }
+
+    /// The identifiers of the notes under `dir` (when given) that are tagged with `tag` or its
+    /// nested tags (when given), sorted.
+    pub fn select(&self, dir: Option<&Path>, tag: Option<&str>) -> GnotesResult<Vec<String>> {
+        let mut note_identifiers = self.identifiers(dir)?;
+
+        if let Some(tag) = tag {
+            let tagged_notes = self.tags.find_tagged_notes(tag, false)?;
+
+            note_identifiers.retain(|note_identifier| tagged_notes.contains(note_identifier));
+        }
+
+        Ok(note_identifiers)
+    }
+
+    /// Finds the notes matching a partial or fuzzy name, best matches first.
+    pub fn find(&self, name: &str, dir: &Option<PathBuf>) -> GnotesResult<Vec<String>> {
+        Ok(match_notes(self.notes.list()?, name, dir))
+    }
+
+    fn assert_exists(&self, note_identifier: &str) -> GnotesResult<()> {
+        if self.exists(note_identifier) {
+            Ok(())
+        } else {
+            Err(GnotesError::NoteNotFound(String::from(
+                self.path(note_identifier).to_string_lossy(),
+            )))
+        }
+    }
+
+    /// Writes the content, encrypting it when the note is, or when it's new in an encrypted directory.
+    fn write(
+        &self,
+        note_identifier: &str,
+        content: &str,
+        passphrase: Option<&str>,
+    ) -> GnotesResult<Note> {
+        let encrypted = passphrase.is_some() || self.needs_passphrase(note_identifier);
+
+        match passphrase {
+            Some(passphrase) => self
+                .notes
+                .write(note_identifier, &encrypt(content, passphrase)?)?,
+            _ if encrypted => {
+                return Err(GnotesError::PassphraseRequired(String::from(
+                    note_identifier,
+                )));
+            }
+            _ => self.notes.write(note_identifier, content)?,
+        }
+
+        if !encrypted {
+            self.refresh_inline_tags(note_identifier, content)?;
+        }
+
+        Ok(Note {
+            identifier: String::from(note_identifier),
+            content: String::from(content),
+            encrypted,
+        })
+    }
+
+    /// Creates a note with the content. It's encrypted when a passphrase is given, which is
+    /// required in encrypted directories.
+    pub fn create(
+        &self,
+        note_identifier: &str,
+        content: &str,
+        passphrase: Option<&str>,
+    ) -> GnotesResult<Note> {
+        if self.exists(note_identifier) {
+            return Err(GnotesError::NoteExists(String::from(
+                self.path(note_identifier).to_string_lossy(),
+            )));
+        }
+
+        self.write(note_identifier, content, passphrase)
+    }
+
+    /// Replaces the content of an existing note, which stays encrypted if it is. A passphrase
+    /// given for a plain note is ignored.
+    pub fn update(
+        &self,
+        note_identifier: &str,
+        content: &str,
+        passphrase: Option<&str>,
+    ) -> GnotesResult<Note> {
+        self.assert_exists(note_identifier)?;
+
+        let passphrase = passphrase.filter(|_| self.needs_passphrase(note_identifier));
+
+        self.write(note_identifier, content, passphrase)
+    }
+
+    /// Appends a line to the end of the note, or to the end of the `under` section.
+    /// The note is created when it doesn't exist. It's read and written again while the notebook
+    /// is locked, so concurrent changes aren't lost.
+    pub fn append(
+        &self,
+        note_identifier: &str,
+        text: &str,
+        under: Option<&str>,
+        passphrase: Option<&str>,
+    ) -> GnotesResult<Note> {
+        let mut note = Note::default();
+
+        self.tags.locked(&mut || {
+            note = self.append_unlocked(note_identifier, text, under, passphrase)?;
+
+            Ok(())
+        })?;
+
+        Ok(note)
+    }
+
+    fn append_unlocked(
+        &self,
+        note_identifier: &str,
+        text: &str,
+        under: Option<&str>,
+        passphrase: Option<&str>,
+    ) -> GnotesResult<Note> {
+        // A passphrase given for a plain note is ignored, so the note stays plain.
+        let passphrase = passphrase.filter(|_| self.needs_passphrase(note_identifier));
+        let mut content = if self.exists(note_identifier) {
+            self.read(note_identifier, passphrase)?.content
+        } else {
+            String::new()
+        };
+
+        match under {
+            Some(section) => {
+                let headings = find_headings(&content);
+
+                content = match find_section(&headings, section) {
+                    Some(heading) => insert_under(&content, heading, text),
+                    _ => return Err(GnotesError::SectionNotFound(String::from(section))),
+                };
+            }
+            // Plain notes are appended to in place, rather than written again.
+            _ if passphrase.is_none() && !self.needs_passphrase(note_identifier) => {
+                let line = format!("{}\n", text);
+
+                self.notes.append(note_identifier, &line)?;
+                content.push_str(&line);
+                self.refresh_inline_tags(note_identifier, &content)?;
+
+                return Ok(Note {
+                    identifier: String::from(note_identifier),
+                    content,
+                    encrypted: false,
+                });
+            }
+            _ => {
+                content.push_str(text);
+                content.push('\n');
+            }
+        }
+
+        self.write(note_identifier, &content, passphrase)
+    }
+
+    /// Reads the note, decrypting it with the passphrase if it's encrypted.
+    pub fn read(&self, note_identifier: &str, passphrase: Option<&str>) -> GnotesResult<Note> {
+        self.assert_exists(note_identifier)?;
+
+        let content = self.notes.read(note_identifier)?;
+
+        if !is_encrypted(&content) {
+            return Ok(Note {
+                identifier: String::from(note_identifier),
+                content,
+                encrypted: false,
+            });
+        }
+
+        match passphrase {
+            Some(passphrase) => Ok(Note {
+                identifier: String::from(note_identifier),
+                content: decrypt(&content, passphrase)?,
+                encrypted: true,
+            }),
+            _ => Err(GnotesError::PassphraseRequired(String::from(
+                note_identifier,
+            ))),
+        }
+    }
+
+    /// Reads the note, unless it's encrypted or isn't valid text, e.g. to scan all the notes
+    /// without their passphrase.
+    pub fn read_plain(&self, note_identifier: &str) -> Option<String> {
+        self.notes
+            .read(note_identifier)
+            .ok()
+            .filter(|content| !is_encrypted(content))
+    }
+
+    /// Lists the notes under `dir`, or all the notes when it's not given, sorted by identifier.
+    pub fn list(&self, dir: Option<&Path>) -> GnotesResult<Vec<NoteInfo>> {
+        self.identifiers(dir)?
+            .into_iter()
+            .map(|note_identifier| {
+                let metadata = self.notes.metadata(&note_identifier)?;
+
+                Ok(NoteInfo {
+                    encrypted: self.is_encrypted(&note_identifier),
+                    identifier: note_identifier,
+                    len: metadata.len,
+                    created: metadata.created,
+                    modified: metadata.modified,
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces the content of the note with what `modify` returns for it, unless it returns
+    /// `None`. The note is read and written again while the notebook is locked, so concurrent
+    /// changes aren't lost. Returns the modified note.
+    pub fn modify(
+        &self,
+        note_identifier: &str,
+        passphrase: Option<&str>,
+        modify: &mut dyn FnMut(&str) -> Option<String>,
+    ) -> GnotesResult<Option<Note>> {
+        let mut note = None;
+
+        self.tags.locked(&mut || {
+            let content = self.read(note_identifier, passphrase)?.content;
+
+            note = match modify(&content) {
+                Some(content) => Some(self.update(note_identifier, &content, passphrase)?),
+                _ => None,
+            };
+
+            Ok(())
+        })?;
+
+        Ok(note)
+    }
+
+    /// Moves the note, with its tags, and updates the links to it in the other notes. Encrypted
+    /// notes can't be scanned without their passphrase, so their links are left as is, and they're
+    /// returned.
+    pub fn rename(
+        &self,
+        note_identifier: &str,
+        new_note_identifier: &str,
+    ) -> GnotesResult<Vec<String>> {
+        let mut skipped = vec![];
+
+        // Moved while holding the lock, so a concurrent gnotes doesn't see the note half moved.
+        self.tags.locked(&mut || {
+            self.assert_exists(note_identifier)?;
+
+            if self.exists(new_note_identifier) {
+                return Err(GnotesError::NoteExists(String::from(
+                    self.path(new_note_identifier).to_string_lossy(),
+                )));
+            }
+
+            self.notes.rename(note_identifier, new_note_identifier)?;
+            self.tags
+                .rename_note(note_identifier, new_note_identifier)?;
+
+            for candidate in self.notes.list()? {
+                if let Some(content) = self.read_plain(&candidate) {
+                    if links_to(&content, note_identifier) {
+                        self.notes.write(
+                            &candidate,
+                            &rewrite_links(&content, note_identifier, new_note_identifier),
+                        )?;
+                    }
+                } else if candidate != new_note_identifier && self.is_encrypted(&candidate) {
+                    skipped.push(candidate);
+                }
+            }
+
+            Ok(())
+        })?;
+
+        Ok(skipped)
+    }
+
+    /// The notes which link to the note. Encrypted notes can't be scanned without their
+    /// passphrase, so they're left out.
+    pub fn backlinks(&self, note_identifier: &str) -> GnotesResult<Vec<String>> {
+        Ok(self
+            .notes
+            .list()?
+            .into_iter()
+            .filter(|candidate| {
+                self.read_plain(candidate)
+                    .is_some_and(|content| links_to(&content, note_identifier))
+            })
+            .collect())
+    }
+
+    /// Removes the note, together with its tags.
+    pub fn remove(&self, note_identifier: &str) -> GnotesResult<()> {
+        self.assert_exists(note_identifier)?;
+
+        self.notes.remove(note_identifier)?;
+        self.tags.remove_note(note_identifier)
+    }
+
+    pub fn tag(&self, note_identifier: &str, tags: &[String]) -> GnotesResult<()> {
+        self.assert_exists(note_identifier)?;
+
+        self.tags.tag_note(note_identifier, tags)
+    }
+
+    pub fn untag(&self, note_identifier: &str, tag: &str) -> GnotesResult<()> {
+        self.assert_exists(note_identifier)?;
+
+        self.tags.untag_note(note_identifier, tag)
+    }
+
+    /// All the tags, with the notes they're on.
+    pub fn tags(&self) -> GnotesResult<Tags> {
+        self.tags.load_tags()
+    }
+
+    /// Applies `modify` to all the tags while the notebook is locked. The inline tags which were
+    /// renamed or removed are no longer backed by a hashtag, so they're kept as regular tags.
+    pub fn modify_tags(
+        &self,
+        modify: &mut dyn FnMut(&mut Tags) -> GnotesResult<()>,
+    ) -> GnotesResult<()> {
+        self.tags.locked(&mut || {
+            let mut tags = self.tags.load_tags()?;
+
+            modify(&mut tags)?;
+            self.tags.update_tags(&tags)?;
+            self.tags.modify_inline_tags(&mut |inline_tags| {
+                inline_tags.retain(|tag, tags_set| {
+                    tags_set.retain(|note_identifier| {
+                        tags.get(tag)
+                            .map(|current| current.contains(note_identifier))
+                            .unwrap_or_default()
+                    });
+
+                    !tags_set.is_empty()
+                })
+            })
+        })
+    }
+
+    /// Runs `f` while the notebook is locked, e.g. to check and write several notes at once.
+    pub fn locked(&self, f: &mut dyn FnMut() -> GnotesResult<()>) -> GnotesResult<()> {
+        self.tags.locked(f)
+    }
+
+    /// Finds the notes tagged with `tag`, or with its nested tags unless `exact` is set.
+    /// Only the notes under `dir` are included when it's given.
+    pub fn search(&self, tag: &str, dir: Option<&Path>, exact: bool) -> GnotesResult<Vec<String>> {
+        let note_identifier_prefix = dir
+            .map(|dir| String::from(dir.join("").to_string_lossy()))
+            .unwrap_or_default();
+
+        Ok(self
+            .tags
+            .find_tagged_notes(tag, exact)?
+            .into_iter()
+            .filter(|note_identifier| note_identifier.starts_with(&note_identifier_prefix))
+            .collect())
+    }
+
+    /// Commits all the changes, and pushes them to the configured repository.
+    pub fn save(&self, message: Option<&str>) -> GnotesResult<()> {
+        let message = message
+            .map(String::from)
+            .unwrap_or_else(|| format!("gnotes manual save {}", now()));
+
+        self.commit(&message)?;
+        self.push()
+    }
+
+    /// Commits all the changes, without pushing them. Returns whether there was anything to commit.
+    pub fn commit(&self, message: &str) -> GnotesResult<bool> {
+        let repository = self.repository()?;
+
+        // The database isn't saved to git, so the tags are saved as JSON instead.
+        if self.config.store == StoreKind::Sqlite {
+            self.tags.export_json(&self.config.notes_dir)?;
+        }
+
+        git::commit(&self.config.notes_dir, repository, message)
+    }
+
+    /// Pushes the commits to the configured repository.
+    pub fn push(&self) -> GnotesResult<()> {
+        git::push(
+            &self.config.notes_dir,
+            &self.config.ssh_file_path,
+            self.repository()?,
+        )
+    }
+
+    fn repository(&self) -> GnotesResult<&str> {
+        self.config
+            .repository
+            .as_deref()
+            .ok_or(GnotesError::NoRepository)
+    }
+
+    /// Tags the note with its `#hashtags`, when inline tags are enabled. Needed after the note is
+    /// changed outside of the notebook, e.g. in an editor. Encrypted notes are skipped, as the
+    /// tags are saved to git in plaintext.
+    pub fn refresh_inline_tags(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        if self.config.inline_tags && !self.needs_passphrase(note_identifier) {
+            self.tags.sync_inline_tags(note_identifier, content)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{MemoryNoteStore, MemoryTagStore};
+    use maplit::hashmap;
+    use std::collections::HashMap;
+
+    fn setup_notebook(dirs: HashMap<String, crate::config::DirConfig>) -> Notebook {
+        let config = Config {
+            notes_dir: PathBuf::from("/gnotes"),
+            auto_save: false,
+            repository: None,
+            ssh_file_path: PathBuf::new(),
+            inline_tags: true,
+            store: StoreKind::Json,
+            dirs,
+            hooks_dir: None,
+            trust_notes_hooks: false,
+            hooks: HashMap::new(),
+        };
+
+        Notebook::with_stores(
+            config,
+            Box::new(MemoryNoteStore::new()),
+            Box::new(MemoryTagStore::new()),
+        )
+    }
+
+    #[test]
+    fn create_and_read_test() {
+        let notebook = setup_notebook(HashMap::new());
+
+        notebook.create("notes/chores", "hello\n", None).unwrap();
+
+        assert_eq!(
+            notebook.read("notes/chores", None).unwrap(),
+            Note {
+                identifier: String::from("notes/chores"),
+                content: String::from("hello\n"),
+                encrypted: false,
+            }
+        );
+        assert!(matches!(
+            notebook.create("notes/chores", "hello\n", None),
+            Err(GnotesError::NoteExists(_))
+        ));
+        assert!(matches!(
+            notebook.read("notes/missing", None),
+            Err(GnotesError::NoteNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn encrypted_notes_test() {
+        let dirs = hashmap! {
+            String::from("secret") => crate::config::DirConfig { encrypted: true },
+        };
+        let notebook = setup_notebook(dirs);
+
+        assert!(notebook.needs_passphrase("secret/keys"));
+        assert!(matches!(
+            notebook.append("secret/keys", "hello", None, None),
+            Err(GnotesError::PassphraseRequired(_))
+        ));
+
+        notebook
+            .append("secret/keys", "hello", None, Some("passphrase"))
+            .unwrap();
+
+        assert!(matches!(
+            notebook.read("secret/keys", None),
+            Err(GnotesError::PassphraseRequired(_))
+        ));
+        assert_eq!(
+            notebook
+                .read("secret/keys", Some("passphrase"))
+                .unwrap()
+                .content,
+            "hello\n"
+        );
+
+        notebook
+            .update("secret/keys", "bye\n", Some("passphrase"))
+            .unwrap();
+
+        assert!(notebook.needs_passphrase("secret/keys"));
+        assert_eq!(
+            notebook
+                .read("secret/keys", Some("passphrase"))
+                .unwrap()
+                .content,
+            "bye\n"
+        );
+    }
+
+    #[test]
+    fn encrypted_notes_skip_inline_tags_test() {
+        let dirs = hashmap! {
+            String::from("secret") => crate::config::DirConfig { encrypted: true },
+        };
+        let notebook = setup_notebook(dirs);
+
+        notebook
+            .append("secret/keys", "#secret", None, Some("passphrase"))
+            .unwrap();
+        notebook
+            .create("notes/keys", "#secret\n", Some("passphrase"))
+            .unwrap();
+        notebook
+            .refresh_inline_tags("notes/keys", "#secret\n")
+            .unwrap();
+
+        assert!(notebook.tag_store().load_tags().unwrap().is_empty());
+        assert!(notebook.tag_store().load_inline_tags().unwrap().is_empty());
+    }
+
+    #[test]
+    fn append_under_section_test() {
+        let notebook = setup_notebook(HashMap::new());
+
+        notebook
+            .create("notes/meeting", "# Meeting\n## Action items\n", None)
+            .unwrap();
+
+        let note = notebook
+            .append("notes/meeting", "- call Bob", Some("Action items"), None)
+            .unwrap();
+
+        assert_eq!(note.content, "# Meeting\n## Action items\n- call Bob\n");
+        assert!(matches!(
+            notebook.append("notes/meeting", "- call Bob", Some("Notes"), None),
+            Err(GnotesError::SectionNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn tags_test() {
+        let notebook = setup_notebook(HashMap::new());
+
+        notebook
+            .create("notes/chores", "#home stuff\n", None)
+            .unwrap();
+        notebook.create("work/plan", "plans\n", None).unwrap();
+        notebook
+            .tag("work/plan", &[String::from("home/garden")])
+            .unwrap();
+
+        assert_eq!(
+            notebook.search("home", None, false).unwrap(),
+            vec!["notes/chores", "work/plan"]
+        );
+        assert_eq!(
+            notebook
+                .search("home", Some(Path::new("work")), false)
+                .unwrap(),
+            vec!["work/plan"]
+        );
+        assert_eq!(
+            notebook.search("home", None, true).unwrap(),
+            vec!["notes/chores"]
+        );
+
+        notebook.untag("work/plan", "home/garden").unwrap();
+        notebook.remove("notes/chores").unwrap();
+
+        assert_eq!(notebook.tag_store().load_tags().unwrap(), hashmap! {});
+        assert!(matches!(
+            notebook.tag("notes/chores", &[String::from("home")]),
+            Err(GnotesError::NoteNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rename_test() {
+        let notebook = setup_notebook(HashMap::new());
+
+        notebook.create("notes/chores", "hello\n", None).unwrap();
+        notebook
+            .create("work/plan", "see [[chores]]\n", None)
+            .unwrap();
+        notebook
+            .tag("notes/chores", &[String::from("home")])
+            .unwrap();
+
+        assert_eq!(
+            notebook.backlinks("notes/chores").unwrap(),
+            vec!["work/plan"]
+        );
+
+        assert!(notebook
+            .rename("notes/chores", "home/chores")
+            .unwrap()
+            .is_empty());
+
+        assert!(!notebook.exists("notes/chores"));
+        assert_eq!(
+            notebook.read("work/plan", None).unwrap().content,
+            "see [[home/chores]]\n"
+        );
+        assert_eq!(
+            notebook.search("home", None, true).unwrap(),
+            vec!["home/chores"]
+        );
+        assert!(matches!(
+            notebook.rename("home/chores", "work/plan"),
+            Err(GnotesError::NoteExists(_))
+        ));
+    }
+
+    #[test]
+    fn modify_test() {
+        let notebook = setup_notebook(HashMap::new());
+
+        notebook
+            .create("notes/chores", "- [ ] dishes\n", None)
+            .unwrap();
+
+        let note = notebook
+            .modify("notes/chores", None, &mut |content| {
+                Some(content.replace("[ ]", "[x]"))
+            })
+            .unwrap();
+
+        assert_eq!(note.unwrap().content, "- [x] dishes\n");
+        assert!(notebook
+            .modify("notes/chores", None, &mut |_| None)
+            .unwrap()
+            .is_none());
+        assert!(!notebook.is_encrypted("notes/chores"));
+    }
+
+    #[test]
+    fn list_test() {
+        let notebook = setup_notebook(HashMap::new());
+
+        notebook.create("notes/chores", "hello\n", None).unwrap();
+        notebook.create("work/plan", "plans\n", None).unwrap();
+
+        let identifiers = |notes: Vec<NoteInfo>| -> Vec<String> {
+            notes.into_iter().map(|note| note.identifier).collect()
+        };
+
+        assert_eq!(
+            identifiers(notebook.list(None).unwrap()),
+            vec!["notes/chores", "work/plan"]
+        );
+        assert_eq!(
+            identifiers(notebook.list(Some(Path::new("work"))).unwrap()),
+            vec!["work/plan"]
+        );
+        assert_eq!(notebook.list(None).unwrap()[0].len, 6);
+        assert!(matches!(
+            notebook.save(None),
+            Err(GnotesError::NoRepository)
+        ));
+    }
+}
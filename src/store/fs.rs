@@ -0,0 +1,199 @@
+use crate::common::lock::{write_atomically, NotebookLock};
+use crate::common::notes::list_note_identifiers;
+use crate::common::tags::{load_inline_tags, load_tags, update_inline_tags, update_tags, Tags};
+use crate::error::GnotesResult;
+use crate::store::{NoteMetadata, NoteStore, TagStore};
+use log::debug;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Keeps each note in a file at `notes_dir/dir/name`.
+#[derive(Debug, Clone)]
+pub struct FsNoteStore {
+    notes_dir: PathBuf,
+}
+
+impl FsNoteStore {
+    pub fn new(notes_dir: &Path) -> Self {
+        Self {
+            notes_dir: notes_dir.to_path_buf(),
+        }
+    }
+
+    fn create_parent_dir(&self, note_file_path: &Path) -> GnotesResult<()> {
+        if let Some(note_parent_dir) = note_file_path.parent() {
+            fs::create_dir_all(note_parent_dir)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NoteStore for FsNoteStore {
+    fn exists(&self, note_identifier: &str) -> bool {
+        self.notes_dir.join(note_identifier).is_file()
+    }
+
+    fn read(&self, note_identifier: &str) -> GnotesResult<String> {
+        Ok(fs::read_to_string(self.notes_dir.join(note_identifier))?)
+    }
+
+    fn read_prefix(&self, note_identifier: &str, len: usize) -> GnotesResult<Vec<u8>> {
+        let mut prefix = vec![];
+
+        File::open(self.notes_dir.join(note_identifier))?
+            .take(len as u64)
+            .read_to_end(&mut prefix)?;
+
+        Ok(prefix)
+    }
+
+    fn write(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        let note_file_path = self.notes_dir.join(note_identifier);
+
+        debug!("Writing {:?}", note_file_path);
+
+        self.create_parent_dir(&note_file_path)?;
+        write_atomically(&note_file_path, content.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn append(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        let note_file_path = self.notes_dir.join(note_identifier);
+
+        debug!("Appending '{}' to {:?}", content, note_file_path);
+
+        self.create_parent_dir(&note_file_path)?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&note_file_path)?
+            .write_all(content.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn list(&self) -> GnotesResult<Vec<String>> {
+        Ok(list_note_identifiers(&self.notes_dir)?)
+    }
+
+    fn remove(&self, note_identifier: &str) -> GnotesResult<()> {
+        Ok(fs::remove_file(self.notes_dir.join(note_identifier))?)
+    }
+
+    fn rename(&self, note_identifier: &str, new_note_identifier: &str) -> GnotesResult<()> {
+        let new_note_file_path = self.notes_dir.join(new_note_identifier);
+
+        debug!("Moving {} to {:?}", note_identifier, new_note_file_path);
+
+        self.create_parent_dir(&new_note_file_path)?;
+        fs::rename(self.notes_dir.join(note_identifier), new_note_file_path)?;
+
+        Ok(())
+    }
+
+    fn metadata(&self, note_identifier: &str) -> GnotesResult<NoteMetadata> {
+        let metadata = self.notes_dir.join(note_identifier).metadata()?;
+
+        Ok(NoteMetadata {
+            len: metadata.len(),
+            created: metadata.created()?,
+            modified: metadata.modified()?,
+        })
+    }
+}
+
+/// Keeps the tags in the `.tags` and `.inline-tags` files of the notes directory.
+#[derive(Debug)]
+pub struct FsTagStore {
+    notes_dir: PathBuf,
+    lock: NotebookLock,
+}
+
+impl FsTagStore {
+    pub fn new(notes_dir: &Path) -> Self {
+        Self {
+            notes_dir: notes_dir.to_path_buf(),
+            lock: NotebookLock::new(notes_dir),
+        }
+    }
+}
+
+impl TagStore for FsTagStore {
+    fn load_tags(&self) -> GnotesResult<Tags> {
+        Ok(load_tags(&self.notes_dir)?)
+    }
+
+    fn update_tags(&self, tags: &Tags) -> GnotesResult<()> {
+        Ok(update_tags(&self.notes_dir, tags)?)
+    }
+
+    fn load_inline_tags(&self) -> GnotesResult<Tags> {
+        Ok(load_inline_tags(&self.notes_dir)?)
+    }
+
+    fn update_inline_tags(&self, tags: &Tags) -> GnotesResult<()> {
+        Ok(update_inline_tags(&self.notes_dir, tags)?)
+    }
+
+    fn locked(&self, f: &mut dyn FnMut() -> GnotesResult<()>) -> GnotesResult<()> {
+        self.lock.run(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use maplit::{hashmap, hashset};
+    use tempdir::TempDir;
+
+    #[test]
+    fn note_store_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+        let store = FsNoteStore::new(notes_dir.path());
+
+        store.append("notes/chores", "hello\n").unwrap();
+        store.append("notes/chores", "bye\n").unwrap();
+        store.write("work/plan", "plans\n").unwrap();
+        fs::write(notes_dir.path().join(".tags"), "{}").unwrap();
+
+        assert!(store.exists("notes/chores"));
+        assert_eq!(store.read("notes/chores").unwrap(), "hello\nbye\n");
+        assert_eq!(store.read_prefix("notes/chores", 3).unwrap(), b"hel");
+        assert_eq!(
+            store.read_prefix("notes/chores", 20).unwrap(),
+            b"hello\nbye\n"
+        );
+        assert_eq!(store.metadata("notes/chores").unwrap().len, 10);
+        assert_eq!(store.list().unwrap(), vec!["notes/chores", "work/plan"]);
+
+        store.remove("notes/chores").unwrap();
+
+        assert!(!store.exists("notes/chores"));
+        assert!(store.read("notes/chores").is_err());
+
+        store.rename("work/plan", "archive/plan").unwrap();
+
+        assert!(!store.exists("work/plan"));
+        assert_eq!(store.read("archive/plan").unwrap(), "plans\n");
+    }
+
+    #[test]
+    fn tag_store_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+        let store = FsTagStore::new(notes_dir.path());
+        let tags = hashmap! {
+            String::from("home") => hashset! { String::from("notes/chores") },
+        };
+
+        assert_eq!(store.load_tags().unwrap(), hashmap! {});
+
+        store.update_tags(&tags).unwrap();
+
+        assert_eq!(store.load_tags().unwrap(), tags);
+        assert_eq!(load_tags(notes_dir.path()).unwrap(), tags);
+        assert_eq!(store.load_inline_tags().unwrap(), hashmap! {});
+    }
+}
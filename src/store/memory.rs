@@ -0,0 +1,172 @@
+use crate::common::tags::Tags;
+use crate::error::GnotesResult;
+use crate::store::{NoteMetadata, NoteStore, TagStore};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::io;
+use std::time::SystemTime;
+
+#[derive(Debug, Clone)]
+struct MemoryNote {
+    content: String,
+    created: SystemTime,
+    modified: SystemTime,
+}
+
+/// Keeps the notes in memory, e.g. for tests. Missing notes fail with the same `NotFound` error
+/// as the filesystem.
+#[derive(Debug, Default)]
+pub struct MemoryNoteStore {
+    notes: RefCell<BTreeMap<String, MemoryNote>>,
+}
+
+fn not_found(note_identifier: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("note '{}' not found", note_identifier),
+    )
+}
+
+impl MemoryNoteStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl NoteStore for MemoryNoteStore {
+    fn exists(&self, note_identifier: &str) -> bool {
+        self.notes.borrow().contains_key(note_identifier)
+    }
+
+    fn read(&self, note_identifier: &str) -> GnotesResult<String> {
+        self.notes
+            .borrow()
+            .get(note_identifier)
+            .map(|note| note.content.clone())
+            .ok_or_else(|| not_found(note_identifier).into())
+    }
+
+    fn write(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        let now = SystemTime::now();
+        let mut notes = self.notes.borrow_mut();
+        let note = notes
+            .entry(String::from(note_identifier))
+            .or_insert_with(|| MemoryNote {
+                content: String::new(),
+                created: now,
+                modified: now,
+            });
+
+        note.content = String::from(content);
+        note.modified = now;
+
+        Ok(())
+    }
+
+    fn append(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        let existing = self.read(note_identifier).unwrap_or_default();
+
+        self.write(note_identifier, &(existing + content))
+    }
+
+    fn list(&self) -> GnotesResult<Vec<String>> {
+        Ok(self.notes.borrow().keys().cloned().collect())
+    }
+
+    fn remove(&self, note_identifier: &str) -> GnotesResult<()> {
+        self.notes
+            .borrow_mut()
+            .remove(note_identifier)
+            .map(|_| ())
+            .ok_or_else(|| not_found(note_identifier).into())
+    }
+
+    fn rename(&self, note_identifier: &str, new_note_identifier: &str) -> GnotesResult<()> {
+        let mut notes = self.notes.borrow_mut();
+        let note = notes
+            .remove(note_identifier)
+            .ok_or_else(|| not_found(note_identifier))?;
+
+        notes.insert(String::from(new_note_identifier), note);
+
+        Ok(())
+    }
+
+    fn metadata(&self, note_identifier: &str) -> GnotesResult<NoteMetadata> {
+        self.notes
+            .borrow()
+            .get(note_identifier)
+            .map(|note| NoteMetadata {
+                len: note.content.len() as u64,
+                created: note.created,
+                modified: note.modified,
+            })
+            .ok_or_else(|| not_found(note_identifier).into())
+    }
+}
+
+/// Keeps the tags in memory, e.g. for tests.
+#[derive(Debug, Default)]
+pub struct MemoryTagStore {
+    tags: RefCell<Tags>,
+    inline_tags: RefCell<Tags>,
+}
+
+impl MemoryTagStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TagStore for MemoryTagStore {
+    fn load_tags(&self) -> GnotesResult<Tags> {
+        Ok(self.tags.borrow().clone())
+    }
+
+    fn update_tags(&self, tags: &Tags) -> GnotesResult<()> {
+        *self.tags.borrow_mut() = tags.clone();
+
+        Ok(())
+    }
+
+    fn load_inline_tags(&self) -> GnotesResult<Tags> {
+        Ok(self.inline_tags.borrow().clone())
+    }
+
+    fn update_inline_tags(&self, tags: &Tags) -> GnotesResult<()> {
+        *self.inline_tags.borrow_mut() = tags.clone();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::GnotesError;
+
+    #[test]
+    fn note_store_test() {
+        let store = MemoryNoteStore::new();
+
+        store.append("notes/chores", "hello\n").unwrap();
+        store.append("notes/chores", "bye\n").unwrap();
+        store.write("work/plan", "plans\n").unwrap();
+
+        assert!(store.exists("notes/chores"));
+        assert_eq!(store.read("notes/chores").unwrap(), "hello\nbye\n");
+        assert_eq!(store.metadata("notes/chores").unwrap().len, 10);
+        assert_eq!(store.list().unwrap(), vec!["notes/chores", "work/plan"]);
+
+        store.remove("notes/chores").unwrap();
+        store.rename("work/plan", "archive/plan").unwrap();
+
+        assert!(!store.exists("notes/chores"));
+        assert!(!store.exists("work/plan"));
+        assert_eq!(store.read("archive/plan").unwrap(), "plans\n");
+        assert!(matches!(
+            store.read("notes/chores"),
+            Err(GnotesError::Io(error)) if error.kind() == io::ErrorKind::NotFound
+        ));
+    }
+}
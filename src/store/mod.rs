@@ -0,0 +1,239 @@
+mod fs;
+mod memory;
+mod sqlite;
+
+pub use self::fs::{FsNoteStore, FsTagStore};
+pub use self::memory::{MemoryNoteStore, MemoryTagStore};
+pub use self::sqlite::{is_database_file, open_sqlite_stores, SqliteNoteStore, SqliteTagStore};
+
+use crate::common::hashtags::find_hashtags;
+use crate::common::tags::{self, apply_inline_tags, remove_from_tag, Tags};
+use crate::config::{Config, StoreKind};
+use crate::error::GnotesResult;
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// The metadata of a stored note.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoteMetadata {
+    pub len: u64,
+    pub created: SystemTime,
+    pub modified: SystemTime,
+}
+
+/// Where the notes are kept, addressed by their identifier, "dir/name". The content is stored as
+/// is, so encrypted notes are read and written as ciphertext.
+pub trait NoteStore: Debug {
+    fn exists(&self, note_identifier: &str) -> bool;
+
+    fn read(&self, note_identifier: &str) -> GnotesResult<String>;
+
+    /// The first `len` bytes of the note, or all of it when it's shorter, e.g. to find out whether
+    /// it's encrypted without reading all of it.
+    fn read_prefix(&self, note_identifier: &str, len: usize) -> GnotesResult<Vec<u8>> {
+        let mut prefix = self.read(note_identifier)?.into_bytes();
+        prefix.truncate(len);
+
+        Ok(prefix)
+    }
+
+    /// Writes the content, replacing the note or creating it.
+    fn write(&self, note_identifier: &str, content: &str) -> GnotesResult<()>;
+
+    /// Appends the content to the end of the note, creating it when it doesn't exist.
+    fn append(&self, note_identifier: &str, content: &str) -> GnotesResult<()>;
+
+    /// The identifiers of all the notes, sorted.
+    fn list(&self) -> GnotesResult<Vec<String>>;
+
+    fn remove(&self, note_identifier: &str) -> GnotesResult<()>;
+
+    /// Moves the note, with its metadata, to the new identifier, which must not exist.
+    fn rename(&self, note_identifier: &str, new_note_identifier: &str) -> GnotesResult<()>;
+
+    fn metadata(&self, note_identifier: &str) -> GnotesResult<NoteMetadata>;
+}
+
+/// Where the tags of the notes are kept. The inline tags are the ones derived from `#hashtags`,
+/// and are a subset of the tags.
+///
+/// The provided methods load and update all the tags. Stores which can do better, e.g. with
+/// indexed lookups, override them.
+pub trait TagStore: Debug {
+    fn load_tags(&self) -> GnotesResult<Tags>;
+
+    fn update_tags(&self, tags: &Tags) -> GnotesResult<()>;
+
+    fn load_inline_tags(&self) -> GnotesResult<Tags>;
+
+    fn update_inline_tags(&self, tags: &Tags) -> GnotesResult<()>;
+
+    /// Runs `f` while no other gnotes can change the tags, e.g. around loading, changing and
+    /// updating them. Can be nested.
+    fn locked(&self, f: &mut dyn FnMut() -> GnotesResult<()>) -> GnotesResult<()> {
+        f()
+    }
+
+    /// Applies `f` to the inline tags, writing them back only if they were changed.
+    fn modify_inline_tags(&self, f: &mut dyn FnMut(&mut Tags)) -> GnotesResult<()> {
+        self.locked(&mut || {
+            let inline_tags = self.load_inline_tags()?;
+            let mut modified = inline_tags.clone();
+
+            f(&mut modified);
+
+            if modified != inline_tags {
+                self.update_inline_tags(&modified)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Syncs the tags with the `#hashtags` found in the note content.
+    fn sync_inline_tags(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        let hashtags = find_hashtags(content);
+
+        self.locked(&mut || {
+            let mut tags = self.load_tags()?;
+            let mut inline_tags = self.load_inline_tags()?;
+
+            apply_inline_tags(&mut tags, &mut inline_tags, note_identifier, &hashtags);
+
+            self.update_tags(&tags)?;
+            self.update_inline_tags(&inline_tags)
+        })
+    }
+
+    fn tag_note(&self, note_identifier: &str, tags: &[String]) -> GnotesResult<()> {
+        self.locked(&mut || {
+            let mut all_tags = self.load_tags()?;
+
+            for tag in tags {
+                all_tags
+                    .entry(tag.clone())
+                    .or_default()
+                    .insert(String::from(note_identifier));
+            }
+
+            self.update_tags(&all_tags)?;
+
+            // Tags added by hand are kept even if the note has a matching hashtag which is later removed.
+            self.modify_inline_tags(&mut |inline_tags| {
+                for tag in tags {
+                    remove_from_tag(inline_tags, tag, note_identifier);
+                }
+            })
+        })
+    }
+
+    fn untag_note(&self, note_identifier: &str, tag: &str) -> GnotesResult<()> {
+        self.locked(&mut || {
+            let mut tags = self.load_tags()?;
+
+            remove_from_tag(&mut tags, tag, note_identifier);
+
+            self.update_tags(&tags)?;
+            self.modify_inline_tags(&mut |inline_tags| {
+                remove_from_tag(inline_tags, tag, note_identifier)
+            })
+        })
+    }
+
+    /// Removes the note from all its tags.
+    fn remove_note(&self, note_identifier: &str) -> GnotesResult<()> {
+        self.locked(&mut || {
+            let mut tags = self.load_tags()?;
+            let all_tags: Vec<String> = tags.keys().cloned().collect();
+
+            for tag in &all_tags {
+                remove_from_tag(&mut tags, tag, note_identifier);
+            }
+
+            self.update_tags(&tags)?;
+            self.modify_inline_tags(&mut |inline_tags| {
+                for tag in &all_tags {
+                    remove_from_tag(inline_tags, tag, note_identifier);
+                }
+            })
+        })
+    }
+
+    /// Moves the tags of the note to its new identifier, after it was renamed or moved.
+    fn rename_note(&self, note_identifier: &str, new_note_identifier: &str) -> GnotesResult<()> {
+        self.locked(&mut || {
+            let mut tags = self.load_tags()?;
+            let mut tags_changed = false;
+            for tags_set in tags.values_mut() {
+                if tags_set.remove(note_identifier) {
+                    tags_set.insert(String::from(new_note_identifier));
+                    tags_changed = true;
+                }
+            }
+
+            if tags_changed {
+                self.update_tags(&tags)?;
+            }
+
+            self.modify_inline_tags(&mut |inline_tags| {
+                for tags_set in inline_tags.values_mut() {
+                    if tags_set.remove(note_identifier) {
+                        tags_set.insert(String::from(new_note_identifier));
+                    }
+                }
+            })
+        })
+    }
+
+    /// Finds the notes tagged with `tag`, or with its nested tags unless `exact` is set.
+    fn find_tagged_notes(&self, tag: &str, exact: bool) -> GnotesResult<BTreeSet<String>> {
+        Ok(tags::find_tagged_notes(&self.load_tags()?, tag, exact)
+            .into_iter()
+            .cloned()
+            .collect())
+    }
+
+    /// Replaces the tags with the ones in the `.tags` and `.inline-tags` files of the notes
+    /// directory.
+    fn import_json(&self, notes_dir: &Path) -> GnotesResult<()> {
+        self.locked(&mut || {
+            self.update_tags(&tags::load_tags(notes_dir)?)?;
+            self.update_inline_tags(&tags::load_inline_tags(notes_dir)?)
+        })
+    }
+
+    /// Writes the tags to the `.tags` and `.inline-tags` files of the notes directory, e.g. so
+    /// they are saved to git with the notes.
+    fn export_json(&self, notes_dir: &Path) -> GnotesResult<()> {
+        tags::update_tags(notes_dir, &self.load_tags()?)?;
+        tags::update_inline_tags(notes_dir, &self.load_inline_tags()?)?;
+
+        Ok(())
+    }
+}
+
+/// Opens the tag store of the configured kind.
+pub fn open_tag_store(config: &Config) -> GnotesResult<Box<dyn TagStore>> {
+    Ok(match config.store {
+        StoreKind::Json => Box::new(FsTagStore::new(&config.notes_dir)),
+        StoreKind::Sqlite => Box::new(SqliteTagStore::open(&config.notes_dir)?),
+    })
+}
+
+/// Opens the note and tag stores of the configured kind, sharing what they can, e.g. the
+/// connection to the SQLite database.
+pub fn open_stores(config: &Config) -> GnotesResult<(Box<dyn NoteStore>, Box<dyn TagStore>)> {
+    Ok(match config.store {
+        StoreKind::Json => (
+            Box::new(FsNoteStore::new(&config.notes_dir)),
+            Box::new(FsTagStore::new(&config.notes_dir)),
+        ),
+        StoreKind::Sqlite => {
+            let (notes, tags) = open_sqlite_stores(&config.notes_dir)?;
+
+            (Box::new(notes), Box::new(tags))
+        }
+    })
+}
@@ -0,0 +1,802 @@
+use crate::common::hashtags::find_hashtags;
+use crate::common::lock::NotebookLock;
+use crate::common::tags::{
+    self, format_tags, load_inline_tags, load_tags, merge_tags, Tags, INLINE_TAGS_FILE_NAME,
+    TAGS_FILE_NAME, TAG_SEPARATOR,
+};
+use crate::error::{GnotesError, GnotesResult};
+use crate::store::{FsNoteStore, NoteMetadata, NoteStore, TagStore};
+use log::debug;
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DATABASE_FILE_NAME: &str = ".gnotes.db";
+const SCHEMA_VERSION: i32 = 3;
+const TAGS_TABLE: &str = "tags";
+const INLINE_TAGS_TABLE: &str = "inline_tags";
+
+const SCHEMA: &str = "
+CREATE TABLE tags (
+    tag TEXT NOT NULL,
+    note_identifier TEXT NOT NULL,
+    PRIMARY KEY (tag, note_identifier)
+);
+CREATE INDEX tags_note_identifier ON tags (note_identifier);
+CREATE TABLE inline_tags (
+    tag TEXT NOT NULL,
+    note_identifier TEXT NOT NULL,
+    PRIMARY KEY (tag, note_identifier)
+);
+CREATE INDEX inline_tags_note_identifier ON inline_tags (note_identifier);
+CREATE TABLE notes (
+    identifier TEXT PRIMARY KEY,
+    created INTEGER NOT NULL
+);
+";
+
+/// The tags files as they were last imported or exported, to find out what changed in them since.
+const SYNCED_SCHEMA: &str = "
+CREATE TABLE synced (
+    file_name TEXT PRIMARY KEY,
+    content TEXT NOT NULL
+);
+";
+
+/// The length and modification time of the tags files when they were last synced, so files which
+/// didn't change since aren't read again.
+const FINGERPRINT_SCHEMA: &str = "
+ALTER TABLE synced ADD COLUMN fingerprint TEXT;
+";
+
+/// Whether the path, relative to the notes directory, is the database or one of its temporary
+/// files. These aren't saved to git, the tags are exported to JSON instead.
+pub fn is_database_file(path: &Path) -> bool {
+    path.to_string_lossy().starts_with(DATABASE_FILE_NAME)
+}
+
+fn insert_tags(connection: &Connection, table: &str, tags: &Tags) -> GnotesResult<()> {
+    let mut statement = connection.prepare(&format!(
+        "INSERT OR IGNORE INTO {} (tag, note_identifier) VALUES (?1, ?2)",
+        table
+    ))?;
+
+    for (tag, tags_set) in tags {
+        for note_identifier in tags_set {
+            statement.execute(params![tag, note_identifier])?;
+        }
+    }
+
+    Ok(())
+}
+
+fn load_table(connection: &Connection, table: &str) -> GnotesResult<Tags> {
+    let mut statement =
+        connection.prepare(&format!("SELECT tag, note_identifier FROM {}", table))?;
+    let mut rows = statement.query([])?;
+    let mut tags = Tags::new();
+
+    while let Some(row) = rows.next()? {
+        tags.entry(row.get(0)?).or_default().insert(row.get(1)?);
+    }
+
+    Ok(tags)
+}
+
+fn replace_table(connection: &Connection, table: &str, tags: &Tags) -> GnotesResult<()> {
+    connection.execute(&format!("DELETE FROM {}", table), [])?;
+
+    insert_tags(connection, table, tags)
+}
+
+/// The length and modification time of the tags file, or `None` when it doesn't exist.
+fn fingerprint(notes_dir: &Path, file_name: &str) -> GnotesResult<Option<String>> {
+    let metadata = match fs::metadata(notes_dir.join(file_name)) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(error.into()),
+    };
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+
+    Ok(Some(format!("{}:{}", metadata.len(), modified)))
+}
+
+fn record_synced(
+    connection: &Connection,
+    file_name: &str,
+    tags: &Tags,
+    fingerprint: &Option<String>,
+) -> GnotesResult<()> {
+    connection.execute(
+        "INSERT OR REPLACE INTO synced (file_name, content, fingerprint) VALUES (?1, ?2, ?3)",
+        params![file_name, format_tags(tags)?, fingerprint],
+    )?;
+
+    Ok(())
+}
+
+/// Merges the changes made to the tags file since it was last synced, e.g. by a git pull, into
+/// its table, so they aren't overwritten by the next export. Without a previous sync, e.g. in a
+/// new database, the tags of the file are added to the table.
+fn sync_json_file(
+    connection: &Connection,
+    notes_dir: &Path,
+    file_name: &str,
+    table: &str,
+    load: fn(&Path) -> anyhow::Result<Tags>,
+) -> GnotesResult<()> {
+    // The fingerprint is taken before loading, so a change made in between is synced next time.
+    let file_fingerprint = fingerprint(notes_dir, file_name)?;
+    let synced: Option<(String, Option<String>)> = connection
+        .query_row(
+            "SELECT content, fingerprint FROM synced WHERE file_name = ?1",
+            [file_name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+
+    if let Some((_, synced_fingerprint)) = &synced {
+        if synced_fingerprint.is_some() && *synced_fingerprint == file_fingerprint {
+            return Ok(());
+        }
+    }
+
+    let file_tags = load(notes_dir)?;
+    let synced = synced.map(|(content, _)| content);
+
+    if synced.as_deref() == Some(format_tags(&file_tags)?.as_str()) {
+        return record_synced(connection, file_name, &file_tags, &file_fingerprint);
+    }
+
+    debug!("Merging the changes of {} into the database", file_name);
+
+    let base = match synced {
+        Some(synced) => serde_json::from_str(&synced)
+            .map_err(|error| GnotesError::Database(error.to_string()))?,
+        _ => Tags::new(),
+    };
+    let merged = merge_tags(&base, &load_table(connection, table)?, &file_tags);
+
+    replace_table(connection, table, &merged)?;
+    record_synced(connection, file_name, &file_tags, &file_fingerprint)
+}
+
+fn sync_json(connection: &Connection, notes_dir: &Path) -> GnotesResult<()> {
+    sync_json_file(connection, notes_dir, TAGS_FILE_NAME, TAGS_TABLE, load_tags)?;
+    sync_json_file(
+        connection,
+        notes_dir,
+        INLINE_TAGS_FILE_NAME,
+        INLINE_TAGS_TABLE,
+        load_inline_tags,
+    )
+}
+
+/// Opens the database of the notes directory, creating or migrating it when needed, and merges
+/// the changes of the `.tags` and `.inline-tags` files into it. A new database gets the tags of
+/// the files, so switching to the SQLite store keeps the existing tags.
+fn open_database(notes_dir: &Path) -> GnotesResult<Connection> {
+    fs::create_dir_all(notes_dir)?;
+
+    let mut connection = Connection::open(notes_dir.join(DATABASE_FILE_NAME))?;
+    connection.busy_timeout(Duration::from_secs(5))?;
+
+    // The version is checked again inside the transaction, in case another gnotes created it.
+    let transaction = connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+    let version: i32 = transaction.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    if version == 0 {
+        debug!("Creating the database in {:?}", notes_dir);
+
+        transaction.execute_batch(SCHEMA)?;
+    }
+
+    if version < 2 {
+        transaction.execute_batch(SYNCED_SCHEMA)?;
+    }
+
+    if version < 3 {
+        transaction.execute_batch(FINGERPRINT_SCHEMA)?;
+        transaction.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    }
+
+    sync_json(&transaction, notes_dir)?;
+    transaction.commit()?;
+
+    Ok(connection)
+}
+
+/// Runs `f` in a savepoint, which unlike a transaction can be nested.
+fn with_savepoint<T, F>(connection: &Connection, f: F) -> GnotesResult<T>
+where
+    F: FnOnce() -> GnotesResult<T>,
+{
+    connection.execute_batch("SAVEPOINT gnotes")?;
+
+    match f() {
+        Ok(value) => {
+            connection.execute_batch("RELEASE gnotes")?;
+
+            Ok(value)
+        }
+        Err(error) => {
+            connection.execute_batch("ROLLBACK TO gnotes; RELEASE gnotes")?;
+
+            Err(error)
+        }
+    }
+}
+
+/// Keeps the tags in the SQLite database of the notes directory, updating them in transactions
+/// and looking them up by index.
+#[derive(Debug)]
+pub struct SqliteTagStore {
+    connection: Rc<Connection>,
+    lock: NotebookLock,
+}
+
+impl SqliteTagStore {
+    pub fn open(notes_dir: &Path) -> GnotesResult<Self> {
+        Ok(Self::with_connection(
+            notes_dir,
+            Rc::new(open_database(notes_dir)?),
+        ))
+    }
+
+    fn with_connection(notes_dir: &Path, connection: Rc<Connection>) -> Self {
+        Self {
+            connection,
+            lock: NotebookLock::new(notes_dir),
+        }
+    }
+
+    fn load(&self, table: &str) -> GnotesResult<Tags> {
+        load_table(&self.connection, table)
+    }
+
+    fn update(&self, table: &str, tags: &Tags) -> GnotesResult<()> {
+        with_savepoint(&self.connection, || {
+            replace_table(&self.connection, table, tags)
+        })
+    }
+
+    fn delete_tag(&self, table: &str, tag: &str, note_identifier: &str) -> GnotesResult<usize> {
+        Ok(self.connection.execute(
+            &format!(
+                "DELETE FROM {} WHERE tag = ?1 AND note_identifier = ?2",
+                table
+            ),
+            params![tag, note_identifier],
+        )?)
+    }
+}
+
+impl TagStore for SqliteTagStore {
+    fn load_tags(&self) -> GnotesResult<Tags> {
+        self.load(TAGS_TABLE)
+    }
+
+    fn update_tags(&self, tags: &Tags) -> GnotesResult<()> {
+        self.update(TAGS_TABLE, tags)
+    }
+
+    fn load_inline_tags(&self) -> GnotesResult<Tags> {
+        self.load(INLINE_TAGS_TABLE)
+    }
+
+    fn update_inline_tags(&self, tags: &Tags) -> GnotesResult<()> {
+        self.update(INLINE_TAGS_TABLE, tags)
+    }
+
+    fn locked(&self, f: &mut dyn FnMut() -> GnotesResult<()>) -> GnotesResult<()> {
+        self.lock.run(|| with_savepoint(&self.connection, f))
+    }
+
+    fn sync_inline_tags(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        let hashtags = find_hashtags(content);
+
+        with_savepoint(&self.connection, || {
+            let previous: Vec<String> = self
+                .connection
+                .prepare("SELECT tag FROM inline_tags WHERE note_identifier = ?1")?
+                .query_map([note_identifier], |row| row.get(0))?
+                .collect::<Result<_, _>>()?;
+
+            for tag in previous {
+                if !hashtags.contains(&tag) {
+                    self.delete_tag(TAGS_TABLE, &tag, note_identifier)?;
+                    self.delete_tag(INLINE_TAGS_TABLE, &tag, note_identifier)?;
+                }
+            }
+
+            // Only the tags the note didn't have already are inline tags.
+            for tag in &hashtags {
+                let inserted = self.connection.execute(
+                    "INSERT OR IGNORE INTO tags (tag, note_identifier) VALUES (?1, ?2)",
+                    params![tag, note_identifier],
+                )?;
+
+                if inserted > 0 {
+                    self.connection.execute(
+                        "INSERT OR IGNORE INTO inline_tags (tag, note_identifier) VALUES (?1, ?2)",
+                        params![tag, note_identifier],
+                    )?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    fn tag_note(&self, note_identifier: &str, tags: &[String]) -> GnotesResult<()> {
+        with_savepoint(&self.connection, || {
+            insert_tags(
+                &self.connection,
+                TAGS_TABLE,
+                &tags
+                    .iter()
+                    .map(|tag| (tag.clone(), [String::from(note_identifier)].into()))
+                    .collect(),
+            )?;
+
+            // Tags added by hand are kept even if the note has a matching hashtag which is later removed.
+            for tag in tags {
+                self.delete_tag(INLINE_TAGS_TABLE, tag, note_identifier)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn untag_note(&self, note_identifier: &str, tag: &str) -> GnotesResult<()> {
+        with_savepoint(&self.connection, || {
+            self.delete_tag(TAGS_TABLE, tag, note_identifier)?;
+            self.delete_tag(INLINE_TAGS_TABLE, tag, note_identifier)?;
+
+            Ok(())
+        })
+    }
+
+    fn remove_note(&self, note_identifier: &str) -> GnotesResult<()> {
+        with_savepoint(&self.connection, || {
+            for table in [TAGS_TABLE, INLINE_TAGS_TABLE] {
+                self.connection.execute(
+                    &format!("DELETE FROM {} WHERE note_identifier = ?1", table),
+                    [note_identifier],
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn rename_note(&self, note_identifier: &str, new_note_identifier: &str) -> GnotesResult<()> {
+        with_savepoint(&self.connection, || {
+            for table in [TAGS_TABLE, INLINE_TAGS_TABLE] {
+                self.connection.execute(
+                    &format!(
+                        "UPDATE OR REPLACE {} SET note_identifier = ?2 WHERE note_identifier = ?1",
+                        table
+                    ),
+                    [note_identifier, new_note_identifier],
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    fn find_tagged_notes(&self, tag: &str, exact: bool) -> GnotesResult<BTreeSet<String>> {
+        // The nested tags are the ones between "tag/" and "tag0", as '0' comes right after '/'.
+        // Unlike `LIKE`, this range is looked up in the index.
+        let query = if exact {
+            "SELECT DISTINCT note_identifier FROM tags WHERE tag = ?1"
+        } else {
+            "SELECT DISTINCT note_identifier FROM tags WHERE tag = ?1 OR (tag >= ?2 AND tag < ?3)"
+        };
+        let mut statement = self.connection.prepare(query)?;
+        let nested_start = format!("{}{}", tag, TAG_SEPARATOR);
+        let nested_end = format!("{}{}", tag, (TAG_SEPARATOR as u8 + 1) as char);
+        let note_identifiers = if exact {
+            statement
+                .query_map([tag], |row| row.get(0))?
+                .collect::<Result<_, _>>()?
+        } else {
+            statement
+                .query_map([tag, &nested_start, &nested_end], |row| row.get(0))?
+                .collect::<Result<_, _>>()?
+        };
+
+        Ok(note_identifiers)
+    }
+
+    fn import_json(&self, notes_dir: &Path) -> GnotesResult<()> {
+        self.locked(&mut || {
+            let tags_fingerprint = fingerprint(notes_dir, TAGS_FILE_NAME)?;
+            let inline_tags_fingerprint = fingerprint(notes_dir, INLINE_TAGS_FILE_NAME)?;
+            let file_tags = load_tags(notes_dir)?;
+            let file_inline_tags = load_inline_tags(notes_dir)?;
+
+            self.update_tags(&file_tags)?;
+            self.update_inline_tags(&file_inline_tags)?;
+            record_synced(
+                &self.connection,
+                TAGS_FILE_NAME,
+                &file_tags,
+                &tags_fingerprint,
+            )?;
+            record_synced(
+                &self.connection,
+                INLINE_TAGS_FILE_NAME,
+                &file_inline_tags,
+                &inline_tags_fingerprint,
+            )
+        })
+    }
+
+    /// Merges the changes made to the tags files since the last sync first, so they aren't lost.
+    fn export_json(&self, notes_dir: &Path) -> GnotesResult<()> {
+        self.locked(&mut || {
+            sync_json(&self.connection, notes_dir)?;
+
+            let tags = self.load_tags()?;
+            let inline_tags = self.load_inline_tags()?;
+
+            tags::update_tags(notes_dir, &tags)?;
+            tags::update_inline_tags(notes_dir, &inline_tags)?;
+            record_synced(
+                &self.connection,
+                TAGS_FILE_NAME,
+                &tags,
+                &fingerprint(notes_dir, TAGS_FILE_NAME)?,
+            )?;
+            record_synced(
+                &self.connection,
+                INLINE_TAGS_FILE_NAME,
+                &inline_tags,
+                &fingerprint(notes_dir, INLINE_TAGS_FILE_NAME)?,
+            )
+        })
+    }
+}
+
+/// Opens the note and tag stores over one connection, so the database is only opened and synced
+/// once.
+pub fn open_sqlite_stores(notes_dir: &Path) -> GnotesResult<(SqliteNoteStore, SqliteTagStore)> {
+    let connection = Rc::new(open_database(notes_dir)?);
+
+    Ok((
+        SqliteNoteStore::with_connection(notes_dir, connection.clone()),
+        SqliteTagStore::with_connection(notes_dir, connection),
+    ))
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+/// Keeps the notes in files like `FsNoteStore`, and their creation times in the SQLite database of
+/// the notes directory, as git and some filesystems don't keep them.
+#[derive(Debug)]
+pub struct SqliteNoteStore {
+    notes_dir: PathBuf,
+    files: FsNoteStore,
+    connection: Rc<Connection>,
+}
+
+impl SqliteNoteStore {
+    pub fn open(notes_dir: &Path) -> GnotesResult<Self> {
+        Ok(Self::with_connection(
+            notes_dir,
+            Rc::new(open_database(notes_dir)?),
+        ))
+    }
+
+    fn with_connection(notes_dir: &Path, connection: Rc<Connection>) -> Self {
+        Self {
+            notes_dir: notes_dir.to_path_buf(),
+            files: FsNoteStore::new(notes_dir),
+            connection,
+        }
+    }
+
+    /// Records the creation time of the note, unless it's already known.
+    fn record_created(&self, note_identifier: &str, created: SystemTime) -> GnotesResult<i64> {
+        self.connection.execute(
+            "INSERT OR IGNORE INTO notes (identifier, created) VALUES (?1, ?2)",
+            params![note_identifier, to_millis(created)],
+        )?;
+
+        Ok(self.connection.query_row(
+            "SELECT created FROM notes WHERE identifier = ?1",
+            [note_identifier],
+            |row| row.get(0),
+        )?)
+    }
+}
+
+impl NoteStore for SqliteNoteStore {
+    fn exists(&self, note_identifier: &str) -> bool {
+        self.files.exists(note_identifier)
+    }
+
+    fn read(&self, note_identifier: &str) -> GnotesResult<String> {
+        self.files.read(note_identifier)
+    }
+
+    fn read_prefix(&self, note_identifier: &str, len: usize) -> GnotesResult<Vec<u8>> {
+        self.files.read_prefix(note_identifier, len)
+    }
+
+    fn write(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        self.files.write(note_identifier, content)?;
+        self.record_created(note_identifier, SystemTime::now())?;
+
+        Ok(())
+    }
+
+    fn append(&self, note_identifier: &str, content: &str) -> GnotesResult<()> {
+        self.files.append(note_identifier, content)?;
+        self.record_created(note_identifier, SystemTime::now())?;
+
+        Ok(())
+    }
+
+    fn list(&self) -> GnotesResult<Vec<String>> {
+        self.files.list()
+    }
+
+    fn remove(&self, note_identifier: &str) -> GnotesResult<()> {
+        self.files.remove(note_identifier)?;
+        self.connection
+            .execute("DELETE FROM notes WHERE identifier = ?1", [note_identifier])?;
+
+        Ok(())
+    }
+
+    fn rename(&self, note_identifier: &str, new_note_identifier: &str) -> GnotesResult<()> {
+        self.files.rename(note_identifier, new_note_identifier)?;
+        self.connection.execute(
+            "UPDATE OR REPLACE notes SET identifier = ?2 WHERE identifier = ?1",
+            [note_identifier, new_note_identifier],
+        )?;
+
+        Ok(())
+    }
+
+    fn metadata(&self, note_identifier: &str) -> GnotesResult<NoteMetadata> {
+        let metadata = self.notes_dir.join(note_identifier).metadata()?;
+        let modified = metadata.modified()?;
+        // Notes which were created outside of gnotes, e.g. cloned, are recorded when first seen.
+        let created = self
+            .connection
+            .query_row(
+                "SELECT created FROM notes WHERE identifier = ?1",
+                [note_identifier],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let created = match created {
+            Some(created) => created,
+            _ => self.record_created(note_identifier, metadata.created().unwrap_or(modified))?,
+        };
+
+        Ok(NoteMetadata {
+            len: metadata.len(),
+            created: UNIX_EPOCH + Duration::from_millis(created as u64),
+            modified,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tags::update_tags;
+    use maplit::{btreeset, hashmap, hashset};
+    use tempdir::TempDir;
+
+    #[test]
+    fn open_imports_json_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+        let tags = hashmap! {
+            String::from("home") => hashset! { String::from("notes/chores") },
+        };
+
+        update_tags(notes_dir.path(), &tags).unwrap();
+
+        let store = SqliteTagStore::open(notes_dir.path()).unwrap();
+
+        assert_eq!(store.load_tags().unwrap(), tags);
+
+        store.untag_note("notes/chores", "home").unwrap();
+
+        // The JSON is only imported again when it changed.
+        let store = SqliteTagStore::open(notes_dir.path()).unwrap();
+
+        assert_eq!(store.load_tags().unwrap(), hashmap! {});
+    }
+
+    #[test]
+    fn open_sqlite_stores_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+
+        update_tags(
+            notes_dir.path(),
+            &hashmap! {
+                String::from("home") => hashset! { String::from("notes/chores") },
+            },
+        )
+        .unwrap();
+
+        let (notes, tags) = open_sqlite_stores(notes_dir.path()).unwrap();
+
+        assert!(Rc::ptr_eq(&notes.connection, &tags.connection));
+
+        // The file is only read again once its fingerprint changes.
+        let synced_fingerprint: Option<String> = tags
+            .connection
+            .query_row(
+                "SELECT fingerprint FROM synced WHERE file_name = ?1",
+                [TAGS_FILE_NAME],
+                |row| row.get(0),
+            )
+            .unwrap();
+
+        assert_eq!(
+            synced_fingerprint,
+            fingerprint(notes_dir.path(), TAGS_FILE_NAME).unwrap()
+        );
+        assert!(synced_fingerprint.is_some());
+    }
+
+    #[test]
+    fn open_merges_changed_json_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+
+        update_tags(
+            notes_dir.path(),
+            &hashmap! {
+                String::from("home") => hashset! { String::from("notes/chores") },
+                String::from("work") => hashset! { String::from("notes/plan") },
+            },
+        )
+        .unwrap();
+
+        let store = SqliteTagStore::open(notes_dir.path()).unwrap();
+
+        store
+            .tag_note("notes/chores", &[String::from("garden")])
+            .unwrap();
+
+        // A pull which adds a tag, and removes another one.
+        update_tags(
+            notes_dir.path(),
+            &hashmap! {
+                String::from("home") => hashset! { String::from("notes/chores"), String::from("notes/tasks") },
+            },
+        )
+        .unwrap();
+
+        let expected = hashmap! {
+            String::from("home") => hashset! { String::from("notes/chores"), String::from("notes/tasks") },
+            String::from("garden") => hashset! { String::from("notes/chores") },
+        };
+        let store = SqliteTagStore::open(notes_dir.path()).unwrap();
+
+        assert_eq!(store.load_tags().unwrap(), expected);
+
+        store.export_json(notes_dir.path()).unwrap();
+
+        assert_eq!(load_tags(notes_dir.path()).unwrap(), expected);
+    }
+
+    #[test]
+    fn tag_store_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+        let store = SqliteTagStore::open(notes_dir.path()).unwrap();
+
+        store
+            .tag_note("notes/chores", &[String::from("home")])
+            .unwrap();
+        store
+            .tag_note("work/plan", &[String::from("home/garden")])
+            .unwrap();
+        store
+            .tag_note("work/plan", &[String::from("home0")])
+            .unwrap();
+        store
+            .sync_inline_tags("notes/plan", "#home/garden #work")
+            .unwrap();
+
+        assert_eq!(
+            store.find_tagged_notes("home", false).unwrap(),
+            btreeset! {
+                String::from("notes/chores"),
+                String::from("notes/plan"),
+                String::from("work/plan"),
+            }
+        );
+        assert_eq!(
+            store.find_tagged_notes("home", true).unwrap(),
+            btreeset! { String::from("notes/chores") }
+        );
+        assert_eq!(
+            store.load_inline_tags().unwrap(),
+            hashmap! {
+                String::from("home/garden") => hashset! { String::from("notes/plan") },
+                String::from("work") => hashset! { String::from("notes/plan") },
+            }
+        );
+
+        store.sync_inline_tags("notes/plan", "#work").unwrap();
+        store.remove_note("work/plan").unwrap();
+
+        assert_eq!(
+            store.load_tags().unwrap(),
+            hashmap! {
+                String::from("home") => hashset! { String::from("notes/chores") },
+                String::from("work") => hashset! { String::from("notes/plan") },
+            }
+        );
+
+        store.rename_note("notes/plan", "work/plan").unwrap();
+
+        assert_eq!(
+            store.load_inline_tags().unwrap(),
+            hashmap! {
+                String::from("work") => hashset! { String::from("work/plan") },
+            }
+        );
+    }
+
+    #[test]
+    fn export_json_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+        let store = SqliteTagStore::open(notes_dir.path()).unwrap();
+
+        store
+            .tag_note("notes/chores", &[String::from("home")])
+            .unwrap();
+        store.export_json(notes_dir.path()).unwrap();
+
+        assert_eq!(
+            load_tags(notes_dir.path()).unwrap(),
+            hashmap! {
+                String::from("home") => hashset! { String::from("notes/chores") },
+            }
+        );
+    }
+
+    #[test]
+    fn note_store_keeps_created_test() {
+        let notes_dir = TempDir::new("gnotes").unwrap();
+        let store = SqliteNoteStore::open(notes_dir.path()).unwrap();
+
+        store.write("notes/chores", "hello\n").unwrap();
+
+        let created = store.metadata("notes/chores").unwrap().created;
+
+        store.append("notes/chores", "bye\n").unwrap();
+
+        assert_eq!(store.metadata("notes/chores").unwrap().created, created);
+        assert_eq!(store.metadata("notes/chores").unwrap().len, 10);
+        assert_eq!(store.list().unwrap(), vec!["notes/chores"]);
+
+        store.rename("notes/chores", "work/chores").unwrap();
+
+        assert_eq!(store.metadata("work/chores").unwrap().created, created);
+        assert_eq!(store.list().unwrap(), vec!["work/chores"]);
+        assert!(is_database_file(Path::new(".gnotes.db-journal")));
+    }
+}
@@ -1,9 +1,10 @@
 mod setup;
 
-use crate::setup::DEFAULT_NOTE_FILE_NAME;
+use crate::setup::{write_encrypted_note, write_note, DEFAULT_NOTE_FILE_NAME};
 use anyhow::Result;
-use gnotes::common::notes::write_note;
-use setup::Setup;
+use gnotes::common::crypto::{decrypt, is_encrypted};
+use gnotes::common::tags::{load_inline_tags, load_tags};
+use setup::{RunOptions, Setup};
 use std::fs;
 
 #[test]
@@ -73,3 +74,201 @@ fn test_add_to_existing_note() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_add_to_encrypted_note() -> Result<()> {
+    let setup = Setup::new()?;
+    let expected_note_file_path = setup.default_note_path();
+
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello\n",
+        "secret",
+    )?;
+
+    setup
+        .run(
+            &["add", DEFAULT_NOTE_FILE_NAME, "do this and that"],
+            Some(RunOptions::with_passphrase("secret")),
+        )?
+        .success();
+
+    let content = fs::read_to_string(expected_note_file_path)?;
+
+    assert!(is_encrypted(&content));
+    assert_eq!(
+        decrypt(&content, "secret")?,
+        String::from("hello\ndo this and that\n")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_add_to_encrypted_note_wrong_passphrase() -> Result<()> {
+    let setup = Setup::new()?;
+    let expected_note_file_path = setup.default_note_path();
+
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello\n",
+        "secret",
+    )?;
+
+    let before = fs::read_to_string(&expected_note_file_path)?;
+
+    setup
+        .run(
+            &["add", DEFAULT_NOTE_FILE_NAME, "do this and that"],
+            Some(RunOptions::with_passphrase("wrong")),
+        )?
+        .failure();
+
+    assert_eq!(fs::read_to_string(expected_note_file_path)?, before);
+
+    Ok(())
+}
+
+#[test]
+fn test_add_inline_tags() -> Result<()> {
+    let setup = Setup::new()?;
+    let options = || {
+        Some(RunOptions {
+            inline_tags: true,
+            ..Default::default()
+        })
+    };
+
+    setup
+        .run(
+            &[
+                "add",
+                DEFAULT_NOTE_FILE_NAME,
+                "paged #oncall for #project/alpha",
+            ],
+            options(),
+        )?
+        .success();
+
+    let tags = load_tags(setup.notes_dir_path())?;
+
+    assert!(tags["oncall"].contains("notes/chores"));
+    assert!(tags["project/alpha"].contains("notes/chores"));
+
+    // Tagging manually keeps the tag once the hashtag is gone.
+    setup
+        .run(&["tag", DEFAULT_NOTE_FILE_NAME, "oncall"], options())?
+        .success();
+
+    fs::write(setup.default_note_path(), "nothing to see\n")?;
+
+    setup
+        .run(&["add", DEFAULT_NOTE_FILE_NAME, "still nothing"], options())?
+        .success();
+
+    let tags = load_tags(setup.notes_dir_path())?;
+
+    assert!(tags["oncall"].contains("notes/chores"));
+    assert!(!tags.contains_key("project/alpha"));
+
+    Ok(())
+}
+
+#[test]
+fn test_add_inline_tags_encrypted_note() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello\n",
+        "secret",
+    )?;
+
+    setup
+        .run(
+            &["add", DEFAULT_NOTE_FILE_NAME, "paged #secret"],
+            Some(RunOptions {
+                inline_tags: true,
+                ..RunOptions::with_passphrase("secret")
+            }),
+        )?
+        .success();
+
+    assert!(load_tags(setup.notes_dir_path())?.is_empty());
+    assert!(load_inline_tags(setup.notes_dir_path())?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_add_inline_tags_disabled() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["add", DEFAULT_NOTE_FILE_NAME, "paged #oncall"], None)?
+        .success();
+
+    assert!(load_tags(setup.notes_dir_path())?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_add_under_section() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "# Meeting\n## Action items\n- call mom\n\n## Notes\nsome notes",
+    )?;
+
+    setup
+        .run(
+            &[
+                "add",
+                DEFAULT_NOTE_FILE_NAME,
+                "- buy milk",
+                "--under",
+                "## Action items",
+            ],
+            None,
+        )?
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(setup.default_note_path())?,
+        "# Meeting\n## Action items\n- call mom\n- buy milk\n\n## Notes\nsome notes\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_add_under_missing_section() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "# Meeting",
+    )?;
+
+    setup
+        .run(
+            &["add", DEFAULT_NOTE_FILE_NAME, "text", "--under", "Missing"],
+            None,
+        )?
+        .stderr("add failed: section 'Missing' not found\n")
+        .code(6);
+
+    assert_eq!(
+        fs::read_to_string(setup.default_note_path())?,
+        "# Meeting\n"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,107 @@
+mod setup;
+
+use anyhow::Result;
+use chrono::prelude::Local;
+use chrono::{Duration, NaiveDate};
+use predicates::prelude::*;
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
+use std::fs;
+
+fn day(offset: i64) -> NaiveDate {
+    Local::today().naive_local() + Duration::days(offset)
+}
+
+fn write_notes(setup: &Setup) -> Result<()> {
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        &format!(
+            "- [ ] pay rent @due({})\n- [x] done @due({})\n- [ ] far away @due({})",
+            day(-2),
+            day(-1),
+            day(30)
+        ),
+    )?;
+    write_note(
+        &setup.note_parent_dir("work"),
+        "plan",
+        &format!(
+            "standup @remind({} 09:30)\nship it @due({})",
+            day(0),
+            day(3)
+        ),
+    )
+}
+
+#[test]
+fn test_agenda() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup.run(&["agenda"], None)?.success().stdout(format!(
+        "total 3
+{} (overdue)
+  due pay rent (notes/chores:1)
+{} (today)
+  remind 09:30 standup (work/plan:1)
+{}
+  due ship it (work/plan:2)
+",
+        day(-2).format("%Y-%m-%d %a"),
+        day(0).format("%Y-%m-%d %a"),
+        day(3).format("%Y-%m-%d %a"),
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_agenda_days_and_dir() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["agenda", "--days", "1", "--dir", "work"], None)?
+        .success()
+        .stdout(predicate::str::contains("total 1\n"))
+        .stdout(predicate::str::contains("standup (work/plan:1)"));
+
+    setup
+        .run(&["agenda", "--days", "31"], None)?
+        .success()
+        .stdout(predicate::str::contains("total 4\n"))
+        .stdout(predicate::str::contains("far away (notes/chores:3)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_agenda_ics() -> Result<()> {
+    let setup = Setup::new()?;
+    let ics_file_path = setup.notes_dir_path().join("agenda.ics");
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["agenda", "--ics", &ics_file_path.to_string_lossy()], None)?
+        .success()
+        .stdout(format!("exported 3 items to {}\n", ics_file_path.display()));
+
+    let ics = fs::read_to_string(ics_file_path)?;
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ics.matches("BEGIN:VEVENT\r\n").count(), 3);
+    assert!(ics.contains(&format!(
+        "DTSTART;VALUE=DATE:{}\r\n",
+        day(-2).format("%Y%m%d")
+    )));
+    assert!(ics.contains(&format!("DTSTART:{}T093000\r\n", day(0).format("%Y%m%d"))));
+    assert!(ics.contains("SUMMARY:standup\r\n"));
+    assert!(ics.contains("UID:work/plan:1:remind@gnotes\r\n"));
+    assert!(ics.contains("BEGIN:VALARM\r\n"));
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+mod setup;
+
+use anyhow::{Context, Result};
+use predicates::prelude::*;
+use setup::{write_encrypted_note, write_note, Setup, DEFAULT_NOTE_FILE_NAME};
+
+#[test]
+fn test_backlinks() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_note(
+        &setup.default_note_parent_dir(),
+        "reminders",
+        "see [[chores]]",
+    )?;
+    write_note(
+        &setup.note_parent_dir("custom"),
+        "standup",
+        "see [[notes/chores]] and [[chores]]",
+    )?;
+    write_note(&setup.note_parent_dir("custom"), "other", "see [[other]]")?;
+
+    setup
+        .run(&["backlinks", DEFAULT_NOTE_FILE_NAME], None)?
+        .success()
+        .stdout(predicate::eq("total 2\ncustom/standup\nnotes/reminders\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_backlinks_custom_dir() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.note_parent_dir("custom"),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_note(
+        &setup.default_note_parent_dir(),
+        "reminders",
+        "see [[custom/chores]] and [[chores]]",
+    )?;
+
+    setup
+        .run(
+            &["backlinks", DEFAULT_NOTE_FILE_NAME, "--dir", "custom"],
+            None,
+        )?
+        .success()
+        .stdout(predicate::eq("total 1\nnotes/reminders\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_backlinks_skips_encrypted_notes() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        "secret",
+        "see [[chores]]\n",
+        "secret",
+    )?;
+
+    setup
+        .run(&["backlinks", DEFAULT_NOTE_FILE_NAME], None)?
+        .success()
+        .stdout(predicate::eq("total 0\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_backlinks_note_does_not_exist() -> Result<()> {
+    let setup = Setup::new()?;
+    let note_file_path = setup.default_note_path();
+
+    setup
+        .run(&["backlinks", DEFAULT_NOTE_FILE_NAME], None)?
+        .stderr(format!(
+            "backlinks failed: file '{}' not found\n",
+            note_file_path.to_str().context("note_file_path.to_str()")?
+        ))
+        .code(4);
+
+    Ok(())
+}
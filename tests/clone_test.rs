@@ -11,9 +11,9 @@ fn test_clone_fails_without_repository() -> Result<()> {
     setup
         .run(&["clone"], None)?
         .stderr(predicate::eq(
-            "Can't clone without a repository. Please specify a repository in the config file.\n",
+            "clone failed: no repository is configured. Please specify a repository in the config file.\n",
         ))
-        .code(1);
+        .code(11);
 
     Ok(())
 }
@@ -26,6 +26,7 @@ fn test_clone_succeeds() -> Result<()> {
     let run_options = RunOptions {
         stdin: None,
         repository: Some(git_setup.bare_dir.path().to_path_buf()),
+        ..Default::default()
     };
 
     setup.run(&["clone"], Some(run_options))?.code(0);
@@ -0,0 +1,111 @@
+mod setup;
+
+use anyhow::Result;
+use gnotes::common::tags::update_tags;
+use maplit::{hashmap, hashset};
+use predicates::prelude::*;
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
+
+#[test]
+fn test_completions_bash() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["completions", "bash"], None)?
+        .stdout(predicate::str::contains("_gnotes()"))
+        .stdout(predicate::str::contains("gnotes __complete"))
+        .stdout(predicate::str::contains("complete -F _gnotes_dynamic"))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_completions_zsh() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["completions", "zsh"], None)?
+        .stdout(predicate::str::starts_with("#compdef gnotes"))
+        .stdout(predicate::str::contains("_gnotes_static()"))
+        .stdout(predicate::str::ends_with("_gnotes \"$@\"\n"))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_completions_fish() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["completions", "fish"], None)?
+        .stdout(predicate::str::contains("__fish_seen_subcommand_from show"))
+        .stdout(predicate::str::contains("function __gnotes_complete"))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_complete_note_names() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_note(&setup.note_parent_dir("work"), "standup", "hello")?;
+
+    setup
+        .run(&["__complete", "--", "show", ""], None)?
+        .stdout("chores\nstandup\n")
+        .success();
+    setup
+        .run(&["__complete", "--", "show", "--dir", "work", "st"], None)?
+        .stdout("standup\n")
+        .success();
+    setup
+        .run(&["__complete", "--", "show", "--dir", ""], None)?
+        .stdout("notes\nwork\n")
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_complete_tags() -> Result<()> {
+    let setup = Setup::new()?;
+
+    update_tags(
+        setup.notes_dir_path(),
+        &hashmap! {
+            String::from("home") => hashset! { String::from("notes/chores") },
+            String::from("project/alpha") => hashset! { String::from("work/standup") },
+        },
+    )?;
+
+    setup
+        .run(&["__complete", "--", "tag", "chores", "p"], None)?
+        .stdout("project/alpha\n")
+        .success();
+    setup
+        .run(&["__complete", "--", "search", ""], None)?
+        .stdout("home\nproject/alpha\n")
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_complete_other_words() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["__complete", "--", "show", "--"], None)?
+        .stdout("")
+        .code(2);
+
+    Ok(())
+}
@@ -1,9 +1,8 @@
 mod setup;
 
-use crate::setup::RunOptions;
 use crate::setup::DEFAULT_NOTE_FILE_NAME;
+use crate::setup::{write_note, RunOptions};
 use anyhow::Result;
-use gnotes::common::notes::write_note;
 use setup::Setup;
 use std::fs;
 
@@ -27,6 +26,7 @@ fn test_edit_note() -> Result<()> {
     let run_options = RunOptions {
         stdin: Some(stdin),
         repository: None,
+        ..Default::default()
     };
 
     setup
@@ -61,6 +61,7 @@ fn test_edit_note_custom_dir() -> Result<()> {
     let run_options = RunOptions {
         stdin: Some(stdin),
         repository: None,
+        ..Default::default()
     };
 
     setup
@@ -93,6 +94,7 @@ fn test_edit_none_existing_note() -> Result<()> {
     let run_options = RunOptions {
         stdin: Some(stdin),
         repository: None,
+        ..Default::default()
     };
 
     setup
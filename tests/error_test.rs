@@ -0,0 +1,70 @@
+mod setup;
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use setup::{write_note, Setup};
+
+fn parse_stderr(stderr: &[u8]) -> Result<Value> {
+    Ok(serde_json::from_slice(stderr)?)
+}
+
+#[test]
+fn test_error_format_json() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(&setup.default_note_parent_dir(), "choir", "la la")?;
+    write_note(&setup.default_note_parent_dir(), "chores", "hello")?;
+
+    let assert = setup
+        .run(&["show", "cho", "--error-format", "json"], None)?
+        .code(3);
+
+    assert_eq!(
+        parse_stderr(&assert.get_output().stderr)?,
+        json!({
+            "command": "show",
+            "kind": "ambiguous_name",
+            "message": "'cho' matches several notes. Did you mean:\n  notes/choir\n  notes/chores",
+            "exit_code": 3,
+            "matches": ["notes/choir", "notes/chores"],
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_error_format_json_sub_command() -> Result<()> {
+    let setup = Setup::new()?;
+
+    let assert = setup
+        .run(&["--error-format=json", "tags", "delete", "missing"], None)?
+        .code(7);
+
+    assert_eq!(
+        parse_stderr(&assert.get_output().stderr)?,
+        json!({
+            "command": "tags delete",
+            "kind": "tag_not_found",
+            "message": "tag 'missing' not found",
+            "exit_code": 7,
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_error_format_json_invalid_args() -> Result<()> {
+    let setup = Setup::new()?;
+
+    let assert = setup
+        .run(&["show", "--error-format", "json"], None)?
+        .code(2);
+    let stderr = parse_stderr(&assert.get_output().stderr)?;
+
+    assert_eq!(stderr["kind"], "invalid_args");
+    assert_eq!(stderr["exit_code"], 2);
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+mod setup;
+
+use anyhow::Result;
+use gnotes::common::archive::{read_archive, read_manifest, ArchiveFormat, ManifestNote};
+use gnotes::common::tags::update_tags;
+use predicates::prelude::*;
+use serde_json::json;
+use setup::{write_encrypted_note, write_note, Setup};
+use std::fs;
+use tempdir::TempDir;
+
+fn write_notes(setup: &Setup) -> Result<()> {
+    write_note(
+        &setup.default_note_parent_dir(),
+        "a",
+        "# Title\n\nsee [[work/b]] and [[missing]]",
+    )?;
+    write_note(&setup.note_parent_dir("work"), "b", "back to [[a]]")?;
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        "secret",
+        "hidden\n",
+        "secret",
+    )?;
+
+    update_tags(
+        setup.notes_dir_path(),
+        &json!({"project/alpha": ["notes/a", "work/b"], "other": ["work/b"]}),
+    )
+}
+
+#[test]
+fn test_export_html() -> Result<()> {
+    let setup = Setup::new()?;
+    let out_dir = TempDir::new("gnotes_export")?;
+    let out_dir_path = out_dir.path().to_str().unwrap_or_default();
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["export", "html", out_dir_path], None)?
+        .success()
+        .stdout(predicate::str::starts_with("exported 2 notes to "));
+
+    let note_page = fs::read_to_string(out_dir.path().join("notes/notes/a.html"))?;
+    assert!(note_page.contains("<h1>Title</h1>"));
+    assert!(note_page.contains("<a href=\"../../notes/work/b.html\">work/b</a>"));
+    assert!(note_page.contains("notes/missing"));
+    assert!(note_page.contains("<a href=\"../../tags/project/alpha.html\">project/alpha</a>"));
+    assert!(note_page.contains("<a href=\"../../index.html\">Index</a>"));
+
+    let tag_page = fs::read_to_string(
+        out_dir
+            .path()
+            .join("tags")
+            .join("project")
+            .join("alpha.html"),
+    )?;
+    assert!(tag_page.contains("<a href=\"../../notes/notes/a.html\">notes/a</a>"));
+    assert!(tag_page.contains("<a href=\"../../notes/work/b.html\">work/b</a>"));
+
+    let index_page = fs::read_to_string(out_dir.path().join("index.html"))?;
+    assert!(index_page.contains("<a href=\"notes/notes/a.html\">notes/a</a>"));
+    assert!(index_page.contains("<a href=\"tags/other.html\">other</a>"));
+    assert!(index_page.contains("search-index.js"));
+
+    let search_index = fs::read_to_string(out_dir.path().join("search-index.js"))?;
+    assert!(search_index.starts_with("window.GNOTES_SEARCH_INDEX = "));
+    assert!(search_index.contains("\"url\":\"notes/work/b.html\""));
+
+    assert!(!out_dir.path().join("notes/notes/secret.html").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_export_html_escapes_html_and_links() -> Result<()> {
+    let setup = Setup::new()?;
+    let out_dir = TempDir::new("gnotes_export")?;
+    let out_dir_path = out_dir.path().to_str().unwrap_or_default();
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        "a",
+        "<script>alert(1)</script>\n\nsee <img src=x onerror=alert(2)> and [[work/c (draft)]]\n\n\
+         [x](javascript:alert(3)) ![y](data:text/html,alert) [z]( JavaScript:alert(4)) [w](java&#9;script:alert(5)) [web](https://example.com)",
+    )?;
+    write_note(&setup.note_parent_dir("work"), "c (draft)", "hello")?;
+
+    setup
+        .run(&["export", "html", out_dir_path], None)?
+        .success();
+
+    let note_page = fs::read_to_string(out_dir.path().join("notes/notes/a.html"))?;
+    assert!(!note_page.contains("<script>alert"));
+    assert!(!note_page.contains("<img src=\"x\""));
+    assert!(note_page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(
+        note_page.contains("<a href=\"../../notes/work/c%20%28draft%29.html\">work/c (draft)</a>")
+    );
+    assert!(!note_page.contains("alert(3)"));
+    assert!(!note_page.contains("data:"));
+    assert!(note_page.contains("<img src=\"#\" alt=\"y\""));
+    assert!(!note_page.contains("alert(4)"));
+    assert!(note_page.contains("<a href=\"#\">x</a>"));
+    assert!(note_page.contains("<a href=\"#\">z</a>"));
+    assert!(note_page.contains("<a href=\"#\">w</a>"));
+    assert!(note_page.contains("<a href=\"https://example.com\">web</a>"));
+
+    Ok(())
+}
+
+#[test]
+fn test_export_html_subset() -> Result<()> {
+    let setup = Setup::new()?;
+    let out_dir = TempDir::new("gnotes_export")?;
+    let out_dir_path = out_dir.path().to_str().unwrap_or_default();
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["export", "html", out_dir_path, "--tag", "other"], None)?
+        .success()
+        .stdout(predicate::str::starts_with("exported 1 notes to "));
+
+    assert!(out_dir.path().join("notes/work/b.html").exists());
+    assert!(!out_dir.path().join("notes/notes/a.html").exists());
+
+    let note_page = fs::read_to_string(out_dir.path().join("notes/work/b.html"))?;
+    assert!(note_page.contains("back to notes/a"));
+    assert!(!note_page.contains("notes/notes/a.html"));
+
+    Ok(())
+}
+
+#[test]
+fn test_export_html_notes_dir_named_tags() -> Result<()> {
+    let setup = Setup::new()?;
+    let out_dir = TempDir::new("gnotes_export")?;
+    let out_dir_path = out_dir.path().to_str().unwrap_or_default();
+
+    write_note(&setup.note_parent_dir("tags"), "other", "a note")?;
+    write_note(&setup.default_note_parent_dir(), "index", "another note")?;
+    update_tags(setup.notes_dir_path(), &json!({"other": ["notes/index"]}))?;
+
+    setup
+        .run(&["export", "html", out_dir_path], None)?
+        .success()
+        .stdout(predicate::str::starts_with("exported 2 notes to "));
+
+    let note_page = fs::read_to_string(out_dir.path().join("notes/tags/other.html"))?;
+    assert!(note_page.contains("a note"));
+
+    let tag_page = fs::read_to_string(out_dir.path().join("tags/other.html"))?;
+    assert!(tag_page.contains("<a href=\"../notes/notes/index.html\">notes/index</a>"));
+
+    let index_page = fs::read_to_string(out_dir.path().join("index.html"))?;
+    assert!(index_page.contains("<h2>Notes</h2>"));
+
+    Ok(())
+}
+
+#[test]
+fn test_export_archive() -> Result<()> {
+    let setup = Setup::new()?;
+    let out_dir = TempDir::new("gnotes_export")?;
+
+    write_notes(&setup)?;
+
+    for file_name in ["notes.tar", "notes.zip"] {
+        let file_path = out_dir.path().join(file_name);
+
+        setup
+            .run(
+                &[
+                    "export",
+                    "archive",
+                    file_path.to_str().unwrap_or_default(),
+                    "--dir",
+                    "notes",
+                ],
+                None,
+            )?
+            .success()
+            .stdout(predicate::str::starts_with("exported 2 notes to "));
+
+        let files = read_archive(&file_path, ArchiveFormat::detect(&file_path))?;
+        let manifest = read_manifest(&files)?;
+
+        assert_eq!(
+            manifest.notes,
+            vec![
+                ManifestNote {
+                    identifier: String::from("notes/a"),
+                    tags: vec![String::from("project/alpha")],
+                },
+                ManifestNote {
+                    identifier: String::from("notes/secret"),
+                    tags: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            files.get("notes/notes/a"),
+            Some(&fs::read(setup.default_note_parent_dir().join("a"))?)
+        );
+    }
+
+    Ok(())
+}
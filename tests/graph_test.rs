@@ -0,0 +1,128 @@
+mod setup;
+
+use anyhow::Result;
+use gnotes::common::tags::update_tags;
+use predicates::prelude::*;
+use serde_json::{json, Value};
+use setup::{write_note, Setup};
+
+fn write_notes(setup: &Setup) -> Result<()> {
+    write_note(&setup.default_note_parent_dir(), "a", "see [[b]]")?;
+    write_note(
+        &setup.default_note_parent_dir(),
+        "b",
+        "see [[work/c]] and [[missing]]",
+    )?;
+    write_note(&setup.note_parent_dir("work"), "c", "see [[work/d]]")?;
+    write_note(&setup.note_parent_dir("work"), "d", "nothing")?;
+
+    update_tags(
+        setup.notes_dir_path(),
+        &json!({"project/alpha": ["notes/a", "work/c"], "other": ["work/d"]}),
+    )
+}
+
+#[test]
+fn test_graph_dot() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["graph", "--dir", "work"], None)?
+        .success()
+        .stdout(predicate::eq(
+            r#"digraph gnotes {
+  "note:work/c" [label="work/c", shape=box];
+  "note:work/d" [label="work/d", shape=box];
+  "tag:other" [label="other", shape=ellipse];
+  "tag:project/alpha" [label="project/alpha", shape=ellipse];
+  "note:work/c" -> "note:work/d";
+  "tag:other" -> "note:work/d" [style=dotted];
+  "tag:project/alpha" -> "note:work/c" [style=dotted];
+}
+"#,
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_graph_json() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    let output = setup
+        .run(&["graph", "--format", "json", "--dir", "notes"], None)?
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let expected = json!({
+        "nodes": [
+            {"id": "note:notes/a", "kind": "note", "label": "notes/a"},
+            {"id": "note:notes/b", "kind": "note", "label": "notes/b"},
+            {"id": "note:notes/missing", "kind": "note", "label": "notes/missing", "missing": true},
+            {"id": "tag:project/alpha", "kind": "tag", "label": "project/alpha"},
+        ],
+        "edges": [
+            {"from": "note:notes/a", "to": "note:notes/b", "kind": "link"},
+            {"from": "note:notes/b", "to": "note:notes/missing", "kind": "link"},
+            {"from": "tag:project/alpha", "to": "note:notes/a", "kind": "tag"},
+        ],
+    });
+
+    assert_eq!(serde_json::from_slice::<Value>(&output)?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_graph_tag_filter() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["graph", "--tag", "project"], None)?
+        .success()
+        .stdout(predicate::str::contains("\"note:notes/a\""))
+        .stdout(predicate::str::contains("\"note:work/c\""))
+        .stdout(predicate::str::contains("\"note:notes/b\"").not())
+        .stdout(predicate::str::contains("\"note:work/d\"").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_graph_root_depth() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["graph", "--root", "notes/b", "--depth", "1"], None)?
+        .success()
+        .stdout(predicate::str::contains("\"note:notes/a\" [label"))
+        .stdout(predicate::str::contains("\"note:notes/b\" [label"))
+        .stdout(predicate::str::contains("\"note:work/c\" [label"))
+        .stdout(predicate::str::contains("\"note:notes/missing\" [label"))
+        .stdout(predicate::str::contains("\"note:work/d\"").not())
+        .stdout(predicate::str::contains("\"tag:project/alpha\"").not());
+
+    Ok(())
+}
+
+#[test]
+fn test_graph_root_does_not_exist() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["graph", "--root", "notes/b"], None)?
+        .stderr(predicate::str::starts_with("graph failed: file"))
+        .code(4);
+
+    Ok(())
+}
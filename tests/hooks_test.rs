@@ -0,0 +1,205 @@
+#![cfg(unix)]
+
+mod setup;
+
+use anyhow::Result;
+use predicates::prelude::*;
+use setup::{write_note, RunOptions, Setup, DEFAULT_NOTE_FILE_NAME};
+use std::collections::HashMap;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use tempdir::TempDir;
+
+fn hook_options(hook: &str, command: &str) -> Option<RunOptions> {
+    Some(RunOptions {
+        hooks: HashMap::from([(String::from(hook), String::from(command))]),
+        ..Default::default()
+    })
+}
+
+fn write_script(script_path: &Path, content: &str, mode: u32) -> Result<()> {
+    fs::create_dir_all(script_path.parent().unwrap())?;
+    fs::write(script_path, content)?;
+    fs::set_permissions(script_path, fs::Permissions::from_mode(mode))?;
+
+    Ok(())
+}
+
+#[test]
+fn test_hook_script() -> Result<()> {
+    let setup = Setup::new()?;
+    let hooks_dir = TempDir::new("gnotes_hooks")?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_script(
+        &hooks_dir.path().join("post-tag"),
+        "#!/bin/sh\necho \"$GNOTES_HOOK $GNOTES_COMMAND $GNOTES_NOTE_IDENTIFIER $GNOTES_NOTE_PATH\" > hook.log\n",
+        0o755,
+    )?;
+
+    setup
+        .run(
+            &["tag", DEFAULT_NOTE_FILE_NAME, "home"],
+            Some(RunOptions {
+                hooks_dir: Some(hooks_dir.path().to_path_buf()),
+                ..Default::default()
+            }),
+        )?
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(setup.notes_dir_path().join("hook.log"))?,
+        format!(
+            "post-tag tag notes/chores {}\n",
+            setup.default_note_path().display()
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_hook_script_in_notes_dir_is_not_run() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_script(
+        &setup.notes_dir_path().join(".hooks").join("post-tag"),
+        "#!/bin/sh\ntouch hook.log\n",
+        0o755,
+    )?;
+
+    setup
+        .run(&["tag", DEFAULT_NOTE_FILE_NAME, "home"], None)?
+        .success();
+
+    assert!(!setup.notes_dir_path().join("hook.log").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_trusted_hook_script_in_notes_dir() -> Result<()> {
+    let setup = Setup::new()?;
+    let hooks_dir = TempDir::new("gnotes_hooks")?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_script(
+        &setup.notes_dir_path().join(".hooks").join("post-tag"),
+        "#!/bin/sh\necho notes >> hook.log\n",
+        0o755,
+    )?;
+    write_script(
+        &hooks_dir.path().join("post-tag"),
+        "#!/bin/sh\necho hooks_dir >> hook.log\n",
+        0o755,
+    )?;
+
+    setup
+        .run(
+            &["tag", DEFAULT_NOTE_FILE_NAME, "home"],
+            Some(RunOptions {
+                hooks_dir: Some(hooks_dir.path().to_path_buf()),
+                trust_notes_hooks: true,
+                ..Default::default()
+            }),
+        )?
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(setup.notes_dir_path().join("hook.log"))?,
+        "notes\nhooks_dir\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_hook_from_config() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(
+            &["add", DEFAULT_NOTE_FILE_NAME, "hello"],
+            hook_options("post-add", "echo \"$GNOTES_NOTE_IDENTIFIER\" >> hook.log"),
+        )?
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(setup.notes_dir_path().join("hook.log"))?,
+        "notes/chores\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_pre_hook_aborts_the_command() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["save"], hook_options("pre-save", "exit 3"))?
+        .stderr(predicate::eq(
+            "save failed: hook 'pre-save' failed with exit status: 3\n",
+        ))
+        .code(19);
+
+    Ok(())
+}
+
+#[test]
+fn test_failed_post_hook_keeps_the_command() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    setup
+        .run(
+            &["remove", DEFAULT_NOTE_FILE_NAME],
+            hook_options("post-remove", "exit 1"),
+        )?
+        .success();
+
+    assert!(!setup.default_note_path().exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_post_hook_which_cant_start_keeps_the_command() -> Result<()> {
+    let setup = Setup::new()?;
+    let hooks_dir = TempDir::new("gnotes_hooks")?;
+
+    write_script(&hooks_dir.path().join("post-add"), "#!/bin/sh\n", 0o644)?;
+
+    setup
+        .run(
+            &["add", DEFAULT_NOTE_FILE_NAME, "hello"],
+            Some(RunOptions {
+                hooks_dir: Some(hooks_dir.path().to_path_buf()),
+                ..Default::default()
+            }),
+        )?
+        .success();
+
+    assert!(setup.default_note_path().exists());
+
+    Ok(())
+}
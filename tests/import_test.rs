@@ -0,0 +1,382 @@
+mod setup;
+
+use anyhow::Result;
+use gnotes::common::archive::{write_archive, ArchiveFormat, Manifest, ManifestNote};
+use gnotes::common::tags::{load_tags, update_tags};
+use maplit::{hashmap, hashset};
+use predicates::prelude::*;
+use serde_json::json;
+use setup::{write_note, Setup};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tempdir::TempDir;
+
+fn write_test_archive(file_path: &Path, format: ArchiveFormat) -> Result<()> {
+    let manifest = Manifest::new(vec![
+        ManifestNote {
+            identifier: String::from("notes/chores"),
+            tags: vec![String::from("home")],
+        },
+        ManifestNote {
+            identifier: String::from("work/plan"),
+            tags: vec![String::from("work"), String::from("home")],
+        },
+    ]);
+    let files = BTreeMap::from([
+        (
+            String::from("manifest.json"),
+            serde_json::to_vec(&manifest)?,
+        ),
+        (String::from("notes/notes/chores"), b"imported\n".to_vec()),
+        (String::from("notes/work/plan"), b"plan\n".to_vec()),
+    ]);
+
+    write_archive(file_path, format, &files)
+}
+
+#[test]
+fn test_import_archive() -> Result<()> {
+    let setup = Setup::new()?;
+    let archive_dir = TempDir::new("gnotes_import")?;
+
+    update_tags(setup.notes_dir_path(), &json!({"home": ["other/note"]}))?;
+
+    for (file_name, format) in [
+        ("notes.tar", ArchiveFormat::Tar),
+        ("notes.zip", ArchiveFormat::Zip),
+    ] {
+        let file_path = archive_dir.path().join(file_name);
+        write_test_archive(&file_path, format)?;
+
+        setup
+            .run(
+                &[
+                    "import",
+                    "archive",
+                    file_path.to_str().unwrap_or_default(),
+                    "--conflict",
+                    "overwrite",
+                ],
+                None,
+            )?
+            .success()
+            .stdout(predicate::str::ends_with("total 2\n"));
+    }
+
+    let expected = hashmap! {
+      String::from("home") => hashset! {
+        String::from("other/note"),
+        String::from("notes/chores"),
+        String::from("work/plan"),
+      },
+      String::from("work") => hashset! { String::from("work/plan") },
+    };
+
+    assert_eq!(fs::read_to_string(setup.default_note_path())?, "imported\n");
+    assert_eq!(
+        fs::read_to_string(setup.note_parent_dir("work").join("plan"))?,
+        "plan\n"
+    );
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_archive_skip_existing() -> Result<()> {
+    let setup = Setup::new()?;
+    let archive_dir = TempDir::new("gnotes_import")?;
+    let file_path = archive_dir.path().join("notes.tar");
+
+    write_test_archive(&file_path, ArchiveFormat::Tar)?;
+    write_note(&setup.default_note_parent_dir(), "chores", "existing")?;
+
+    setup
+        .run(
+            &["import", "archive", file_path.to_str().unwrap_or_default()],
+            None,
+        )?
+        .success()
+        .stdout(predicate::eq(
+            "skipped notes/chores\nimported work/plan\ntotal 1\n",
+        ));
+
+    assert_eq!(fs::read_to_string(setup.default_note_path())?, "existing\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_import_archive_rename_existing() -> Result<()> {
+    let setup = Setup::new()?;
+    let archive_dir = TempDir::new("gnotes_import")?;
+    let file_path = archive_dir.path().join("notes.tar");
+
+    write_test_archive(&file_path, ArchiveFormat::Tar)?;
+    write_note(&setup.default_note_parent_dir(), "chores", "existing")?;
+
+    setup
+        .run(
+            &[
+                "import",
+                "archive",
+                file_path.to_str().unwrap_or_default(),
+                "--conflict",
+                "rename",
+            ],
+            None,
+        )?
+        .success()
+        .stdout(predicate::eq(
+            "renamed notes/chores to notes/chores-1\nimported work/plan\ntotal 2\n",
+        ));
+
+    assert_eq!(fs::read_to_string(setup.default_note_path())?, "existing\n");
+    assert_eq!(
+        fs::read_to_string(setup.default_note_parent_dir().join("chores-1"))?,
+        "imported\n"
+    );
+    assert!(load_tags(setup.notes_dir_path())?["home"].contains("notes/chores-1"));
+
+    Ok(())
+}
+
+#[test]
+fn test_import_archive_rejects_unsafe_identifiers() -> Result<()> {
+    let setup = Setup::new()?;
+    let archive_dir = TempDir::new("gnotes_import")?;
+    let file_path = archive_dir.path().join("notes.zip");
+
+    let manifest = Manifest::new(vec![ManifestNote {
+        identifier: String::from("notes/../../escape"),
+        tags: vec![],
+    }]);
+    let files = BTreeMap::from([
+        (
+            String::from("manifest.json"),
+            serde_json::to_vec(&manifest)?,
+        ),
+        (String::from("notes/notes/../../escape"), b"bad\n".to_vec()),
+    ]);
+    write_archive(&file_path, ArchiveFormat::Zip, &files)?;
+
+    setup
+        .run(
+            &["import", "archive", file_path.to_str().unwrap_or_default()],
+            None,
+        )?
+        .stderr(predicate::str::contains(
+            "invalid archive: bad note identifier",
+        ))
+        .code(21);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_archive_rejects_hidden_files_before_writing() -> Result<()> {
+    let setup = Setup::new()?;
+    let archive_dir = TempDir::new("gnotes_import")?;
+    let file_path = archive_dir.path().join("notes.zip");
+
+    let manifest = Manifest::new(vec![
+        ManifestNote {
+            identifier: String::from("notes/chores"),
+            tags: vec![String::from("home")],
+        },
+        ManifestNote {
+            identifier: String::from(".hooks/pre-save"),
+            tags: vec![],
+        },
+    ]);
+    let files = BTreeMap::from([
+        (
+            String::from("manifest.json"),
+            serde_json::to_vec(&manifest)?,
+        ),
+        (String::from("notes/notes/chores"), b"imported\n".to_vec()),
+        (
+            String::from("notes/.hooks/pre-save"),
+            b"#!/bin/sh\necho planted\n".to_vec(),
+        ),
+    ]);
+    write_archive(&file_path, ArchiveFormat::Zip, &files)?;
+
+    setup
+        .run(
+            &["import", "archive", file_path.to_str().unwrap_or_default()],
+            None,
+        )?
+        .failure()
+        .stderr(predicate::str::contains(
+            "bad note identifier '.hooks/pre-save'",
+        ));
+
+    assert!(!setup.default_note_path().exists());
+    assert!(!setup.notes_dir_path().join(".hooks").exists());
+
+    Ok(())
+}
+
+fn write_vault(vault: &Path) -> Result<()> {
+    fs::create_dir_all(vault.join("projects").join("alpha"))?;
+    fs::create_dir_all(vault.join(".obsidian"))?;
+
+    fs::write(
+        vault.join("Daily.md"),
+        "---\ntags: [journal, work/standup]\n---\nsee [[Plan|the plan]] and [[Unknown]] #oncall\n",
+    )?;
+    fs::write(
+        vault.join("projects").join("alpha").join("Plan.md"),
+        "---\ntags: project\n---\n# Plan #notatag\nback to [[Daily]]\n```\n#code\n```\n",
+    )?;
+    fs::write(vault.join("projects").join("todo.txt"), "plain text\n")?;
+    fs::write(vault.join("image.png"), "not a note")?;
+    fs::write(vault.join(".obsidian").join("config.md"), "hidden")?;
+
+    Ok(())
+}
+
+#[test]
+fn test_import_dir() -> Result<()> {
+    let setup = Setup::new()?;
+    let vault = TempDir::new("gnotes_vault")?;
+
+    write_vault(vault.path())?;
+
+    let plan_mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+    filetime::set_file_mtime(
+        vault.path().join("projects").join("alpha").join("Plan.md"),
+        plan_mtime,
+    )?;
+
+    setup
+        .run(
+            &["import", "dir", vault.path().to_str().unwrap_or_default()],
+            None,
+        )?
+        .success()
+        .stdout(predicate::eq(
+            "imported notes/Daily [journal, oncall, work/standup]\n\
+             imported notes/projects/alpha/Plan [project]\n\
+             imported notes/projects/todo []\n\
+             total 3\n",
+        ));
+
+    assert_eq!(
+        fs::read_to_string(setup.default_note_parent_dir().join("Daily"))?,
+        "---\ntags: [journal, work/standup]\n---\nsee [[notes/projects/alpha/Plan]] and [[Unknown]] #oncall\n"
+    );
+    assert_eq!(
+        fs::read_to_string(
+            setup
+                .default_note_parent_dir()
+                .join("projects")
+                .join("alpha")
+                .join("Plan")
+        )?,
+        "---\ntags: project\n---\n# Plan #notatag\nback to [[Daily]]\n```\n#code\n```\n"
+    );
+
+    let metadata = fs::metadata(
+        setup
+            .default_note_parent_dir()
+            .join("projects")
+            .join("alpha")
+            .join("Plan"),
+    )?;
+    assert_eq!(
+        filetime::FileTime::from_last_modification_time(&metadata),
+        plan_mtime
+    );
+
+    let expected = hashmap! {
+      String::from("journal") => hashset! { String::from("notes/Daily") },
+      String::from("work/standup") => hashset! { String::from("notes/Daily") },
+      String::from("oncall") => hashset! { String::from("notes/Daily") },
+      String::from("project") => hashset! { String::from("notes/projects/alpha/Plan") },
+    };
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_import_dir_into_dry_run() -> Result<()> {
+    let setup = Setup::new()?;
+    let vault = TempDir::new("gnotes_vault")?;
+
+    write_vault(vault.path())?;
+
+    setup
+        .run(
+            &[
+                "import",
+                "dir",
+                vault.path().to_str().unwrap_or_default(),
+                "--into",
+                "vault",
+                "--dry-run",
+            ],
+            None,
+        )?
+        .success()
+        .stdout(predicate::eq(
+            "would import vault/Daily [journal, oncall, work/standup]\n\
+             would import vault/projects/alpha/Plan [project]\n\
+             would import vault/projects/todo []\n\
+             total 3\n",
+        ));
+
+    assert!(!setup.note_parent_dir("vault").exists());
+    assert!(!setup.notes_dir_path().join(".tags").exists());
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_import_dir_collisions_and_symlink_loop() -> Result<()> {
+    let setup = Setup::new()?;
+    let vault = TempDir::new("gnotes_vault")?;
+
+    fs::write(vault.path().join("plan.md"), "from md\n")?;
+    fs::write(vault.path().join("plan.txt"), "from txt\n")?;
+    fs::create_dir(vault.path().join("nested"))?;
+    std::os::unix::fs::symlink(vault.path(), vault.path().join("nested").join("loop"))?;
+
+    setup
+        .run(
+            &["import", "dir", vault.path().to_str().unwrap_or_default()],
+            None,
+        )?
+        .success()
+        .stdout("imported notes/plan []\ntotal 1\n")
+        .stderr(format!(
+            "skipped {}: same note notes/plan as {}\n",
+            vault.path().join("plan.txt").display(),
+            vault.path().join("plan.md").display()
+        ));
+
+    assert_eq!(
+        fs::read_to_string(setup.default_note_parent_dir().join("plan"))?,
+        "from md\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_import_dir_does_not_exist() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["import", "dir", "/does/not/exist"], None)?
+        .stderr(predicate::eq(
+            "import dir failed: directory '/does/not/exist' not found\n",
+        ))
+        .code(2);
+
+    Ok(())
+}
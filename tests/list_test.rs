@@ -1,8 +1,9 @@
 mod setup;
 
-use crate::setup::{DEFAULT_NOTES_DIR_NAME, DEFAULT_NOTE_FILE_NAME};
+use crate::setup::{
+    write_encrypted_note, write_note, DEFAULT_NOTES_DIR_NAME, DEFAULT_NOTE_FILE_NAME,
+};
 use anyhow::{Context, Result};
-use gnotes::common::notes::write_note;
 use predicates::prelude::*;
 use setup::Setup;
 use std::fs;
@@ -78,7 +79,7 @@ fn test_list_notes_does_not_exist() -> Result<()> {
 fn test_list_notes_ignore_non_directories() -> Result<()> {
     let setup = Setup::new()?;
 
-    fs::write(&setup.default_note_parent_dir(), "hello\n")?;
+    fs::write(setup.default_note_parent_dir(), "hello\n")?;
 
     setup
         .run(&["list"], None)?
@@ -115,6 +116,28 @@ fn test_list_notes_custom_dir() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_list_notes_marks_encrypted_notes() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(&setup.default_note_parent_dir(), "plain", "hello")?;
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello\n",
+        "secret",
+    )?;
+
+    setup
+        .run(&["list"], None)?
+        .success()
+        .stdout(predicate::str::contains("total 2\n"))
+        .stdout(predicate::str::contains("notes/chores (encrypted)\n"))
+        .stdout(predicate::str::contains("notes/plain\n"));
+
+    Ok(())
+}
+
 #[test]
 fn test_list_notes_include_headers() -> Result<()> {
     let setup = Setup::new()?;
@@ -188,7 +211,7 @@ fn test_list_notes_all() -> Result<()> {
 fn test_list_notes_all_ignore_non_directories() -> Result<()> {
     let setup = Setup::new()?;
 
-    fs::write(&setup.default_note_parent_dir(), "hello\n")?;
+    fs::write(setup.default_note_parent_dir(), "hello\n")?;
 
     setup
         .run(&["list", "--all"], None)?
@@ -204,8 +227,10 @@ fn test_list_notes_custom_dir_with_all() -> Result<()> {
 
     setup
         .run(&["list", "--dir", "custom", "--all"], None)?
-        .code(1)
-        .stderr(predicate::eq("--dir can't be used with --all\n"));
+        .code(2)
+        .stderr(predicate::eq(
+            "list failed: --dir can't be used with --all\n",
+        ));
 
     Ok(())
 }
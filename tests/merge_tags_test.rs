@@ -0,0 +1,102 @@
+mod setup;
+
+use anyhow::Result;
+use predicates::prelude::*;
+use setup::Setup;
+use std::fs;
+
+#[test]
+fn test_merge_tags() -> Result<()> {
+    let setup = Setup::new()?;
+    let base = setup.notes_dir_path().join("base");
+    let ours = setup.notes_dir_path().join("ours");
+    let theirs = setup.notes_dir_path().join("theirs");
+
+    fs::write(&base, r#"{"home":["notes/a"],"work":["notes/b"]}"#)?;
+    fs::write(
+        &ours,
+        r#"{"home":["notes/a","notes/c"],"work":["notes/b"]}"#,
+    )?;
+    fs::write(&theirs, r#"{"home":["notes/a"],"todo":["notes/d"]}"#)?;
+
+    setup
+        .run(
+            &[
+                "merge-tags",
+                &base.to_string_lossy(),
+                &ours.to_string_lossy(),
+                &theirs.to_string_lossy(),
+            ],
+            None,
+        )?
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(&ours)?,
+        "{\n  \"home\": [\n    \"notes/a\",\n    \"notes/c\"\n  ],\n  \"todo\": [\n    \"notes/d\"\n  ]\n}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_tags_without_base() -> Result<()> {
+    let setup = Setup::new()?;
+    let base = setup.notes_dir_path().join("base");
+    let ours = setup.notes_dir_path().join("ours");
+    let theirs = setup.notes_dir_path().join("theirs");
+
+    fs::write(&base, "")?;
+    fs::write(&ours, r#"{"home":["notes/a"]}"#)?;
+    fs::write(&theirs, r#"{"home":["notes/b"]}"#)?;
+
+    setup
+        .run(
+            &[
+                "merge-tags",
+                &base.to_string_lossy(),
+                &ours.to_string_lossy(),
+                &theirs.to_string_lossy(),
+            ],
+            None,
+        )?
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(&ours)?,
+        "{\n  \"home\": [\n    \"notes/a\",\n    \"notes/b\"\n  ]\n}\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_tags_corrupt_file() -> Result<()> {
+    let setup = Setup::new()?;
+    let base = setup.notes_dir_path().join("base");
+    let ours = setup.notes_dir_path().join("ours");
+    let theirs = setup.notes_dir_path().join("theirs");
+
+    fs::write(&base, "{}")?;
+    fs::write(&ours, "<<<<<<< HEAD")?;
+    fs::write(&theirs, "{}")?;
+
+    setup
+        .run(
+            &[
+                "merge-tags",
+                &base.to_string_lossy(),
+                &ours.to_string_lossy(),
+                &theirs.to_string_lossy(),
+            ],
+            None,
+        )?
+        .stderr(predicate::str::starts_with(
+            "merge-tags failed: the tags file",
+        ))
+        .code(15);
+
+    assert_eq!(fs::read_to_string(&ours)?, "<<<<<<< HEAD");
+
+    Ok(())
+}
@@ -0,0 +1,193 @@
+mod setup;
+
+use anyhow::{Context, Result};
+use gnotes::common::lock::NotebookLock;
+use gnotes::common::tags::{load_tags, update_tags};
+use maplit::{hashmap, hashset};
+use setup::{write_encrypted_note, write_note, Setup, DEFAULT_NOTE_FILE_NAME};
+use std::fs;
+use std::process::Child;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn test_mv_note() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    setup
+        .run(&["mv", DEFAULT_NOTE_FILE_NAME, "todo"], None)?
+        .success();
+
+    assert!(!setup.default_note_path().exists());
+    assert_eq!(
+        fs::read_to_string(setup.default_note_parent_dir().join("todo"))?,
+        "hello\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_mv_note_to_dir_updates_tags() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    let tags = hashmap! {
+      String::from("tag1") => hashset! { String::from("notes/chores"), String::from("notes/other") },
+    };
+    update_tags(setup.notes_dir_path(), &tags)?;
+
+    let expected = hashmap! {
+      String::from("tag1") => hashset! { String::from("home/chores"), String::from("notes/other") },
+    };
+
+    setup
+        .run(
+            &[
+                "move",
+                DEFAULT_NOTE_FILE_NAME,
+                DEFAULT_NOTE_FILE_NAME,
+                "--to-dir",
+                "home",
+            ],
+            None,
+        )?
+        .success();
+
+    assert!(setup.note_path("home").exists());
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_mv_note_updates_links() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_note(
+        &setup.note_parent_dir("custom"),
+        "standup",
+        "see [[chores]], [[notes/chores]] and [[custom/chores]]",
+    )?;
+
+    setup
+        .run(&["mv", DEFAULT_NOTE_FILE_NAME, "todo"], None)?
+        .success();
+
+    assert_eq!(
+        fs::read_to_string(setup.note_parent_dir("custom").join("standup"))?,
+        "see [[todo]], [[todo]] and [[custom/chores]]\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_mv_note_reports_encrypted_notes() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_encrypted_note(
+        &setup.note_parent_dir("secret"),
+        "keys",
+        "see [[chores]]",
+        "passphrase",
+    )?;
+
+    setup
+        .run(&["mv", DEFAULT_NOTE_FILE_NAME, "todo"], None)?
+        .success()
+        .stderr("links in secret/keys weren't updated, as it's encrypted\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_mv_note_target_exists() -> Result<()> {
+    let setup = Setup::new()?;
+    let target_file_path = setup.default_note_parent_dir().join("todo");
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_note(&setup.default_note_parent_dir(), "todo", "goodbye")?;
+
+    setup
+        .run(&["mv", DEFAULT_NOTE_FILE_NAME, "todo"], None)?
+        .stderr(format!(
+            "mv failed: file '{}' already exists\n",
+            target_file_path
+                .to_str()
+                .context("target_file_path.to_str()")?
+        ))
+        .code(5);
+
+    assert!(setup.default_note_path().exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_mv_note_does_not_exist() -> Result<()> {
+    let setup = Setup::new()?;
+    let note_file_path = setup.default_note_path();
+
+    setup
+        .run(&["mv", DEFAULT_NOTE_FILE_NAME, "todo"], None)?
+        .stderr(format!(
+            "mv failed: file '{}' not found\n",
+            note_file_path.to_str().context("note_file_path.to_str()")?
+        ))
+        .code(4);
+
+    Ok(())
+}
+
+#[test]
+fn test_mv_note_waits_for_lock() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    let lock = NotebookLock::new(setup.notes_dir_path());
+    let child = lock.run(|| -> Result<Child> {
+        let child = setup.spawn(&["mv", DEFAULT_NOTE_FILE_NAME, "todo"], None)?;
+
+        thread::sleep(Duration::from_millis(500));
+        assert!(setup.default_note_path().exists());
+
+        Ok(child)
+    })?;
+
+    assert!(child.wait_with_output()?.status.success());
+    assert!(!setup.default_note_path().exists());
+    assert!(setup.default_note_parent_dir().join("todo").exists());
+
+    Ok(())
+}
@@ -2,7 +2,10 @@ mod setup;
 
 use crate::setup::RunOptions;
 use anyhow::Result;
-use setup::{Setup, DEFAULT_NOTE_FILE_NAME};
+use gnotes::common::crypto::{decrypt, is_encrypted};
+use gnotes::config::DirConfig;
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
+use std::collections::HashMap;
 use std::fs;
 
 #[test]
@@ -69,6 +72,7 @@ fn test_new_note_interactive() -> Result<()> {
     let run_options = RunOptions {
         stdin: Some(stdin),
         repository: None,
+        ..Default::default()
     };
 
     setup
@@ -83,3 +87,137 @@ fn test_new_note_interactive() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_new_encrypted_note() -> Result<()> {
+    let setup = Setup::new()?;
+    let expected_note_file_path = setup.default_note_path();
+
+    setup
+        .run(
+            &[
+                "new",
+                DEFAULT_NOTE_FILE_NAME,
+                "-m",
+                "do this and that",
+                "--encrypt",
+            ],
+            Some(RunOptions::with_passphrase("secret")),
+        )?
+        .success();
+
+    let content = fs::read_to_string(&expected_note_file_path)?;
+
+    assert!(is_encrypted(&content));
+    assert!(!content.contains("do this and that"));
+    assert_eq!(
+        decrypt(&content, "secret")?,
+        String::from("do this and that\n")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_new_note_in_encrypted_dir() -> Result<()> {
+    let setup = Setup::new()?;
+    let expected_note_file_path = setup.note_path("secrets");
+
+    let run_options = RunOptions {
+        dirs: HashMap::from([(String::from("secrets"), DirConfig { encrypted: true })]),
+        ..RunOptions::with_passphrase("secret")
+    };
+
+    setup
+        .run(
+            &[
+                "new",
+                DEFAULT_NOTE_FILE_NAME,
+                "-m",
+                "do this and that",
+                "--dir",
+                "secrets",
+            ],
+            Some(run_options),
+        )?
+        .success();
+
+    let content = fs::read_to_string(&expected_note_file_path)?;
+
+    assert!(is_encrypted(&content));
+    assert_eq!(
+        decrypt(&content, "secret")?,
+        String::from("do this and that\n")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_new_note_appends_to_existing_note() -> Result<()> {
+    let setup = Setup::new()?;
+    let note_file_path = setup.default_note_path();
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    setup
+        .run(&["new", DEFAULT_NOTE_FILE_NAME, "-m", "bye"], None)?
+        .success();
+
+    assert_eq!(fs::read_to_string(note_file_path)?, "hello\nbye\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_new_encrypted_note_appends_to_existing_note() -> Result<()> {
+    let setup = Setup::new()?;
+    let note_file_path = setup.default_note_path();
+
+    for message in ["hello", "bye"] {
+        setup
+            .run(
+                &["new", DEFAULT_NOTE_FILE_NAME, "-m", message, "--encrypt"],
+                Some(RunOptions::with_passphrase("secret")),
+            )?
+            .success();
+    }
+
+    let content = fs::read_to_string(&note_file_path)?;
+
+    assert!(is_encrypted(&content));
+    assert_eq!(decrypt(&content, "secret")?, "hello\nbye\n");
+
+    Ok(())
+}
+
+#[test]
+fn test_new_encrypted_note_with_existing_plain_note() -> Result<()> {
+    let setup = Setup::new()?;
+    let note_file_path = setup.default_note_path();
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    setup
+        .run(
+            &["new", DEFAULT_NOTE_FILE_NAME, "-m", "bye", "--encrypt"],
+            Some(RunOptions::with_passphrase("secret")),
+        )?
+        .stderr(format!(
+            "new failed: file '{}' already exists\n",
+            note_file_path.to_string_lossy()
+        ))
+        .code(5);
+
+    assert_eq!(fs::read_to_string(note_file_path)?, "hello\n");
+
+    Ok(())
+}
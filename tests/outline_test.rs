@@ -0,0 +1,35 @@
+mod setup;
+
+use anyhow::Result;
+use predicates::prelude::*;
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
+
+#[test]
+fn test_outline() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "## Notes\nsome notes\n```\n# not a heading\n```\n### Details\n## Action items\n- call mom",
+    )?;
+
+    setup
+        .run(&["outline", DEFAULT_NOTE_FILE_NAME], None)?
+        .success()
+        .stdout(predicate::eq("Notes\n  Details\nAction items\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_outline_note_does_not_exist() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["outline", DEFAULT_NOTE_FILE_NAME], None)?
+        .stderr(predicate::str::starts_with("outline failed: file"))
+        .code(4);
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+#![cfg(unix)]
+
+mod setup;
+
+use anyhow::Result;
+use predicates::prelude::*;
+use setup::{RunOptions, Setup};
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use tempdir::TempDir;
+
+/// A directory with the plugin, and the options to run gnotes with it on `PATH`.
+fn setup_plugin(name: &str, script: &str) -> Result<(TempDir, Option<RunOptions>)> {
+    let plugins_dir = TempDir::new("gnotes_plugins")?;
+    let plugin_path = plugins_dir.path().join(format!("gnotes-{}", name));
+
+    fs::write(&plugin_path, script)?;
+    fs::set_permissions(&plugin_path, fs::Permissions::from_mode(0o755))?;
+
+    let path = env::join_paths(
+        [plugins_dir.path().to_path_buf()]
+            .into_iter()
+            .chain(env::split_paths(&env::var_os("PATH").unwrap_or_default())),
+    )?;
+    let run_options = RunOptions {
+        envs: HashMap::from([(String::from("PATH"), path.to_string_lossy().into_owned())]),
+        ..Default::default()
+    };
+
+    Ok((plugins_dir, Some(run_options)))
+}
+
+#[test]
+fn test_plugin() -> Result<()> {
+    let setup = Setup::new()?;
+    let (_plugins_dir, run_options) = setup_plugin(
+        "hello",
+        "#!/bin/sh\necho \"$@\" \"$GNOTES_NOTES_DIR\" \"$GNOTES_STORE\"\n",
+    )?;
+
+    setup
+        .run(&["hello", "a", "--b"], run_options)?
+        .stdout(format!("a --b {} json\n", setup.notes_dir_path().display()))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_plugin_gets_hooks_config() -> Result<()> {
+    let setup = Setup::new()?;
+    let hooks_dir = TempDir::new("gnotes_hooks")?;
+    let (_plugins_dir, run_options) = setup_plugin(
+        "hooks",
+        "#!/bin/sh\necho \"$GNOTES_HOOKS_DIR\" \"$GNOTES_TRUST_NOTES_HOOKS\"\n",
+    )?;
+    let run_options = run_options.map(|run_options| RunOptions {
+        hooks_dir: Some(hooks_dir.path().to_path_buf()),
+        trust_notes_hooks: true,
+        ..run_options
+    });
+
+    setup
+        .run(&["hooks"], run_options)?
+        .stdout(format!("{} true\n", hooks_dir.path().display()))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_plugin_exit_code() -> Result<()> {
+    let setup = Setup::new()?;
+    let (_plugins_dir, run_options) = setup_plugin("fail", "#!/bin/sh\nexit 4\n")?;
+
+    setup
+        .run(&["fail"], run_options.clone())?
+        .stderr("fail failed: plugin 'fail' failed with exit code 4\n")
+        .code(20);
+    setup
+        .run(&["--error-format", "json", "fail"], run_options)?
+        .stderr(predicate::str::contains("\"kind\":\"plugin_failed\""))
+        .code(20);
+
+    Ok(())
+}
+
+#[test]
+fn test_plugin_not_found() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["missing"], None)?
+        .stderr(predicate::eq(
+            "missing failed: 'missing' isn't a gnotes command, and no 'gnotes-missing' plugin was found on PATH\n",
+        ))
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn test_mistyped_command_suggests_the_command() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["serach", "home"], None)?
+        .stderr(predicate::str::contains("Did you mean 'search'?"))
+        .code(2);
+    setup
+        .run(&["--error-format", "json", "serach", "home"], None)?
+        .stderr(predicate::str::contains(
+            "The subcommand 'serach' wasn't recognized. Did you mean 'search'?",
+        ))
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn test_help_lists_plugins() -> Result<()> {
+    let setup = Setup::new()?;
+    let (_plugins_dir, run_options) = setup_plugin("hello", "#!/bin/sh\n")?;
+
+    setup
+        .run(&["help"], run_options)?
+        .stdout(predicate::str::ends_with("PLUGINS:\n    hello\n"))
+        .success();
+
+    Ok(())
+}
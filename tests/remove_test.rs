@@ -1,11 +1,10 @@
 mod setup;
 
 use anyhow::Result;
-use gnotes::common::notes::write_note;
 use gnotes::common::tags::{load_tags, update_tags};
 use maplit::{hashmap, hashset};
 use serde_json::json;
-use setup::{Setup, DEFAULT_NOTE_FILE_NAME};
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
 
 #[test]
 fn test_remove_note() -> Result<()> {
@@ -102,3 +101,21 @@ fn test_remove_note_also_removes_tag() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_remove_note_partial_name() -> Result<()> {
+    let setup = Setup::new()?;
+    let note_file_path = setup.note_parent_dir("custom").join(DEFAULT_NOTE_FILE_NAME);
+
+    write_note(
+        &setup.note_parent_dir("custom"),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    setup.run(&["remove", "chor"], None)?.success();
+
+    assert!(!note_file_path.exists());
+
+    Ok(())
+}
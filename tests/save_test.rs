@@ -12,9 +12,9 @@ fn test_save_fails_without_repository() -> Result<()> {
     setup
         .run(&["save"], None)?
         .stderr(predicate::eq(
-            "Can't save without a repository. Please specify a repository in the config file.\n",
+            "save failed: no repository is configured. Please specify a repository in the config file.\n",
         ))
-        .code(1);
+        .code(11);
 
     Ok(())
 }
@@ -34,6 +34,7 @@ fn test_save_succeeds_on_empty_repository() -> Result<()> {
     let run_options = RunOptions {
         stdin: None,
         repository: Some(git_setup.bare_dir.path().to_path_buf()),
+        ..Default::default()
     };
 
     setup.run(&["save"], Some(run_options))?.code(0);
@@ -60,6 +61,7 @@ fn test_save_succeeds_on_non_empty_repository() -> Result<()> {
     let run_options = RunOptions {
         stdin: None,
         repository: Some(git_setup.bare_dir.path().to_path_buf()),
+        ..Default::default()
     };
 
     setup.run(&["save"], Some(run_options))?.code(0);
@@ -73,3 +75,34 @@ fn test_save_succeeds_on_non_empty_repository() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_save_registers_tags_merge_driver() -> Result<()> {
+    let setup = Setup::new()?;
+    let git_setup = GitSetup::new(None)?;
+
+    GitSetup::clone_to(git_setup.bare_dir.path(), setup.notes_dir_path())?;
+
+    let run_options = RunOptions {
+        stdin: None,
+        repository: Some(git_setup.bare_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+
+    setup.run(&["save"], Some(run_options))?.code(0);
+
+    let config = git2::Repository::open(setup.notes_dir_path())?.config()?;
+    assert_eq!(
+        config.get_string("merge.gnotes-tags.driver")?,
+        "gnotes merge-tags %O %A %B"
+    );
+
+    git_setup.update_clone()?;
+
+    assert_eq!(
+        fs::read_to_string(git_setup.clone_dir.path().join(".gitattributes"))?,
+        ".tags merge=gnotes-tags\n.inline-tags merge=gnotes-tags\n"
+    );
+
+    Ok(())
+}
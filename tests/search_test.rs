@@ -1,11 +1,10 @@
 mod setup;
 
 use anyhow::Result;
-use gnotes::common::notes::write_note;
 use gnotes::common::tags::update_tags;
 use predicates::prelude::*;
 use serde_json::json;
-use setup::{Setup, DEFAULT_NOTE_FILE_NAME};
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
 
 #[test]
 fn test_search_note() -> Result<()> {
@@ -100,8 +99,48 @@ fn test_search_note_custom_dir_with_all() -> Result<()> {
 
     setup
         .run(&["search", "tag", "--dir", "custom", "--all"], None)?
-        .stderr(predicate::eq("--dir can't be used with --all\n"))
-        .code(1);
+        .stderr(predicate::eq(
+            "search failed: --dir can't be used with --all\n",
+        ))
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn test_search_note_includes_nested_tags() -> Result<()> {
+    let setup = Setup::new()?;
+    let tags = json!({
+        "project/alpha": ["notes/chores"],
+        "project/alpha/backend": ["notes/reminders", "notes/chores"],
+        "project/beta": ["notes/other"],
+        "project/alphabet": ["notes/letters"],
+    });
+
+    update_tags(setup.notes_dir_path(), &tags)?;
+
+    setup
+        .run(&["search", "project/alpha"], None)?
+        .stdout(predicate::eq("total 2\nnotes/chores\nnotes/reminders\n"))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_search_note_exact() -> Result<()> {
+    let setup = Setup::new()?;
+    let tags = json!({
+        "project/alpha": ["notes/chores"],
+        "project/alpha/backend": ["notes/reminders"],
+    });
+
+    update_tags(setup.notes_dir_path(), &tags)?;
+
+    setup
+        .run(&["search", "project/alpha", "--exact"], None)?
+        .stdout(predicate::eq("total 1\nnotes/chores\n"))
+        .success();
 
     Ok(())
 }
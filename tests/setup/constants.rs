@@ -1,2 +1,2 @@
-pub const DEFAULT_NOTES_DIR_NAME: &'static str = "notes";
-pub const DEFAULT_NOTE_FILE_NAME: &'static str = "chores";
+pub const DEFAULT_NOTES_DIR_NAME: &str = "notes";
+pub const DEFAULT_NOTE_FILE_NAME: &str = "chores";
@@ -2,7 +2,7 @@
 #![allow(dead_code)]
 
 use super::constants::*;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -44,7 +44,7 @@ impl GitSetup {
     pub fn clone_to(from: &Path, to: &Path) -> Result<()> {
         let from_str = from.to_str().context("from.to_str()")?;
 
-        GitSetup::run_git_command(to, &["clone", &from_str, "."])?;
+        GitSetup::run_git_command(to, &["clone", from_str, "."])?;
 
         Ok(())
     }
@@ -84,14 +84,22 @@ impl GitSetup {
         Ok(clone_dir)
     }
 
+    /// Runs git with its own identity, so the commits don't depend on the git config of the machine.
     fn run_git_command(repo_path: &Path, args: &[&str]) -> Result<()> {
-        Command::new("git")
+        let status = Command::new("git")
             .args(args)
-            .current_dir(&repo_path)
+            .current_dir(repo_path)
+            .env("GIT_AUTHOR_NAME", "gnotes tests")
+            .env("GIT_AUTHOR_EMAIL", "tests@gnotes.com")
+            .env("GIT_COMMITTER_NAME", "gnotes tests")
+            .env("GIT_COMMITTER_EMAIL", "tests@gnotes.com")
             .stderr(Stdio::null())
             .stdout(Stdio::null())
-            .spawn()?
-            .wait()?;
+            .status()?;
+
+        if !status.success() {
+            bail!("git {} failed with {}", args.join(" "), status);
+        }
 
         Ok(())
     }
@@ -1,9 +1,18 @@
 mod constants;
 mod git_setup;
+mod notes;
 mod run_options;
+#[allow(clippy::module_inception)]
 mod setup;
 
+// Each test crate only uses some of these, see https://github.com/rust-lang/rust/issues/46379
+#[allow(unused_imports)]
 pub use constants::*;
+#[allow(unused_imports)]
 pub use git_setup::{GitSetup, GitSetupOptions};
+#[allow(unused_imports)]
+pub use notes::{write_encrypted_note, write_note};
+#[allow(unused_imports)]
 pub use run_options::RunOptions;
+#[allow(unused_imports)]
 pub use setup::Setup;
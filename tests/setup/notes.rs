@@ -0,0 +1,38 @@
+// See https://github.com/rust-lang/rust/issues/46379
+#![allow(dead_code)]
+
+use anyhow::Result;
+use gnotes::common::crypto::encrypt;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+/// Appends a line to the note, creating it and its directories when needed.
+pub fn write_note(note_parent_dir: &Path, note_file_name: &str, content: &str) -> Result<()> {
+    fs::create_dir_all(note_parent_dir)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(note_parent_dir.join(note_file_name))?;
+
+    writeln!(file, "{}", content)?;
+
+    Ok(())
+}
+
+/// Overwrites the note with the encrypted content.
+pub fn write_encrypted_note(
+    note_parent_dir: &Path,
+    note_file_name: &str,
+    content: &str,
+    passphrase: &str,
+) -> Result<()> {
+    fs::create_dir_all(note_parent_dir)?;
+    fs::write(
+        note_parent_dir.join(note_file_name),
+        encrypt(content, passphrase)?,
+    )?;
+
+    Ok(())
+}
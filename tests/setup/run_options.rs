@@ -1,15 +1,28 @@
+// See https://github.com/rust-lang/rust/issues/46379
+#![allow(dead_code)]
+
+use gnotes::config::{DirConfig, StoreKind};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+#[derive(Clone, Default)]
 pub struct RunOptions {
     pub stdin: Option<String>,
     pub repository: Option<PathBuf>,
+    pub envs: HashMap<String, String>,
+    pub inline_tags: bool,
+    pub store: StoreKind,
+    pub dirs: HashMap<String, DirConfig>,
+    pub hooks_dir: Option<PathBuf>,
+    pub trust_notes_hooks: bool,
+    pub hooks: HashMap<String, String>,
 }
 
-impl Default for RunOptions {
-    fn default() -> Self {
-        RunOptions {
-            stdin: None,
-            repository: None,
+impl RunOptions {
+    pub fn with_passphrase(passphrase: &str) -> Self {
+        Self {
+            envs: HashMap::from([(String::from("GNOTES_PASSPHRASE"), String::from(passphrase))]),
+            ..Default::default()
         }
     }
 }
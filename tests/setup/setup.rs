@@ -8,6 +8,7 @@ use assert_cmd::assert::Assert;
 use gnotes::config::Config;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
 use tempdir::TempDir;
 
 pub struct Setup {
@@ -25,6 +26,36 @@ impl Setup {
 
     pub fn run(&self, args: &[&str], options: Option<RunOptions>) -> Result<Assert> {
         let options = options.unwrap_or_default();
+
+        self.write_config(&options)?;
+
+        let stdin: String = options.stdin.unwrap_or_default();
+
+        Ok(assert_cmd::Command::cargo_bin("gnotes")?
+            .args(args)
+            .env("EDITOR", "vim")
+            .env("GNOTES_HOME_DIR", self.home_dir.path())
+            .envs(options.envs)
+            .write_stdin(stdin)
+            .assert())
+    }
+
+    /// Starts the command without waiting for it, e.g. for `watch`.
+    pub fn spawn(&self, args: &[&str], options: Option<RunOptions>) -> Result<Child> {
+        let options = options.unwrap_or_default();
+
+        self.write_config(&options)?;
+
+        Ok(Command::new(assert_cmd::cargo::cargo_bin("gnotes"))
+            .args(args)
+            .env("GNOTES_HOME_DIR", self.home_dir.path())
+            .envs(options.envs)
+            .stdin(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?)
+    }
+
+    fn write_config(&self, options: &RunOptions) -> Result<()> {
         let ssh_file_path = self.home_dir.path().join("id_rsa");
 
         let config = Config {
@@ -32,24 +63,26 @@ impl Setup {
             auto_save: false,
             repository: options
                 .repository
+                .as_ref()
                 .map(|p| String::from(p.to_string_lossy())),
             ssh_file_path: ssh_file_path.to_path_buf(),
+            inline_tags: options.inline_tags,
+            store: options.store,
+            dirs: options.dirs.clone(),
+            hooks_dir: options.hooks_dir.clone(),
+            trust_notes_hooks: options.trust_notes_hooks,
+            hooks: options.hooks.clone(),
         };
 
         fs::write(&ssh_file_path, "TODO: write a valid id_rsa inside")?;
-        fs::write(
-            self.home_dir.path().join(".gnotes.toml"),
-            toml::to_string(&config)?,
-        )?;
+        // Renamed over the config, as spawned commands may be reading it.
+        let config_path = self.home_dir.path().join(".gnotes.toml");
+        let temp_config_path = config_path.with_extension("toml.tmp");
 
-        let stdin: String = options.stdin.unwrap_or_default();
+        fs::write(&temp_config_path, toml::to_string(&config)?)?;
+        fs::rename(temp_config_path, config_path)?;
 
-        Ok(assert_cmd::Command::cargo_bin("gnotes")?
-            .args(args)
-            .env("EDITOR", "vim")
-            .env("GNOTES_HOME_DIR", self.home_dir.path())
-            .write_stdin(stdin)
-            .assert())
+        Ok(())
     }
 
     pub fn notes_dir_path(&self) -> &Path {
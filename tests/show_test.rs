@@ -1,9 +1,8 @@
 mod setup;
 
 use anyhow::{Context, Result};
-use gnotes::common::notes::write_note;
 use predicates::prelude::*;
-use setup::{Setup, DEFAULT_NOTE_FILE_NAME};
+use setup::{write_encrypted_note, write_note, RunOptions, Setup, DEFAULT_NOTE_FILE_NAME};
 
 #[test]
 fn test_show_note() -> Result<()> {
@@ -55,7 +54,139 @@ fn test_show_note_does_not_exist() -> Result<()> {
             "show failed: file '{}' not found\n",
             note_file_path.to_str().context("note_file_path.to_str()")?
         ))
-        .code(1);
+        .code(4);
+
+    Ok(())
+}
+
+#[test]
+fn test_show_encrypted_note() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello\n",
+        "secret",
+    )?;
+
+    setup
+        .run(
+            &["show", DEFAULT_NOTE_FILE_NAME],
+            Some(RunOptions::with_passphrase("secret")),
+        )?
+        .stdout(predicate::str::contains("notes/chores:\n"))
+        .stdout(predicate::str::contains("hello"))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_show_note_with_links() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "see [[reminders]] and [[custom/standup]]",
+    )?;
+    write_note(&setup.default_note_parent_dir(), "reminders", "hello")?;
+
+    setup
+        .run(&["show", DEFAULT_NOTE_FILE_NAME], None)?
+        .stdout(predicate::str::contains("→ notes/reminders"))
+        .stdout(predicate::str::contains("[[reminders]]").not())
+        .stdout(predicate::str::ends_with(
+            "links:\n  notes/reminders\n  custom/standup (missing)\n",
+        ))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_show_note_highlights_code_blocks() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "intro\n```rust\nfn main() {}\n```\noutro",
+    )?;
+
+    setup
+        .run(&["show", DEFAULT_NOTE_FILE_NAME, "--no-pager"], None)?
+        .stdout(predicate::str::contains("intro"))
+        .stdout(predicate::str::contains("\x1b[38;2;"))
+        .stdout(predicate::str::contains("main"))
+        .stdout(predicate::str::contains("outro"))
+        .stdout(predicate::str::contains("```").not())
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_show_note_section() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "# Meeting\nintro\n## Action items\ncall mom\n## Notes\nsome notes",
+    )?;
+
+    setup
+        .run(
+            &["show", DEFAULT_NOTE_FILE_NAME, "--section", "action items"],
+            None,
+        )?
+        .stdout(predicate::str::contains("Action items"))
+        .stdout(predicate::str::contains("call mom"))
+        .stdout(predicate::str::contains("intro").not())
+        .stdout(predicate::str::contains("some notes").not())
+        .success();
+
+    setup
+        .run(
+            &["show", DEFAULT_NOTE_FILE_NAME, "--section", "Missing"],
+            None,
+        )?
+        .stderr("show failed: section 'Missing' not found\n")
+        .code(6);
+
+    Ok(())
+}
+
+#[test]
+fn test_show_note_partial_name() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(&setup.note_parent_dir("work"), "weekly-report", "hello")?;
+
+    setup
+        .run(&["show", "wkrep"], None)?
+        .stdout(predicate::str::contains("work/weekly-report:\n"))
+        .stdout(predicate::str::contains("hello"))
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_show_note_ambiguous_name() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(&setup.default_note_parent_dir(), "choir", "la la")?;
+    write_note(&setup.default_note_parent_dir(), "chores", "hello")?;
+
+    setup
+        .run(&["show", "cho"], None)?
+        .stderr(
+            "show failed: 'cho' matches several notes. Did you mean:\n  notes/choir\n  notes/chores\n",
+        )
+        .code(3);
 
     Ok(())
 }
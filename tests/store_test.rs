@@ -0,0 +1,159 @@
+mod setup;
+
+use crate::setup::{write_note, GitSetup, RunOptions, Setup, DEFAULT_NOTE_FILE_NAME};
+use anyhow::Result;
+use gnotes::common::tags::{load_tags, update_tags};
+use gnotes::config::StoreKind;
+use maplit::{hashmap, hashset};
+use predicates::prelude::*;
+use std::fs;
+
+fn sqlite_options() -> Option<RunOptions> {
+    Some(RunOptions {
+        store: StoreKind::Sqlite,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_sqlite_store_tags() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    write_note(&setup.note_parent_dir("work"), "plan", "plans")?;
+
+    setup
+        .run(&["tag", DEFAULT_NOTE_FILE_NAME, "home"], sqlite_options())?
+        .success();
+    setup
+        .run(
+            &["tag", "plan", "home/garden", "--dir", "work"],
+            sqlite_options(),
+        )?
+        .success();
+    setup
+        .run(&["search", "home", "--all"], sqlite_options())?
+        .stdout("total 2\nnotes/chores\nwork/plan\n")
+        .success();
+
+    setup
+        .run(&["untag", DEFAULT_NOTE_FILE_NAME, "home"], sqlite_options())?
+        .success();
+    setup
+        .run(&["search", "home", "--all"], sqlite_options())?
+        .stdout("total 1\nwork/plan\n")
+        .success();
+
+    // The tags are only written to the JSON files when exported.
+    assert!(!setup.notes_dir_path().join(".tags").exists());
+
+    setup.run(&["store", "export"], sqlite_options())?.success();
+
+    assert_eq!(
+        load_tags(setup.notes_dir_path())?,
+        hashmap! {
+            String::from("home/garden") => hashset! { String::from("work/plan") },
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_sqlite_store_imports_json() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    update_tags(
+        setup.notes_dir_path(),
+        &hashmap! {
+            String::from("home") => hashset! { String::from("notes/chores") },
+        },
+    )?;
+
+    setup
+        .run(&["search", "home"], sqlite_options())?
+        .stdout("total 1\nnotes/chores\n")
+        .success();
+
+    update_tags(
+        setup.notes_dir_path(),
+        &hashmap! {
+            String::from("work") => hashset! { String::from("notes/chores") },
+        },
+    )?;
+
+    setup.run(&["store", "import"], sqlite_options())?.success();
+    setup
+        .run(&["search", "work"], sqlite_options())?
+        .stdout("total 1\nnotes/chores\n")
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn test_store_requires_sqlite() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["store", "export"], None)?
+        .stderr(predicate::str::starts_with(
+            "store export failed: the tags are already kept in JSON",
+        ))
+        .code(2);
+
+    Ok(())
+}
+
+#[test]
+fn test_save_exports_sqlite_tags() -> Result<()> {
+    let setup = Setup::new()?;
+    let git_setup = GitSetup::new(None)?;
+
+    GitSetup::clone_to(git_setup.bare_dir.path(), setup.notes_dir_path())?;
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    let run_options = || {
+        Some(RunOptions {
+            repository: Some(git_setup.bare_dir.path().to_path_buf()),
+            store: StoreKind::Sqlite,
+            ..Default::default()
+        })
+    };
+
+    setup
+        .run(&["tag", DEFAULT_NOTE_FILE_NAME, "home"], run_options())?
+        .success();
+    setup.run(&["save"], run_options())?.success();
+
+    git_setup.update_clone()?;
+
+    assert_eq!(
+        load_tags(git_setup.clone_dir.path())?,
+        hashmap! {
+            String::from("home") => hashset! { String::from("notes/chores") },
+        }
+    );
+    assert!(!git_setup.clone_dir.path().join(".gnotes.db").exists());
+    assert!(fs::read_dir(git_setup.clone_dir.path())?
+        .filter_map(|entry| entry.ok())
+        .all(|entry| !entry
+            .file_name()
+            .to_string_lossy()
+            .starts_with(".gnotes.db")));
+
+    Ok(())
+}
@@ -1,10 +1,9 @@
 mod setup;
 
 use anyhow::{Context, Result};
-use gnotes::common::notes::write_note;
 use gnotes::common::tags::load_tags;
 use maplit::{hashmap, hashset};
-use setup::{Setup, DEFAULT_NOTE_FILE_NAME};
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
 
 #[test]
 fn test_tag_note() -> Result<()> {
@@ -65,7 +64,7 @@ fn test_tag_note_does_not_exist() -> Result<()> {
             "tag failed: file '{}' not found\n",
             note_file_path.to_str().context("note_file_path.to_str()")?
         ))
-        .code(1);
+        .code(4);
 
     assert!(!tags_file_path.exists());
 
@@ -160,3 +159,83 @@ fn test_tag_note_tag_already_exists_for_this_note() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_tag_note_in_other_dir() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.note_parent_dir("custom"),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    let expected = hashmap! {
+      String::from("tag1") => hashset! { String::from("custom/chores") },
+    };
+
+    setup
+        .run(&["tag", DEFAULT_NOTE_FILE_NAME, "tag1"], None)?
+        .success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_tag_note_truncated_tags_file() -> Result<()> {
+    let setup = Setup::new()?;
+    let tags_file_path = setup.notes_dir_path().join(".tags");
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+    std::fs::write(&tags_file_path, r#"{"old":["notes/chores"],"lost":["no"#)?;
+
+    let expected = hashmap! {
+      String::from("old") => hashset! { String::from("notes/chores") },
+      String::from("new") => hashset! { String::from("notes/chores") },
+    };
+
+    setup
+        .run(&["tag", DEFAULT_NOTE_FILE_NAME, "new"], None)?
+        .success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+    assert!(setup.notes_dir_path().join(".tags.corrupt").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_tag_note_concurrently() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "hello",
+    )?;
+
+    let tags: Vec<String> = (0..8).map(|index| format!("tag{}", index)).collect();
+    let children = tags
+        .iter()
+        .map(|tag| setup.spawn(&["tag", DEFAULT_NOTE_FILE_NAME, tag], None))
+        .collect::<Result<Vec<_>>>()?;
+
+    for child in children {
+        assert!(child.wait_with_output()?.status.success());
+    }
+
+    assert_eq!(
+        load_tags(setup.notes_dir_path())?,
+        tags.into_iter()
+            .map(|tag| (tag, hashset! { String::from("notes/chores") }))
+            .collect()
+    );
+
+    Ok(())
+}
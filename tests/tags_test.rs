@@ -0,0 +1,286 @@
+mod setup;
+
+use anyhow::Result;
+use gnotes::common::tags::{load_tags, update_tags};
+use maplit::{hashmap, hashset};
+use predicates::prelude::*;
+use setup::Setup;
+
+fn write_tags(setup: &Setup) -> Result<()> {
+    let tags = hashmap! {
+      String::from("tag1") => hashset! { String::from("notes/chores"), String::from("custom/chores") },
+      String::from("tag2") => hashset! { String::from("notes/chores") },
+      String::from("tag3") => hashset! { String::from("custom/reminders") },
+    };
+
+    update_tags(setup.notes_dir_path(), &tags)
+}
+
+#[test]
+fn test_tags_list() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    setup
+        .run(&["tags"], None)?
+        .success()
+        .stdout(predicate::str::contains("total 3\n"))
+        .stdout(predicate::str::is_match("tag1 +2\ntag2 +1\ntag3 +1\n")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_list_custom_dir() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    setup
+        .run(&["tags", "--dir", "custom"], None)?
+        .success()
+        .stdout(predicate::str::contains("total 2\n"))
+        .stdout(predicate::str::is_match("tag1 +1\ntag3 +1\n")?);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_list_empty() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["tags"], None)?
+        .success()
+        .stdout(predicate::eq("total 0\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_rename() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    let expected = hashmap! {
+      String::from("renamed") => hashset! { String::from("notes/chores"), String::from("custom/chores") },
+      String::from("tag2") => hashset! { String::from("notes/chores") },
+      String::from("tag3") => hashset! { String::from("custom/reminders") },
+    };
+
+    setup
+        .run(&["tags", "rename", "tag1", "renamed"], None)?
+        .success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_rename_to_existing_tag() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    setup
+        .run(&["tags", "rename", "tag1", "tag2"], None)?
+        .stderr(predicate::eq(
+            "tags rename failed: tag 'tag2' already exists. Use 'tags merge' instead\n",
+        ))
+        .code(8);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_rename_does_not_exist() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    setup
+        .run(&["tags", "rename", "missing", "renamed"], None)?
+        .stderr(predicate::eq(
+            "tags rename failed: tag 'missing' not found\n",
+        ))
+        .code(7);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_merge() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    let expected = hashmap! {
+      String::from("merged") => hashset! {
+        String::from("notes/chores"),
+        String::from("custom/chores"),
+        String::from("custom/reminders"),
+      },
+      String::from("tag2") => hashset! { String::from("notes/chores") },
+    };
+
+    setup
+        .run(&["tags", "merge", "tag1", "tag3", "--into", "merged"], None)?
+        .success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_merge_into_existing_tag() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    let expected = hashmap! {
+      String::from("tag1") => hashset! {
+        String::from("notes/chores"),
+        String::from("custom/chores"),
+        String::from("custom/reminders"),
+      },
+      String::from("tag2") => hashset! { String::from("notes/chores") },
+    };
+
+    setup
+        .run(&["tags", "merge", "tag1", "tag3", "--into", "tag1"], None)?
+        .success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_delete() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    let expected = hashmap! {
+      String::from("tag2") => hashset! { String::from("notes/chores") },
+      String::from("tag3") => hashset! { String::from("custom/reminders") },
+    };
+
+    setup.run(&["tags", "delete", "tag1"], None)?.success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_delete_does_not_exist() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    setup
+        .run(&["tags", "delete", "missing"], None)?
+        .stderr(predicate::eq(
+            "tags delete failed: tag 'missing' not found\n",
+        ))
+        .code(7);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_delete_with_dir() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_tags(&setup)?;
+
+    setup
+        .run(&["tags", "--dir", "custom", "delete", "tag1"], None)?
+        .stderr(predicate::eq(
+            "tags delete failed: --dir can only be used to list the tags\n",
+        ))
+        .code(2);
+
+    assert!(load_tags(setup.notes_dir_path())?.contains_key("tag1"));
+
+    Ok(())
+}
+
+fn write_nested_tags(setup: &Setup) -> Result<()> {
+    let tags = hashmap! {
+      String::from("project") => hashset! { String::from("notes/a") },
+      String::from("project/alpha") => hashset! { String::from("notes/b") },
+      String::from("project/alpha/backend") => hashset! { String::from("notes/b"), String::from("notes/c") },
+      String::from("project-x") => hashset! { String::from("notes/d") },
+      String::from("work/oncall") => hashset! { String::from("notes/e") },
+    };
+
+    update_tags(setup.notes_dir_path(), &tags)
+}
+
+#[test]
+fn test_tags_tree() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_nested_tags(&setup)?;
+
+    setup
+        .run(&["tags", "--tree"], None)?
+        .success()
+        .stdout(predicate::eq(
+            "total 5\nproject (3)\n  alpha (2)\n    backend (2)\nproject-x (1)\nwork (1)\n  oncall (1)\n",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_rename_parent_renames_children() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_nested_tags(&setup)?;
+
+    let expected = hashmap! {
+      String::from("project") => hashset! { String::from("notes/a") },
+      String::from("project/beta") => hashset! { String::from("notes/b") },
+      String::from("project/beta/backend") => hashset! { String::from("notes/b"), String::from("notes/c") },
+      String::from("project-x") => hashset! { String::from("notes/d") },
+      String::from("work/oncall") => hashset! { String::from("notes/e") },
+    };
+
+    setup
+        .run(&["tags", "rename", "project/alpha", "project/beta"], None)?
+        .success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
+
+#[test]
+fn test_tags_rename_implicit_parent() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_nested_tags(&setup)?;
+
+    let expected = hashmap! {
+      String::from("project") => hashset! { String::from("notes/a") },
+      String::from("project/alpha") => hashset! { String::from("notes/b") },
+      String::from("project/alpha/backend") => hashset! { String::from("notes/b"), String::from("notes/c") },
+      String::from("project-x") => hashset! { String::from("notes/d") },
+      String::from("team/oncall") => hashset! { String::from("notes/e") },
+    };
+
+    setup
+        .run(&["tags", "rename", "work", "team"], None)?
+        .success();
+
+    assert_eq!(load_tags(setup.notes_dir_path())?, expected);
+
+    Ok(())
+}
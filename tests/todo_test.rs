@@ -0,0 +1,174 @@
+mod setup;
+
+use anyhow::Result;
+use gnotes::common::crypto::decrypt;
+use gnotes::common::tags::update_tags;
+use predicates::prelude::*;
+use serde_json::json;
+use setup::{write_encrypted_note, write_note, RunOptions, Setup, DEFAULT_NOTE_FILE_NAME};
+use std::fs;
+
+fn write_notes(setup: &Setup) -> Result<()> {
+    write_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "# Chores\n- [ ] buy milk\n- [x] call mom",
+    )?;
+    write_note(&setup.note_parent_dir("work"), "plan", "1. [ ] write docs")?;
+
+    update_tags(setup.notes_dir_path(), &json!({"work": ["work/plan"]}))
+}
+
+#[test]
+fn test_todo_list() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup.run(&["todo"], None)?.success().stdout(predicate::eq(
+        "total 3
+notes/chores:2 [ ] buy milk
+notes/chores:3 [x] call mom
+work/plan:1 [ ] write docs
+",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_todo_list_filters() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["todo", "--open", "--dir", "notes"], None)?
+        .success()
+        .stdout(predicate::eq("total 1\nnotes/chores:2 [ ] buy milk\n"));
+
+    setup
+        .run(&["todo", "--done"], None)?
+        .success()
+        .stdout(predicate::eq("total 1\nnotes/chores:3 [x] call mom\n"));
+
+    setup
+        .run(&["todo", "--tag", "work"], None)?
+        .success()
+        .stdout(predicate::eq("total 1\nwork/plan:1 [ ] write docs\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_todo_done() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["todo", "done", "notes/chores:2"], None)?
+        .success()
+        .stdout(predicate::eq("notes/chores:2 [x] buy milk\n"));
+
+    setup
+        .run(&["todo", "done", "notes/chores:3"], None)?
+        .success()
+        .stdout(predicate::eq("notes/chores:3 [ ] call mom\n"));
+
+    assert_eq!(
+        fs::read_to_string(setup.default_note_path())?,
+        "# Chores\n- [x] buy milk\n- [ ] call mom\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_todo_done_no_item() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(&["todo", "done", "notes/chores:1"], None)?
+        .stderr(predicate::eq(
+            "todo done failed: no item found at 'notes/chores:1'\n",
+        ))
+        .code(9);
+
+    setup
+        .run(&["todo", "done", "notes/chores"], None)?
+        .stderr(predicate::str::starts_with(
+            "todo done failed: id must be in the form of",
+        ))
+        .code(2);
+
+    setup
+        .run(&["todo", "done", "notes/missing:1"], None)?
+        .stderr(predicate::str::starts_with("todo done failed: file"))
+        .code(4);
+
+    Ok(())
+}
+
+#[test]
+fn test_todo_add() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_notes(&setup)?;
+
+    setup
+        .run(
+            &["todo", "add", DEFAULT_NOTE_FILE_NAME, "take out the trash"],
+            None,
+        )?
+        .success()
+        .stdout(predicate::eq("notes/chores:4 [ ] take out the trash\n"));
+
+    assert_eq!(
+        fs::read_to_string(setup.default_note_path())?,
+        "# Chores\n- [ ] buy milk\n- [x] call mom\n- [ ] take out the trash\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_todo_add_encrypted() -> Result<()> {
+    let setup = Setup::new()?;
+
+    write_encrypted_note(
+        &setup.default_note_parent_dir(),
+        DEFAULT_NOTE_FILE_NAME,
+        "- [ ] buy milk\n",
+        "secret",
+    )?;
+
+    setup
+        .run(
+            &["todo", "add", DEFAULT_NOTE_FILE_NAME, "call mom"],
+            Some(RunOptions::with_passphrase("secret")),
+        )?
+        .success();
+
+    setup
+        .run(
+            &["todo", "done", "notes/chores:1"],
+            Some(RunOptions::with_passphrase("secret")),
+        )?
+        .success();
+
+    assert_eq!(
+        decrypt(&fs::read_to_string(setup.default_note_path())?, "secret")?,
+        "- [x] buy milk\n- [ ] call mom\n"
+    );
+
+    // Encrypted notes are not listed.
+    setup
+        .run(&["todo"], None)?
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    Ok(())
+}
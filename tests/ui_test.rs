@@ -0,0 +1,16 @@
+mod setup;
+
+use anyhow::Result;
+use setup::Setup;
+
+#[test]
+fn test_ui_requires_terminal() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["ui"], None)?
+        .stderr("ui failed: gnotes ui must be run in a terminal\n")
+        .code(2);
+
+    Ok(())
+}
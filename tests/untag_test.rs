@@ -1,11 +1,10 @@
 mod setup;
 
 use anyhow::{Context, Result};
-use gnotes::common::notes::write_note;
 use gnotes::common::tags::{load_tags, update_tags};
 use maplit::{hashmap, hashset};
 use serde_json::json;
-use setup::{Setup, DEFAULT_NOTE_FILE_NAME};
+use setup::{write_note, Setup, DEFAULT_NOTE_FILE_NAME};
 
 #[test]
 fn test_untag_note() -> Result<()> {
@@ -98,7 +97,7 @@ fn test_untag_note_does_not_exist() -> Result<()> {
             "untag failed: file '{}' not found\n",
             note_file_path.to_str().context("note_file_path.to_str()")?
         ))
-        .code(1);
+        .code(4);
 
     Ok(())
 }
@@ -0,0 +1,132 @@
+mod setup;
+
+use anyhow::Result;
+use gnotes::common::tags::{load_tags, update_tags};
+use maplit::{hashmap, hashset};
+use predicates::prelude::*;
+use setup::{GitSetup, RunOptions, Setup};
+use std::fs;
+use std::process::{Child, Command};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Gives the watcher time to start, or the changes to be seen.
+const WAIT: Duration = Duration::from_secs(2);
+
+fn run_options(git_setup: &GitSetup) -> Option<RunOptions> {
+    Some(RunOptions {
+        stdin: None,
+        repository: Some(git_setup.bare_dir.path().to_path_buf()),
+        ..Default::default()
+    })
+}
+
+fn stop(watch: Child) -> Result<()> {
+    Command::new("kill")
+        .args(["-INT", &watch.id().to_string()])
+        .status()?;
+
+    let output = watch.wait_with_output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success(), "{}", stderr);
+    assert!(stderr.contains("stopping"), "{}", stderr);
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_fails_without_repository() -> Result<()> {
+    let setup = Setup::new()?;
+
+    setup
+        .run(&["watch"], None)?
+        .stderr(predicate::eq(
+            "watch failed: no repository is configured. Please specify a repository in the config file.\n",
+        ))
+        .code(11);
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_saves_changes_when_stopped() -> Result<()> {
+    let setup = Setup::new()?;
+    let git_setup = GitSetup::new(None)?;
+
+    GitSetup::clone_to(git_setup.bare_dir.path(), setup.notes_dir_path())?;
+
+    fs::write(setup.note_parent_dir("notes").join("plan"), "plan")?;
+    fs::write(setup.note_parent_dir("notes").join("old"), "old")?;
+    fs::create_dir(setup.note_parent_dir("work"))?;
+    update_tags(
+        setup.notes_dir_path(),
+        &hashmap! {
+          String::from("work") => hashset! { String::from("notes/plan") },
+          String::from("old") => hashset! { String::from("notes/old") },
+        },
+    )?;
+
+    let watch = setup.spawn(&["watch", "--debounce", "60"], run_options(&git_setup))?;
+    thread::sleep(WAIT);
+
+    fs::write(setup.default_note_path(), "changed outside gnotes\n")?;
+    fs::rename(
+        setup.note_parent_dir("notes").join("plan"),
+        setup.note_parent_dir("work").join("plan"),
+    )?;
+    fs::remove_file(setup.note_parent_dir("notes").join("old"))?;
+    thread::sleep(WAIT);
+
+    stop(watch)?;
+
+    git_setup.update_clone()?;
+
+    assert_eq!(
+        fs::read_to_string(GitSetup::build_note_path(git_setup.clone_dir.path()))?,
+        "changed outside gnotes\n"
+    );
+    assert!(!git_setup.clone_dir.path().join("notes/old").exists());
+    assert_eq!(
+        load_tags(git_setup.clone_dir.path())?,
+        hashmap! {
+          String::from("work") => hashset! { String::from("work/plan") },
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_watch_commits_and_pushes_after_debounce() -> Result<()> {
+    let setup = Setup::new()?;
+    let git_setup = GitSetup::new(None)?;
+
+    GitSetup::clone_to(git_setup.bare_dir.path(), setup.notes_dir_path())?;
+
+    let watch = setup.spawn(
+        &["watch", "--debounce", "1", "--idle", "1"],
+        run_options(&git_setup),
+    )?;
+    thread::sleep(WAIT);
+
+    fs::write(setup.default_note_path(), "changed outside gnotes\n")?;
+
+    let note_file_path = GitSetup::build_note_path(git_setup.clone_dir.path());
+    let start = Instant::now();
+    while fs::read_to_string(&note_file_path)? != "changed outside gnotes\n"
+        && start.elapsed() < Duration::from_secs(20)
+    {
+        thread::sleep(Duration::from_millis(500));
+        git_setup.update_clone()?;
+    }
+
+    stop(watch)?;
+
+    assert_eq!(
+        fs::read_to_string(&note_file_path)?,
+        "changed outside gnotes\n"
+    );
+
+    Ok(())
+}